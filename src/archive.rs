@@ -0,0 +1,202 @@
+use std::{collections::{BTreeMap, HashSet}, fs::{self, File}, io::{self, BufReader, Read, Seek}, path::{Component, Path, PathBuf}};
+
+use flate2::read::MultiGzDecoder;
+
+use crate::{directory, my_errors::MyError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGzip,
+    TarZstd,
+    Zip,
+}
+
+impl ArchiveFormat {
+    // Longest suffixes first, so `.tar.gz` isn't taken for a plain `.gz`
+    const SUFFIXES: [(&'static str, ArchiveFormat); 6] = [
+        (".tar.gz", ArchiveFormat::TarGzip),
+        (".tar.zst", ArchiveFormat::TarZstd),
+        (".tgz", ArchiveFormat::TarGzip),
+        (".tzst", ArchiveFormat::TarZstd),
+        (".tar", ArchiveFormat::Tar),
+        (".zip", ArchiveFormat::Zip),
+    ];
+
+    /// The archive format of a file, going by its name.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+        Self::SUFFIXES
+            .iter()
+            .find(|(suffix, _)| name.len() > suffix.len() && name.ends_with(suffix))
+            .map(|(_, format)| *format)
+    }
+
+    /// The file name without the archive suffix, e.g. `project` for `project.tar.gz`.
+    fn stem(path: &Path) -> String {
+        let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+        let lowercase = name.to_lowercase();
+
+        match Self::SUFFIXES.iter().find(|(suffix, _)| lowercase.ends_with(suffix)) {
+            Some((suffix, _)) => name[..name.len() - suffix.len()].to_string(),
+            None => name,
+        }
+    }
+}
+
+/// What an archive holds, as its preview shows it.
+pub struct ArchiveContents {
+    /// Names at the top of the archive, directories first and ending in `/`
+    pub top_level: Vec<String>,
+    pub files: u64,
+    /// The size of every file once extracted
+    pub size: u64,
+}
+
+/// Lists an archive without extracting it. A zip's central directory is read directly,
+/// while a tar is streamed through, decompressing it but skipping over the contents.
+pub fn contents(path: &Path, format: ArchiveFormat) -> Result<ArchiveContents, MyError> {
+    let file = File::open(path).map_err(|e| MyError::io(e, path))?;
+    let invalid = |e: &dyn std::fmt::Display| MyError::FileError(format!("Couldn't read {}: {}", path.display(), e));
+
+    // Top-level name to whether it's a directory
+    let mut top_level = BTreeMap::new();
+    let (mut files, mut size) = (0, 0);
+    let mut add = |name: &Path, is_dir: bool, bytes: u64| {
+        let mut components = name.components().filter(|component| matches!(component, Component::Normal(_)));
+        let Some(first) = components.next() else { return };
+        let nested = components.next().is_some();
+
+        *top_level.entry(first.as_os_str().to_string_lossy().to_string()).or_insert(false) |= is_dir || nested;
+
+        if !is_dir {
+            files += 1;
+            size += bytes;
+        }
+    };
+
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| invalid(&e))?;
+
+            for index in 0..archive.len() {
+                let entry = archive.by_index_raw(index).map_err(|e| invalid(&e))?;
+                add(Path::new(entry.name()), entry.is_dir(), entry.size());
+            }
+
+            return Ok(summarize(top_level, files, size));
+        },
+        ArchiveFormat::Tar => Box::new(BufReader::new(file)),
+        ArchiveFormat::TarGzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        ArchiveFormat::TarZstd => Box::new(zstd::Decoder::new(file).map_err(|e| invalid(&e))?),
+    };
+
+    let mut archive = tar::Archive::new(reader);
+
+    for entry in archive.entries().map_err(|e| invalid(&e))? {
+        let entry = entry.map_err(|e| invalid(&e))?;
+        let name = entry.path().map_err(|e| invalid(&e))?.to_path_buf();
+
+        add(&name, entry.header().entry_type().is_dir(), entry.size());
+    }
+
+    Ok(summarize(top_level, files, size))
+}
+
+fn summarize(top_level: BTreeMap<String, bool>, files: u64, size: u64) -> ArchiveContents {
+    let (directories, others): (Vec<_>, Vec<_>) = top_level.into_iter().partition(|(_, is_dir)| *is_dir);
+
+    let top_level = directories
+        .into_iter()
+        .map(|(name, _)| name + "/")
+        .chain(others.into_iter().map(|(name, _)| name))
+        .collect();
+
+    ArchiveContents { top_level, files, size }
+}
+
+/// Extracts an archive into a new directory next to it, named after the archive, and
+/// returns the directory. Entries that would land outside of it are skipped.
+pub fn extract(path: &Path) -> Result<PathBuf, MyError> {
+    let Some(format) = ArchiveFormat::for_path(path) else {
+        return Err(MyError::FileError("Not a tar or zip archive".to_string()));
+    };
+
+    let destination = directory::unique_path(&path.with_file_name(ArchiveFormat::stem(path)), &HashSet::new());
+    fs::create_dir(&destination).map_err(|e| MyError::io(e, &destination))?;
+
+    let result = unpack(path, format, &destination);
+
+    // Don't leave a half-extracted directory behind
+    if result.is_err() {
+        let _ = fs::remove_dir_all(&destination);
+    }
+
+    result.map(|_| destination)
+}
+
+fn unpack(path: &Path, format: ArchiveFormat, destination: &Path) -> Result<(), MyError> {
+    let file = File::open(path).map_err(|e| MyError::io(e, path))?;
+    let invalid = |e: &dyn std::fmt::Display| MyError::FileError(format!("Couldn't extract {}: {}", path.display(), e));
+
+    let reader: Box<dyn Read> = match format {
+        ArchiveFormat::Zip => {
+            let mut archive = zip::ZipArchive::new(BufReader::new(file)).map_err(|e| invalid(&e))?;
+            return unpack_zip(&mut archive, destination).map_err(|e| invalid(&e));
+        },
+        ArchiveFormat::Tar => Box::new(BufReader::new(file)),
+        ArchiveFormat::TarGzip => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+        ArchiveFormat::TarZstd => Box::new(zstd::Decoder::new(file).map_err(|e| invalid(&e))?),
+    };
+
+    tar::Archive::new(reader).unpack(destination).map_err(|e| invalid(&e))
+}
+
+// Skips what would land outside of `destination` the way tar does, where the zip crate's own
+// extraction gives up on the whole archive. Links are made last, so nothing is written through one
+fn unpack_zip<R: Read + Seek>(archive: &mut zip::ZipArchive<R>, destination: &Path) -> zip::result::ZipResult<()> {
+    let mut links = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+        let Some(name) = entry.enclosed_name() else { continue };
+        let target = destination.join(name);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&target)?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        if entry.is_symlink() {
+            let mut link = String::new();
+            entry.read_to_string(&mut link)?;
+            links.push((target, link));
+            continue;
+        }
+
+        io::copy(&mut entry, &mut File::create(&target)?)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = entry.unix_mode() {
+            use std::os::unix::fs::PermissionsExt;
+
+            fs::set_permissions(&target, fs::Permissions::from_mode(mode & 0o7777))?;
+        }
+    }
+
+    for (target, link) in links {
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(link, target)?;
+
+        // Where links can't be made, the file holds what it pointed to, like zip does
+        #[cfg(not(unix))]
+        fs::write(target, link)?;
+    }
+
+    Ok(())
+}
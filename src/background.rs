@@ -0,0 +1,206 @@
+use std::{
+    collections::VecDeque,
+    path::PathBuf,
+    sync::{atomic::{AtomicBool, AtomicU64, Ordering}, mpsc::{self, Receiver, TryRecvError}, Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::my_errors::MyError;
+
+// Speed is averaged over this much recent progress, so it follows slow and fast stretches
+const SPEED_WINDOW: Duration = Duration::from_secs(5);
+// Samples closer together than this are skipped, which bounds how many the window holds
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+// The time left is only guessed once a task has run this long, since the first moments say little
+const ESTIMATE_AFTER: Duration = Duration::from_secs(2);
+
+/// How far along a task is, updated by the work itself as it goes.
+/// Work that can't tell leaves the total at 0.
+pub struct Progress {
+    done: AtomicU64,
+    total: AtomicU64,
+    started: Instant,
+    /// Counted in entries rather than bytes
+    items: AtomicBool,
+    /// Whether the work checks `is_cancelled` as it goes
+    cancellable: AtomicBool,
+    cancelled: AtomicBool,
+    /// When `done` had which value, taken as the speed is read
+    samples: Mutex<VecDeque<(Instant, u64)>>,
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        let started = Instant::now();
+
+        Self {
+            done: AtomicU64::new(0),
+            total: AtomicU64::new(0),
+            started,
+            items: AtomicBool::new(false),
+            cancellable: AtomicBool::new(false),
+            cancelled: AtomicBool::new(false),
+            samples: Mutex::new(VecDeque::from([(started, 0)])),
+        }
+    }
+}
+
+impl Progress {
+    /// Makes `add` and the total count entries instead of bytes.
+    pub fn count_items(&self) {
+        self.items.store(true, Ordering::Relaxed);
+    }
+
+    pub fn counts_items(&self) -> bool {
+        self.items.load(Ordering::Relaxed)
+    }
+
+    /// Lets the task be stopped, for work that checks `is_cancelled` between steps.
+    pub fn allow_cancel(&self) {
+        self.cancellable.store(true, Ordering::Relaxed);
+    }
+
+    /// Asks the work to stop, returning false if it can't be.
+    pub fn cancel(&self) -> bool {
+        let cancellable = self.cancellable.load(Ordering::Relaxed);
+
+        if cancellable {
+            self.cancelled.store(true, Ordering::Relaxed);
+        }
+
+        cancellable
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn add(&self, bytes: u64) {
+        self.done.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub fn set_total(&self, bytes: u64) {
+        self.total.store(bytes, Ordering::Relaxed);
+    }
+
+    pub fn done(&self) -> u64 {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total.load(Ordering::Relaxed)
+    }
+
+    /// Percentage done, if the total is known.
+    pub fn percent(&self) -> Option<u64> {
+        match self.total() {
+            0 => None,
+            total => Some((self.done() * 100 / total).min(100)),
+        }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Bytes (or entries) per second over the last few seconds, or since the start
+    /// for tasks younger than that.
+    pub fn speed(&self) -> u64 {
+        let now = Instant::now();
+        let done = self.done();
+        let Ok(mut samples) = self.samples.lock() else { return 0 };
+
+        if samples.back().is_none_or(|(at, _)| now.duration_since(*at) >= SAMPLE_INTERVAL) {
+            samples.push_back((now, done));
+        }
+
+        // The oldest sample kept is the last one from before the window, so it always spans one
+        while samples.get(1).is_some_and(|(at, _)| now.duration_since(*at) > SPEED_WINDOW) {
+            samples.pop_front();
+        }
+
+        let Some(&(since, from)) = samples.front() else { return 0 };
+        let seconds = now.duration_since(since).as_secs_f64();
+
+        match seconds > 0.0 {
+            true => (done.saturating_sub(from) as f64 / seconds) as u64,
+            false => 0,
+        }
+    }
+
+    /// How long the rest should take at the current speed, once the total is known.
+    pub fn remaining(&self) -> Option<Duration> {
+        let (done, total, speed) = (self.done(), self.total(), self.speed());
+
+        (total > done && speed > 0 && self.elapsed() >= ESTIMATE_AFTER).then(|| Duration::from_secs((total - done).div_ceil(speed)))
+    }
+}
+
+/// A file operation running on its own thread so large files don't freeze the UI.
+/// By default the work returns the path it produced.
+pub struct BackgroundTask<T = PathBuf> {
+    pub operation: String,
+    pub source: PathBuf,
+    pub progress: Arc<Progress>,
+    receiver: Receiver<Result<T, MyError>>,
+}
+
+impl<T: Send + 'static> BackgroundTask<T> {
+    pub fn spawn<F>(operation: &str, source: PathBuf, work: F) -> Self
+    where
+        F: FnOnce() -> Result<T, MyError> + Send + 'static,
+    {
+        Self::spawn_with_progress(operation, source, |_| work())
+    }
+
+    /// Like `spawn`, but the work reports its progress as it goes.
+    pub fn spawn_with_progress<F>(operation: &str, source: PathBuf, work: F) -> Self
+    where
+        F: FnOnce(&Progress) -> Result<T, MyError> + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let progress = Arc::new(Progress::default());
+        let shared = Arc::clone(&progress);
+
+        thread::spawn(move || {
+            let _ = sender.send(work(&shared));
+        });
+
+        Self {
+            operation: operation.to_string(),
+            source,
+            progress,
+            receiver,
+        }
+    }
+
+    /// The outcome of the work, once it has finished.
+    pub fn try_result(&self) -> Option<Result<T, MyError>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(MyError::FileError(format!("{} stopped unexpectedly", self.operation)))),
+        }
+    }
+
+    /// A record of the task once its result has been taken.
+    pub fn finished(&self, error: Option<String>) -> FinishedTask {
+        FinishedTask {
+            operation: self.operation.clone(),
+            source: self.source.clone(),
+            bytes: if self.progress.counts_items() { 0 } else { self.progress.done() },
+            elapsed: self.progress.elapsed(),
+            error,
+        }
+    }
+}
+
+/// A task that's done, kept around for the task panel.
+pub struct FinishedTask {
+    pub operation: String,
+    pub source: PathBuf,
+    pub bytes: u64,
+    pub elapsed: Duration,
+    pub error: Option<String>,
+}
@@ -0,0 +1,730 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    fs::{self, File, FileTimes, Metadata},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::{atomic::{AtomicBool, AtomicUsize, Ordering}, Mutex},
+    thread,
+    time::UNIX_EPOCH,
+};
+
+use serde::Deserialize;
+use walkdir::WalkDir;
+
+use crate::{background::Progress, directory::{self, FileTypeEnum}, my_errors::MyError};
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+// Threads copying the files of one tree at the same time. Copying waits on the disk or
+// network far more than on the CPU, so this isn't tied to the number of cores
+const COPY_THREADS: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BatchKind {
+    Copy,
+    Move,
+    Delete,
+}
+
+impl fmt::Display for BatchKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Why a step is skipped when the plan runs. Nothing is ever overwritten.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Conflict {
+    SourceMissing,
+    SameLocation,
+    DestinationExists,
+    IntoItself,
+    Duplicate,
+    /// The source has no name to copy or move it under, like `/`
+    NoName,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            Conflict::SourceMissing => "source no longer exists",
+            Conflict::SameLocation => "already there",
+            Conflict::DestinationExists => "destination exists",
+            Conflict::IntoItself => "destination is inside the source",
+            Conflict::Duplicate => "another source has the same name",
+            Conflict::NoName => "source has no name to keep",
+        };
+
+        write!(f, "{}", reason)
+    }
+}
+
+/// What copying does with symlinks. Moves always keep them as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymlinkPolicy {
+    /// Copy the link itself
+    #[default]
+    Copy,
+    /// Copy what the link points to
+    Follow,
+    /// Make the choice in the preview whenever the copy contains links
+    Ask,
+}
+
+impl SymlinkPolicy {
+    /// The policy picked next in the preview, where asking again makes no sense.
+    pub fn next(&self) -> SymlinkPolicy {
+        match self {
+            SymlinkPolicy::Copy => SymlinkPolicy::Follow,
+            SymlinkPolicy::Follow | SymlinkPolicy::Ask => SymlinkPolicy::Copy,
+        }
+    }
+}
+
+impl fmt::Display for SymlinkPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// Whether copying may bring existing destinations up to date, merging into existing
+/// directories and replacing the files that differ.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePolicy {
+    /// Existing destinations are conflicts and nothing is overwritten
+    #[default]
+    Never,
+    /// Replace files unless their size and modification time are the same
+    Changed,
+    /// Replace files only with newer ones
+    Newer,
+}
+
+impl UpdatePolicy {
+    pub fn next(&self) -> UpdatePolicy {
+        match self {
+            UpdatePolicy::Never => UpdatePolicy::Changed,
+            UpdatePolicy::Changed => UpdatePolicy::Newer,
+            UpdatePolicy::Newer => UpdatePolicy::Never,
+        }
+    }
+
+    /// Whether the existing file at the destination gets replaced by the source.
+    fn replaces(&self, source: &Metadata, existing: &Metadata) -> bool {
+        // Whole seconds, as not every file system keeps more
+        let modified = |metadata: &Metadata| metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|since| since.as_secs());
+
+        match self {
+            UpdatePolicy::Never => false,
+            UpdatePolicy::Changed => source.len() != existing.len() || modified(source) != modified(existing),
+            UpdatePolicy::Newer => modified(source) > modified(existing),
+        }
+    }
+}
+
+impl fmt::Display for UpdatePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+/// How copies are made. Defaults come from the `[copy]` section of the config
+/// and can be changed for each operation in its preview.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(default)]
+pub struct CopyOptions {
+    /// Name collisions get a `name (1).ext` style destination instead of being skipped
+    pub keep_both: bool,
+    /// Keep permissions, timestamps and, where permitted, ownership
+    pub preserve: bool,
+    pub symlinks: SymlinkPolicy,
+    pub update: UpdatePolicy,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self {
+            keep_both: false,
+            preserve: true,
+            symlinks: SymlinkPolicy::Copy,
+            update: UpdatePolicy::Never,
+        }
+    }
+}
+
+pub struct BatchStep {
+    pub source: PathBuf,
+    pub destination: Option<PathBuf>,
+    pub conflict: Option<Conflict>,
+    /// The destination exists and is brought up to date instead
+    pub updates: bool,
+}
+
+/// Every concrete source → destination (or deletion) of a bulk operation,
+/// worked out up front so it can be reviewed before anything is touched.
+pub struct BatchPlan {
+    pub kind: BatchKind,
+    pub steps: Vec<BatchStep>,
+    pub options: CopyOptions,
+    /// Symlinks among and below the sources, only counted when copying asks what to do with them
+    pub symlinks: usize,
+    target_dir: Option<PathBuf>,
+}
+
+impl BatchPlan {
+    /// Plans the operation; copies and moves go into `destination`, which must be a directory.
+    pub fn new(kind: BatchKind, sources: Vec<PathBuf>, destination: Option<&Path>, options: CopyOptions) -> Result<Self, MyError> {
+        let target_dir = match (kind, destination) {
+            (BatchKind::Delete, _) => None,
+            (_, Some(dir)) if dir.is_dir() => Some(dir),
+            (_, Some(dir)) => return Err(MyError::FileError(format!("{} is not a directory", dir.display()))),
+            (_, None) => return Err(MyError::FileError("No destination".to_string())),
+        };
+
+        let mut seen = HashSet::new();
+        let update = kind == BatchKind::Copy && options.update != UpdatePolicy::Never;
+
+        let steps: Vec<BatchStep> = sources
+            .into_iter()
+            .map(|source| {
+                let mut updates = false;
+                let mut destination = target_dir.and_then(|dir| source.file_name().map(|name| dir.join(name)));
+
+                // Copying onto itself with keep both makes a duplicate next to the original
+                if options.keep_both && (kind == BatchKind::Copy || destination.as_ref() != Some(&source)) {
+                    destination = destination.map(|destination| directory::unique_path(&destination, &seen));
+                }
+
+                let conflict = if source.symlink_metadata().is_err() {
+                    Some(Conflict::SourceMissing)
+                } else if let Some(destination) = &destination {
+                    if *destination == source {
+                        Some(Conflict::SameLocation)
+                    } else if destination.starts_with(&source) {
+                        Some(Conflict::IntoItself)
+                    } else if !seen.insert(destination.clone()) {
+                        Some(Conflict::Duplicate)
+                    } else if destination.symlink_metadata().is_ok() {
+                        // Only a directory can be merged into a directory, and a file replace a file
+                        updates = update && source.is_dir() == destination.is_dir();
+                        (!updates).then_some(Conflict::DestinationExists)
+                    } else {
+                        None
+                    }
+                } else if target_dir.is_some() {
+                    Some(Conflict::NoName)
+                } else {
+                    None
+                };
+
+                BatchStep { source, destination, conflict, updates }
+            })
+            .collect();
+
+        let symlinks = match (kind, options.symlinks) {
+            (BatchKind::Copy, SymlinkPolicy::Ask) => count_symlinks(&steps),
+            _ => 0,
+        };
+
+        Ok(Self {
+            kind,
+            steps,
+            options,
+            symlinks,
+            target_dir: target_dir.map(Path::to_path_buf),
+        })
+    }
+
+    /// Plans the same sources again with different options.
+    pub fn replan(&self, options: CopyOptions) -> Result<Self, MyError> {
+        let sources = self.steps.iter().map(|step| step.source.clone()).collect();
+
+        BatchPlan::new(self.kind, sources, self.target_dir.as_deref(), options)
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.steps.iter().filter(|step| step.conflict.is_some()).count()
+    }
+
+    /// The steps that will actually run.
+    pub fn runnable(&self) -> impl Iterator<Item = &BatchStep> {
+        self.steps.iter().filter(|step| step.conflict.is_none())
+    }
+
+    /// Whether the symlink policy still has to be chosen before the plan can run.
+    pub fn needs_symlink_choice(&self) -> bool {
+        self.options.symlinks == SymlinkPolicy::Ask && self.symlinks > 0
+    }
+}
+
+/// How the steps of a running plan turned out, so the ones that failed can be planned
+/// again. Steps keep running past failures; copies finish in the background.
+pub struct BatchOutcome {
+    pub kind: BatchKind,
+    options: CopyOptions,
+    target_dir: Option<PathBuf>,
+    /// Sources of copies that haven't finished yet
+    pending: HashSet<PathBuf>,
+    pub ran: usize,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl BatchOutcome {
+    pub fn for_plan(plan: &BatchPlan) -> Self {
+        Self {
+            kind: plan.kind,
+            options: plan.options,
+            target_dir: plan.target_dir.clone(),
+            pending: HashSet::new(),
+            ran: 0,
+            failed: Vec::new(),
+        }
+    }
+
+    pub fn start(&mut self, source: &Path) {
+        self.ran += 1;
+        self.pending.insert(source.to_path_buf());
+    }
+
+    /// Notes how the step for `source` went. Returns false for results of other work.
+    pub fn finish<T>(&mut self, source: &Path, result: &Result<T, MyError>) -> bool {
+        if !self.pending.remove(source) {
+            return false;
+        }
+
+        if let Err(e) = result {
+            self.failed.push((source.to_path_buf(), e.message()));
+        }
+
+        true
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Plans the failed sources again with the same options.
+    pub fn retry(&self) -> Result<BatchPlan, MyError> {
+        let sources = self.failed.iter().map(|(source, _)| source.clone()).collect();
+
+        BatchPlan::new(self.kind, sources, self.target_dir.as_deref(), self.options)
+    }
+}
+
+fn count_symlinks(steps: &[BatchStep]) -> usize {
+    steps
+        .iter()
+        .filter(|step| step.conflict.is_none())
+        .flat_map(|step| WalkDir::new(&step.source).follow_root_links(false))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path_is_symlink())
+        .count()
+}
+
+/// Copies a file, symlink or whole directory tree to a destination that doesn't exist yet,
+/// reporting the bytes copied to `progress`. FIFOs, sockets and devices are skipped, and
+/// the copy fails naming them once everything else is copied.
+pub fn copy_recursive(source: &Path, destination: &Path, options: &CopyOptions, progress: &Progress) -> Result<(), MyError> {
+    let update = options.update != UpdatePolicy::Never;
+
+    if !update && destination.symlink_metadata().is_ok() {
+        return Err(MyError::AlreadyExists(destination.to_path_buf()));
+    }
+
+    progress.set_total(match source.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => directory::dir_stats(source, u64::MAX, u64::MAX).size,
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    });
+
+    // Directory attributes are applied last, so read-only directories can still be filled
+    // and their timestamps aren't changed again by what's copied into them
+    let mut directories = Vec::new();
+    // Directories and symlinks are made while walking, the files are copied in parallel afterwards
+    let mut files = Vec::new();
+    let mut skipped = Vec::new();
+    let follow = options.symlinks == SymlinkPolicy::Follow;
+
+    for entry in WalkDir::new(source).follow_links(follow).follow_root_links(follow) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            // A dangling link has nothing to follow, so it's copied as it is
+            Err(e) if follow && e.path().is_some_and(|path| path.is_symlink() && !path.exists()) => {
+                let path = e.path().unwrap_or(source);
+                let target = destination.join(path.strip_prefix(source).unwrap_or(path));
+                copy_symlink(path, &target).map_err(|e| MyError::io(e, target))?;
+                continue;
+            },
+            Err(e) => return Err(walk_error(e, source)),
+        };
+        let relative = entry.path().strip_prefix(source).unwrap_or(entry.path());
+        // Joining an empty path would add a trailing slash to a copied file's name
+        let target = match relative.as_os_str().is_empty() {
+            true => destination.to_path_buf(),
+            false => destination.join(relative),
+        };
+        let file_type = entry.file_type();
+
+        let result = if file_type.is_symlink() {
+            // Links already at the destination are left as they are when updating
+            if update && target.symlink_metadata().is_ok() {
+                continue;
+            }
+
+            copy_symlink(entry.path(), &target).map(|_| {
+                if let (true, Ok(metadata)) = (options.preserve, entry.metadata()) {
+                    preserve_link_owner(&metadata, &target);
+                }
+            })
+        } else if file_type.is_dir() {
+            let created = match fs::create_dir(&target) {
+                Err(e) if update && e.kind() == io::ErrorKind::AlreadyExists && target.is_dir() => Ok(()),
+                result => result,
+            };
+
+            created.and_then(|_| {
+                directories.push((target.clone(), entry.metadata()?));
+                Ok(())
+            })
+        } else if FileTypeEnum::of(file_type).is_special() {
+            // Opening a FIFO waits for a writer forever, and the others can't be read at all
+            skipped.push(entry.path().to_path_buf());
+            Ok(())
+        } else {
+            files.push((entry.path().to_path_buf(), target.clone()));
+            Ok(())
+        };
+
+        result.map_err(|e| MyError::io(e, target))?;
+    }
+
+    copy_files(&files, options, progress)?;
+
+    for (directory, metadata) in directories.into_iter().rev() {
+        let result = match options.preserve {
+            true => File::open(&directory).and_then(|handle| preserve_attributes(&metadata, &handle)),
+            false => Ok(()),
+        };
+
+        result.map_err(|e| MyError::io(e, directory))?;
+    }
+
+    match skipped.as_slice() {
+        [] => Ok(()),
+        [path] => Err(MyError::FileError(format!("Skipped {}, a FIFO, socket or device, which can't be copied", path.display()))),
+        [path, ..] => Err(MyError::FileError(format!(
+            "Skipped {} FIFOs, sockets or devices, which can't be copied, like {}",
+            skipped.len(),
+            path.display(),
+        ))),
+    }
+}
+
+fn walk_error(error: walkdir::Error, root: &Path) -> MyError {
+    let path = error.path().unwrap_or(root).to_path_buf();
+
+    match error.into_io_error() {
+        Some(error) => MyError::io(error, path),
+        None => MyError::FileError(format!("{} links back to a directory it's in", path.display())),
+    }
+}
+
+/// Copies every (source, destination) pair on a bounded number of threads, which pays off
+/// for trees of many small files. Stops at the first failure.
+fn copy_files(files: &[(PathBuf, PathBuf)], options: &CopyOptions, progress: &Progress) -> Result<(), MyError> {
+    let threads = COPY_THREADS.min(files.len());
+
+    let next = AtomicUsize::new(0);
+    let failed = AtomicBool::new(false);
+    let failure = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                while !failed.load(Ordering::Relaxed) {
+                    let Some((source, destination)) = files.get(next.fetch_add(1, Ordering::Relaxed)) else { break };
+
+                    if let Err(e) = copy_file(source, destination, options, progress) {
+                        failed.store(true, Ordering::Relaxed);
+                        failure.lock().unwrap_or_else(|e| e.into_inner()).get_or_insert(e);
+                    }
+                }
+            });
+        }
+    });
+
+    match failure.into_inner().unwrap_or_else(|e| e.into_inner()) {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+/// Copies a regular file, replacing an existing destination only if the update policy says so.
+fn copy_file(source: &Path, destination: &Path, options: &CopyOptions, progress: &Progress) -> Result<(), MyError> {
+    let metadata = fs::metadata(source).map_err(|e| MyError::io(e, source))?;
+
+    let existing = match options.update {
+        UpdatePolicy::Never => None,
+        _ => destination.symlink_metadata().ok(),
+    };
+
+    let Some(existing) = existing else {
+        return copy_new_file(source, destination, &metadata, options.preserve, progress);
+    };
+
+    if !options.update.replaces(&metadata, &existing) {
+        progress.add(metadata.len());
+        return Ok(());
+    }
+
+    // Copied next to the old file first, which is only replaced once the copy is complete
+    let mut partial = destination.as_os_str().to_owned();
+    partial.push(".partial");
+    let partial = PathBuf::from(partial);
+
+    copy_new_file(source, &partial, &metadata, options.preserve, progress)
+        .and_then(|_| fs::rename(&partial, destination).map_err(|e| MyError::io(e, destination)))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&partial);
+        })
+}
+
+/// Copies a regular file into a new `destination`. Where the file system allows it the data
+/// is cloned or copied inside the kernel, otherwise it goes through a buffer.
+fn copy_new_file(source: &Path, destination: &Path, metadata: &Metadata, preserve: bool, progress: &Progress) -> Result<(), MyError> {
+    // A clone carries the attributes along
+    if clone_path(source, destination) {
+        progress.add(metadata.len());
+        return Ok(());
+    }
+
+    let mut reader = File::open(source).map_err(|e| MyError::io(e, source))?;
+    let mut writer = File::create_new(destination).map_err(|e| MyError::io(e, destination))?;
+
+    // Once both are open, failures are nearly always about the destination, like a full disk
+    let copied = match copy_in_kernel(&reader, &writer, metadata.len(), progress) {
+        Ok(false) => copy_buffered(&mut reader, &mut writer, progress),
+        result => result.map(|_| ()),
+    };
+
+    copied
+        .and_then(|_| match preserve {
+            true => preserve_attributes(metadata, &writer),
+            false => Ok(()),
+        })
+        .map_err(|e| MyError::io(e, destination))
+}
+
+/// Gives the copy the timestamps, owner and permissions of its source. The permissions
+/// come last, as changing the owner can drop setuid and setgid bits.
+fn preserve_attributes(metadata: &Metadata, copy: &File) -> io::Result<()> {
+    let mut times = FileTimes::new();
+
+    if let Ok(modified) = metadata.modified() {
+        times = times.set_modified(modified);
+    }
+
+    if let Ok(accessed) = metadata.accessed() {
+        times = times.set_accessed(accessed);
+    }
+
+    copy.set_times(times)?;
+    preserve_owner(metadata, copy);
+    copy.set_permissions(metadata.permissions())
+}
+
+/// Only root can give files away, but anyone can keep the group if they belong to it,
+/// so failing to change the owner isn't an error.
+#[cfg(unix)]
+fn preserve_owner(metadata: &Metadata, copy: &File) {
+    use std::os::unix::fs::{fchown, MetadataExt};
+
+    if fchown(copy, Some(metadata.uid()), Some(metadata.gid())).is_err() {
+        let _ = fchown(copy, None, Some(metadata.gid()));
+    }
+}
+
+#[cfg(not(unix))]
+fn preserve_owner(_metadata: &Metadata, _copy: &File) {}
+
+#[cfg(unix)]
+fn preserve_link_owner(metadata: &Metadata, link: &Path) {
+    use std::os::unix::fs::{lchown, MetadataExt};
+
+    if lchown(link, Some(metadata.uid()), Some(metadata.gid())).is_err() {
+        let _ = lchown(link, None, Some(metadata.gid()));
+    }
+}
+
+#[cfg(not(unix))]
+fn preserve_link_owner(_metadata: &Metadata, _link: &Path) {}
+
+fn copy_buffered(reader: &mut File, writer: &mut File, progress: &Progress) -> io::Result<()> {
+    let mut buffer = vec![0; COPY_BUFFER_SIZE];
+
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(read) => read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        writer.write_all(&buffer[..read])?;
+        progress.add(read as u64);
+    }
+}
+
+/// Clones the file on APFS, which shares the data until either copy changes.
+#[cfg(target_os = "macos")]
+fn clone_path(source: &Path, destination: &Path) -> bool {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    let (Ok(source), Ok(destination)) = (
+        CString::new(source.as_os_str().as_bytes()),
+        CString::new(destination.as_os_str().as_bytes()),
+    ) else {
+        return false;
+    };
+
+    // Fails on other file systems, which just means copying the usual way
+    unsafe { libc::clonefile(source.as_ptr(), destination.as_ptr(), 0) == 0 }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn clone_path(_source: &Path, _destination: &Path) -> bool {
+    false
+}
+
+/// Reflinks the file on btrfs and XFS, or has the kernel copy it without going through user
+/// space. Returns false, having written nothing, when the file system supports neither.
+#[cfg(target_os = "linux")]
+fn copy_in_kernel(reader: &File, writer: &File, length: u64, progress: &Progress) -> io::Result<bool> {
+    use std::{os::fd::AsRawFd, ptr};
+
+    let (source, destination) = (reader.as_raw_fd(), writer.as_raw_fd());
+
+    if unsafe { libc::ioctl(destination, libc::FICLONE as _, source) } == 0 {
+        progress.add(length);
+        return Ok(true);
+    }
+
+    let mut copied = 0;
+
+    loop {
+        let result = unsafe {
+            libc::copy_file_range(source, ptr::null_mut(), destination, ptr::null_mut(), COPY_BUFFER_SIZE, 0)
+        };
+
+        match result {
+            // Files like those in /proc claim to be empty, so they're left to be read normally
+            0 if copied == 0 => return Ok(false),
+            0 => return Ok(true),
+            -1 => {
+                let error = io::Error::last_os_error();
+
+                match error.raw_os_error() {
+                    Some(libc::EINTR) => continue,
+                    Some(libc::EXDEV | libc::ENOSYS | libc::EINVAL | libc::EOPNOTSUPP | libc::EPERM | libc::EBADF) if copied == 0 => {
+                        return Ok(false)
+                    },
+                    _ => return Err(error),
+                }
+            },
+            written => {
+                copied += written as u64;
+                progress.add(written as u64);
+            },
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn copy_in_kernel(_reader: &File, _writer: &File, _length: u64, _progress: &Progress) -> io::Result<bool> {
+    Ok(false)
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(source)?, destination)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, destination: &Path) -> io::Result<()> {
+    fs::copy(source, destination).map(|_| ())
+}
+
+/// Renames `source`, which fails with `MyError::CrossDevice` when the destination is on
+/// another file system and the move has to be done by `move_across` instead.
+pub fn rename_path(source: &Path, destination: &Path) -> Result<(), MyError> {
+    if destination.symlink_metadata().is_ok() {
+        return Err(MyError::AlreadyExists(destination.to_path_buf()));
+    }
+
+    fs::rename(source, destination).map_err(|e| MyError::io(e, source))
+}
+
+/// Moves to another file system by copying and then removing the source. Trees with
+/// FIFOs, sockets or devices aren't touched, as the copy would have to leave them out.
+pub fn move_across(source: &Path, destination: &Path, progress: &Progress) -> Result<(), MyError> {
+    let special = WalkDir::new(source)
+        .into_iter()
+        .filter_map(Result::ok)
+        .find(|entry| FileTypeEnum::of(entry.file_type()).is_special());
+
+    if let Some(entry) = special {
+        return Err(MyError::FileError(format!(
+            "{} is a FIFO, socket or device, which can't be moved to another file system",
+            entry.path().display(),
+        )));
+    }
+
+    // Like mv, a move that has to copy keeps everything about the files
+    let options = CopyOptions { preserve: true, ..CopyOptions::default() };
+
+    copy_recursive(source, destination, &options, progress)?;
+    remove_path(source)
+}
+
+/// Removes a directory tree one entry at a time, so progress can be counted in entries
+/// and the removal stopped in between. What was removed before stopping stays removed.
+pub fn remove_tree(path: &Path, progress: &Progress) -> Result<(), MyError> {
+    progress.count_items();
+    progress.allow_cancel();
+
+    let total = WalkDir::new(path).into_iter().take_while(|_| !progress.is_cancelled()).count() as u64;
+    progress.set_total(total);
+
+    for entry in WalkDir::new(path).contents_first(true) {
+        if progress.is_cancelled() {
+            return Err(MyError::FileError(format!("Stopped after deleting {} of {} entries in {}", progress.done(), total, path.display())));
+        }
+
+        let entry = entry.map_err(|e| walk_error(e, path))?;
+
+        let result = match entry.file_type().is_dir() {
+            true => fs::remove_dir(entry.path()),
+            false => fs::remove_file(entry.path()),
+        };
+
+        result.map_err(|e| MyError::io(e, entry.path()))?;
+        progress.add(1);
+    }
+
+    Ok(())
+}
+
+pub fn remove_path(path: &Path) -> Result<(), MyError> {
+    let result = match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(path),
+        _ => fs::remove_file(path),
+    };
+
+    result.map_err(|e| MyError::io(e, path))
+}
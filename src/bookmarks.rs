@@ -0,0 +1,126 @@
+use std::{fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{config, my_errors::MyError};
+
+/// Labelled directory bookmarks, persisted to `bookmarks.toml` in the config directory.
+#[derive(Default, Serialize, Deserialize)]
+pub struct Bookmarks {
+    #[serde(default, rename = "bookmark")]
+    entries: Vec<Bookmark>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub label: String,
+    pub path: String,
+    /// Digit 1–9 that jumps straight to the bookmark from the file list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<u8>,
+    /// Set by `validate` when the directory is gone
+    #[serde(skip)]
+    pub missing: bool,
+}
+
+impl Bookmarks {
+    pub fn load() -> Result<Self, MyError> {
+        let Some(file_path) = config::bookmarks_file() else { return Ok(Bookmarks::default()) };
+
+        if !file_path.exists() {
+            return Ok(Bookmarks::default());
+        }
+
+        let contents = fs::read_to_string(&file_path)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't read {}: {}", file_path.display(), e)))?;
+
+        toml::from_str(&contents)
+            .map_err(|e| MyError::ConfigError(format!("Invalid {}: {}", file_path.display(), e.message())))
+    }
+
+    pub fn save(&self) -> Result<(), MyError> {
+        let Some(file_path) = config::bookmarks_file() else {
+            return Err(MyError::ConfigError("No config directory".to_string()));
+        };
+
+        let contents = toml::to_string_pretty(self)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't serialize bookmarks: {}", e)))?;
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| MyError::ConfigError(format!("Couldn't create {}: {}", parent.display(), e)))?;
+        }
+
+        fs::write(&file_path, contents)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't write {}: {}", file_path.display(), e)))
+    }
+
+    pub fn entries(&self) -> &[Bookmark] {
+        &self.entries
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Bookmark> {
+        self.entries.get(index)
+    }
+
+    pub fn add(&mut self, path: PathBuf) {
+        let label = match path.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => path.to_string_lossy().to_string(),
+        };
+
+        self.entries.push(Bookmark {
+            label,
+            path: path.to_string_lossy().to_string(),
+            slot: None,
+            missing: false,
+        });
+    }
+
+    pub fn remove(&mut self, index: usize) {
+        if index < self.entries.len() {
+            self.entries.remove(index);
+        }
+    }
+
+    pub fn by_slot(&self, slot: u8) -> Option<&Bookmark> {
+        self.entries.iter().find(|bookmark| bookmark.slot == Some(slot))
+    }
+
+    /// Binds the bookmark to a slot, taking it from whichever bookmark had it.
+    /// Setting a bookmark's current slot again, or slot 0, unbinds it.
+    pub fn set_slot(&mut self, index: usize, slot: u8) {
+        let Some(current) = self.entries.get(index).map(|bookmark| bookmark.slot) else { return };
+        let slot = if slot == 0 || current == Some(slot) { None } else { Some(slot) };
+
+        for bookmark in self.entries.iter_mut().filter(|bookmark| slot.is_some() && bookmark.slot == slot) {
+            bookmark.slot = None;
+        }
+
+        self.entries[index].slot = slot;
+    }
+
+    /// Flags the bookmarks whose directory no longer exists, e.g. after it was moved or
+    /// its drive unmounted, and returns how many there are.
+    pub fn validate(&mut self) -> usize {
+        for bookmark in &mut self.entries {
+            bookmark.missing = !Path::new(&bookmark.path).is_dir();
+        }
+
+        self.entries.iter().filter(|bookmark| bookmark.missing).count()
+    }
+
+    /// Points the bookmark at `path` instead, keeping its label and slot.
+    pub fn relocate(&mut self, index: usize, path: PathBuf) {
+        if let Some(bookmark) = self.entries.get_mut(index) {
+            bookmark.path = path.to_string_lossy().to_string();
+            bookmark.missing = false;
+        }
+    }
+
+    pub fn rename(&mut self, index: usize, label: String) {
+        if let Some(bookmark) = self.entries.get_mut(index) {
+            bookmark.label = label;
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::{fs::{self, File}, io, path::{Path, PathBuf}};
+
+use md5::Md5;
+use sha2::{Digest, Sha256};
+
+use crate::my_errors::MyError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    Sha256,
+    Md5,
+}
+
+impl Algorithm {
+    const ALL: [Algorithm; 2] = [Algorithm::Sha256, Algorithm::Md5];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Algorithm::Sha256 => "sha256",
+            Algorithm::Md5 => "md5",
+        }
+    }
+
+    pub fn for_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+
+        Algorithm::ALL.into_iter().find(|algorithm| algorithm.extension() == extension)
+    }
+
+    fn digest(&self, path: &Path) -> io::Result<String> {
+        let mut file = File::open(path)?;
+
+        let hash = match self {
+            Algorithm::Sha256 => hex(&hash_reader::<Sha256>(&mut file)?),
+            Algorithm::Md5 => hex(&hash_reader::<Md5>(&mut file)?),
+        };
+
+        Ok(hash)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VerificationStatus {
+    Passed,
+    Failed,
+    Missing,
+}
+
+pub struct Verification {
+    pub file_name: String,
+    pub status: VerificationStatus,
+}
+
+/// The checksum file to verify for `path`: `path` itself if it is one,
+/// otherwise a `path.sha256` or `path.md5` next to it.
+pub fn sidecar_for(path: &Path) -> Option<PathBuf> {
+    if Algorithm::for_path(path).is_some() {
+        return Some(path.to_path_buf());
+    }
+
+    Algorithm::ALL
+        .into_iter()
+        .map(|algorithm| {
+            let mut sidecar = path.as_os_str().to_owned();
+            sidecar.push(".");
+            sidecar.push(algorithm.extension());
+            PathBuf::from(sidecar)
+        })
+        .find(|sidecar| sidecar.is_file())
+}
+
+/// Checks every file listed in `sidecar` against its checksum. Lines use the
+/// `sha256sum`/`md5sum` format; a file holding only a hash refers to the file
+/// named like the sidecar without its extension. Paths are relative to the sidecar.
+pub fn verify(sidecar: &Path) -> Result<Vec<Verification>, MyError> {
+    let Some(algorithm) = Algorithm::for_path(sidecar) else {
+        return Err(MyError::FileError("Not a .sha256 or .md5 file".to_string()));
+    };
+
+    let contents = fs::read_to_string(sidecar).map_err(|e| MyError::io(e, sidecar))?;
+    let dir = sidecar.parent().unwrap_or(Path::new(""));
+
+    let entries = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(|line| match line.trim().split_once(char::is_whitespace) {
+            // `*` marks binary mode in the coreutils format
+            Some((hash, name)) => (hash.to_string(), name.trim_start().trim_start_matches('*').to_string()),
+            None => (line.trim().to_string(), stem_name(sidecar)),
+        })
+        .collect::<Vec<(String, String)>>();
+
+    if entries.is_empty() {
+        return Err(MyError::FileError("Checksum file is empty".to_string()));
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|(hash, file_name)| {
+            let status = match algorithm.digest(&dir.join(&file_name)) {
+                Ok(actual) if actual.eq_ignore_ascii_case(&hash) => VerificationStatus::Passed,
+                Ok(_) => VerificationStatus::Failed,
+                Err(_) => VerificationStatus::Missing,
+            };
+
+            Verification { file_name, status }
+        })
+        .collect())
+}
+
+fn stem_name(sidecar: &Path) -> String {
+    sidecar
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn hash_reader<D: Digest + io::Write>(reader: &mut impl io::Read) -> io::Result<Vec<u8>> {
+    let mut hasher = D::new();
+    io::copy(reader, &mut hasher)?;
+
+    Ok(hasher.finalize().to_vec())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
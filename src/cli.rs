@@ -0,0 +1,143 @@
+use crate::my_errors::MyError;
+
+/// A command-line flag, described once for both `main` and the completion scripts.
+pub struct Flag {
+    pub name: &'static str,
+    /// What the flag's value completes to, if it takes one
+    pub value: Option<Value>,
+    pub help: &'static str,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Value {
+    Profile,
+}
+
+/// A command given instead of starting the UI, like `dirman completions bash`.
+pub struct Command {
+    pub name: &'static str,
+    pub arguments: &'static [&'static str],
+    pub help: &'static str,
+}
+
+pub const FLAGS: &[Flag] = &[
+    Flag { name: "--profile", value: Some(Value::Profile), help: "Read config, bookmarks and plugins from a named profile" },
+    Flag { name: "--create-from-stdin", value: None, help: "Save piped data as a new file under the name typed" },
+];
+
+pub const COMMANDS: &[Command] = &[
+    Command { name: "completions", arguments: &["bash", "zsh", "fish"], help: "Print a shell completion script" },
+    Command { name: "profiles", arguments: &[], help: "List the profiles in the config directory" },
+];
+
+// The completion scripts ask dirman itself for the profiles, so new ones complete right away
+const LIST_PROFILES: &str = "dirman profiles 2>/dev/null";
+
+/// The completion script for `shell`, generated from `FLAGS` and `COMMANDS`.
+pub fn completions(shell: &str) -> Result<String, MyError> {
+    match shell {
+        "bash" => Ok(bash()),
+        "zsh" => Ok(zsh()),
+        "fish" => Ok(fish()),
+        "" => Err(MyError::CommandError("Name a shell: bash, zsh or fish".to_string())),
+        _ => Err(MyError::CommandError(format!("No completions for {}, only for bash, zsh and fish", shell))),
+    }
+}
+
+fn bash() -> String {
+    let words = FLAGS
+        .iter()
+        .map(|flag| flag.name)
+        .chain(COMMANDS.iter().map(|command| command.name))
+        .collect::<Vec<&str>>()
+        .join(" ");
+
+    let mut cases = String::new();
+
+    for flag in FLAGS.iter().filter(|flag| flag.value == Some(Value::Profile)) {
+        cases.push_str(&format!(
+            "        {}) COMPREPLY=($(compgen -W \"$({})\" -- \"$cur\")); return ;;\n",
+            flag.name, LIST_PROFILES,
+        ));
+    }
+
+    for command in COMMANDS.iter().filter(|command| !command.arguments.is_empty()) {
+        cases.push_str(&format!(
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")); return ;;\n",
+            command.name, command.arguments.join(" "),
+        ));
+    }
+
+    format!(
+        "_dirman() {{\n    \
+            local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n\n    \
+            case \"$prev\" in\n{}    esac\n\n    \
+            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n\
+        }}\n\n\
+        complete -F _dirman dirman\n",
+        cases, words,
+    )
+}
+
+fn zsh() -> String {
+    let mut specs = FLAGS
+        .iter()
+        .map(|flag| match flag.value {
+            Some(Value::Profile) => format!("'{}[{}]:profile:($({}))'", flag.name, flag.help, LIST_PROFILES),
+            None => format!("'{}[{}]'", flag.name, flag.help),
+        })
+        .collect::<Vec<String>>();
+
+    let commands = COMMANDS
+        .iter()
+        .map(|command| format!("{}\\:\"{}\"", command.name, command.help))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    specs.push(format!("'1:command:(({}))'", commands));
+    specs.push("'*::argument:->argument'".to_string());
+
+    let mut cases = String::new();
+
+    for command in COMMANDS.iter().filter(|command| !command.arguments.is_empty()) {
+        cases.push_str(&format!("        {}) _values 'argument' {} ;;\n", command.name, command.arguments.join(" ")));
+    }
+
+    format!(
+        "#compdef dirman\n\n\
+        _dirman() {{\n    \
+            local state\n    \
+            _arguments \\\n        {}\n\n    \
+            [[ $state == argument ]] || return\n\n    \
+            case $words[1] in\n{}    esac\n\
+        }}\n\n\
+        _dirman \"$@\"\n",
+        specs.join(" \\\n        "), cases,
+    )
+}
+
+fn fish() -> String {
+    let mut lines = Vec::new();
+
+    for flag in FLAGS {
+        let name = flag.name.trim_start_matches("--");
+
+        match flag.value {
+            Some(Value::Profile) => lines.push(format!("complete -c dirman -l {} -x -a '({})' -d '{}'", name, LIST_PROFILES, flag.help)),
+            None => lines.push(format!("complete -c dirman -l {} -d '{}'", name, flag.help)),
+        }
+    }
+
+    for command in COMMANDS {
+        lines.push(format!("complete -c dirman -n __fish_use_subcommand -f -a {} -d '{}'", command.name, command.help));
+
+        if !command.arguments.is_empty() {
+            lines.push(format!(
+                "complete -c dirman -n '__fish_seen_subcommand_from {}' -f -a '{}'",
+                command.name, command.arguments.join(" "),
+            ));
+        }
+    }
+
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,21 @@
+use std::process::Command;
+
+use crate::my_errors::MyError;
+
+// Tried in order; the first one that's installed and succeeds wins
+const PASTE_COMMANDS: &[(&str, &[&str])] = &[
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-out", "-selection", "clipboard"]),
+    ("xsel", &["--output", "--clipboard"]),
+    ("pbpaste", &[]),
+];
+
+/// Reads the system clipboard through whichever clipboard tool is available.
+pub fn paste() -> Result<Vec<u8>, MyError> {
+    PASTE_COMMANDS
+        .iter()
+        .filter_map(|(program, args)| Command::new(program).args(*args).output().ok())
+        .find(|output| output.status.success())
+        .map(|output| output.stdout)
+        .ok_or_else(|| MyError::CommandError("No clipboard tool found (wl-paste, xclip, xsel or pbpaste)".to_string()))
+}
@@ -0,0 +1,75 @@
+use std::{fmt, io, path::{Path, PathBuf}};
+
+use flate2::{read::MultiGzDecoder, write::GzEncoder, Compression};
+
+use crate::{directory, my_errors::MyError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Codec::Gzip => write!(f, "gzip"),
+            Codec::Zstd => write!(f, "zstd"),
+        }
+    }
+}
+
+impl Codec {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Codec::Gzip => "gz",
+            Codec::Zstd => "zst",
+        }
+    }
+
+    /// The codec a file was compressed with, going by its extension.
+    pub fn for_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Codec::Gzip),
+            "zst" => Some(Codec::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Compresses `path` into `path.gz`/`path.zst` next to it and returns the new path.
+/// The original is removed afterwards unless `keep` is set.
+pub fn compress(path: &Path, codec: Codec, keep: bool) -> Result<PathBuf, MyError> {
+    let mut destination = path.as_os_str().to_owned();
+    destination.push(".");
+    destination.push(codec.extension());
+    let destination = PathBuf::from(destination);
+
+    directory::transcode(path, &destination, keep, |mut source, destination| match codec {
+        Codec::Gzip => {
+            let mut encoder = GzEncoder::new(destination, Compression::default());
+            io::copy(&mut source, &mut encoder)?;
+            encoder.finish().map(|_| ())
+        },
+        Codec::Zstd => zstd::stream::copy_encode(source, destination, 0),
+    })?;
+
+    Ok(destination)
+}
+
+/// Decompresses a `.gz`/`.zst` file next to itself, dropping the extension, and returns the new path.
+/// The compressed file is removed afterwards unless `keep` is set.
+pub fn decompress(path: &Path, keep: bool) -> Result<PathBuf, MyError> {
+    let Some(codec) = Codec::for_path(path) else {
+        return Err(MyError::FileError("Not a gzip or zstd file".to_string()));
+    };
+
+    let destination = path.with_extension("");
+
+    directory::transcode(path, &destination, keep, |source, mut destination| match codec {
+        Codec::Gzip => io::copy(&mut MultiGzDecoder::new(source), &mut destination).map(|_| ()),
+        Codec::Zstd => zstd::stream::copy_decode(source, destination),
+    })?;
+
+    Ok(destination)
+}
@@ -0,0 +1,430 @@
+use std::{fs::{self, OpenOptions}, io::Write, path::PathBuf, sync::OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{batch::CopyOptions, directory::SortMode, filter::{Category, Recency}, hooks::Hook, matcher::FileMatcher, my_errors::MyError, ui::{columns::Column, format::TimeFormat}};
+
+/// User settings read from `config.toml` in the config directory.
+/// Every section is optional and falls back to its defaults.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub zoxide: ZoxideConfig,
+    pub search: SearchConfig,
+    pub preview: PreviewConfig,
+    pub opener: OpenerConfig,
+    pub delete: DeleteConfig,
+    pub paths: PathsConfig,
+    pub network: NetworkConfig,
+    pub listing: ListingConfig,
+    pub copy: CopyOptions,
+    #[serde(rename = "hook")]
+    pub hooks: Vec<Hook>,
+    #[serde(rename = "view")]
+    pub views: Vec<View>,
+    #[serde(rename = "action")]
+    pub actions: Vec<CustomAction>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct ZoxideConfig {
+    pub enabled: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+    pub fd: bool,
+    pub ripgrep: bool,
+    pub max_results: usize,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            fd: true,
+            ripgrep: true,
+            max_results: 1000,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PreviewConfig {
+    #[serde(rename = "command")]
+    pub commands: Vec<PreviewCommand>,
+    pub max_size: u64,
+    /// How many files' previews are kept, so moving back over them doesn't read or run anything
+    pub cache_entries: usize,
+}
+
+impl Default for PreviewConfig {
+    fn default() -> Self {
+        Self {
+            commands: Vec::new(),
+            max_size: 10 * 1024 * 1024,
+            cache_entries: 64,
+        }
+    }
+}
+
+/// A command whose output replaces the built-in preview for matching files.
+/// `{}` in `command` is replaced with the quoted file path.
+#[derive(Clone, Deserialize)]
+pub struct PreviewCommand {
+    #[serde(flatten)]
+    pub matcher: FileMatcher,
+    pub command: String,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+pub struct OpenerConfig {
+    #[serde(rename = "rule")]
+    pub rules: Vec<OpenerRule>,
+    /// Replaces xdg-open/open for files matching no rule
+    pub default: Option<OpenerCommand>,
+    /// Replaces `$VISUAL`/`$EDITOR` for editing
+    pub editor: Option<OpenerCommand>,
+}
+
+/// A command configured in place of one found in the environment. `{}` stands for
+/// the file path, which is appended when the command doesn't mention it.
+#[derive(Clone, Deserialize)]
+pub struct OpenerCommand {
+    pub command: String,
+    #[serde(default)]
+    pub terminal: bool,
+    #[serde(default)]
+    pub detached: bool,
+}
+
+/// Opens matching files with `command`. Terminal commands suspend the UI until they exit,
+/// GUI commands either block or run detached.
+#[derive(Clone, Deserialize)]
+pub struct OpenerRule {
+    #[serde(flatten)]
+    pub matcher: FileMatcher,
+    pub command: String,
+    #[serde(default)]
+    pub terminal: bool,
+    #[serde(default)]
+    pub detached: bool,
+}
+
+/// A shell command added to the Action menu of matching files. `{}` in `command` is
+/// replaced with the quoted file path; `terminal` and `detached` work as for openers.
+#[derive(Clone, Deserialize)]
+pub struct CustomAction {
+    pub name: String,
+    #[serde(flatten)]
+    pub matcher: FileMatcher,
+    pub command: String,
+    #[serde(default)]
+    pub terminal: bool,
+    #[serde(default)]
+    pub detached: bool,
+}
+
+/// Directories with more entries or bytes than these limits can only be
+/// deleted by typing their name instead of `y`.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct DeleteConfig {
+    pub confirm_entries: u64,
+    pub confirm_size: u64,
+    /// Empty items trashed longer ago than this out of the system trash at startup
+    pub purge_trash_after_days: Option<u64>,
+}
+
+impl Default for DeleteConfig {
+    fn default() -> Self {
+        Self {
+            confirm_entries: 100,
+            confirm_size: 100 * 1024 * 1024,
+            purge_trash_after_days: None,
+        }
+    }
+}
+
+/// On NFS, SMB, SSHFS and other network file systems, `cautious` only refreshes the
+/// listing on request and leaves entries alone until one is previewed.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub cautious: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self { cautious: true }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct PathsConfig {
+    pub physical: bool,
+    pub show_resolved: bool,
+    /// Files or directories marking a project root, for jumping to it
+    pub root_markers: Vec<String>,
+}
+
+impl Default for PathsConfig {
+    fn default() -> Self {
+        Self {
+            physical: false,
+            show_resolved: true,
+            root_markers: [".git", ".hg", "Cargo.toml", "package.json", "go.mod", "pyproject.toml"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+/// Limits for the flattened listing, which shows every file below the current
+/// directory that isn't ignored by `.gitignore` and friends, and the initial layout.
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct ListingConfig {
+    pub flat_depth: usize,
+    pub flat_max_entries: usize,
+    /// Start in the detailed layout
+    pub details: bool,
+    /// Start with hidden files shown
+    pub show_hidden: bool,
+    pub layout: MainLayout,
+    /// Columns of the detailed layout, in order
+    #[serde(rename = "column")]
+    pub columns: Vec<ColumnConfig>,
+    /// Names never listed, even with hidden files shown
+    pub always_hidden: Vec<String>,
+    /// Globs on the name of entries left out of listings until toggled with `<,>`
+    pub ignore: Vec<String>,
+    pub remember_views: ViewMemory,
+    /// How the modified column and Properties show times
+    pub time_format: TimeFormat,
+}
+
+/// Whether each directory keeps the sort order, filters and hidden toggle it was left
+/// with: not at all, until dirman exits, or across restarts.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewMemory {
+    Off,
+    #[default]
+    Session,
+    Persist,
+}
+
+/// Arrangement of the main screen: the file list next to properties and preview,
+/// or Miller columns with the parent directory on the left.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MainLayout {
+    #[default]
+    Standard,
+    Miller,
+}
+
+/// A column of the detailed layout; `width` overrides the column's default width
+/// and gives the name column a fixed one instead of the remaining space.
+#[derive(Clone, Deserialize)]
+pub struct ColumnConfig {
+    pub name: Column,
+    #[serde(default)]
+    pub width: Option<usize>,
+}
+
+impl ColumnConfig {
+    pub fn width(&self) -> Option<usize> {
+        self.width.or(self.name.width())
+    }
+}
+
+impl Default for ListingConfig {
+    fn default() -> Self {
+        Self {
+            flat_depth: 8,
+            flat_max_entries: 10_000,
+            details: false,
+            show_hidden: true,
+            layout: MainLayout::default(),
+            columns: Column::DEFAULT.map(|name| ColumnConfig { name, width: None }).to_vec(),
+            always_hidden: vec![".DS_Store".to_string()],
+            ignore: Vec::new(),
+            remember_views: ViewMemory::default(),
+            time_format: TimeFormat::default(),
+        }
+    }
+}
+
+/// A saved combination of sort order, filters and the hidden-files toggle,
+/// recalled with `<V>` followed by `key` or with `:view <name>`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct View {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<char>,
+    pub sort: SortMode,
+    pub descending: bool,
+    pub hidden: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub filter: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extensions: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<Recency>,
+}
+
+impl Config {
+    pub fn load() -> Result<Self, MyError> {
+        let Some(file_path) = config_file() else { return Ok(Config::default()) };
+
+        if !file_path.exists() {
+            return Ok(Config::default());
+        }
+
+        let contents = fs::read_to_string(&file_path)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't read {}: {}", file_path.display(), e)))?;
+
+        toml::from_str(&contents)
+            .map_err(|e| MyError::ConfigError(format!("Invalid {}: {}", file_path.display(), e.message())))
+    }
+
+    /// Whether there's no config file yet, which is what makes a run the first one.
+    pub fn is_missing() -> bool {
+        config_file().is_some_and(|file_path| !file_path.exists())
+    }
+
+    /// Writes the first config file, never replacing one that appeared in the meantime.
+    pub fn write_initial(contents: &str) -> Result<PathBuf, MyError> {
+        let Some(file_path) = config_file() else {
+            return Err(MyError::ConfigError("No config directory".to_string()));
+        };
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| MyError::ConfigError(format!("Couldn't create {}: {}", parent.display(), e)))?;
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+            .and_then(|mut file| file.write_all(contents.as_bytes()))
+            .map_err(|e| MyError::ConfigError(format!("Couldn't write {}: {}", file_path.display(), e)))?;
+
+        Ok(file_path)
+    }
+
+    /// Appends the view to the config file, leaving the rest of it untouched.
+    pub fn save_view(&mut self, view: View) -> Result<(), MyError> {
+        if self.views.iter().any(|saved| saved.name == view.name) {
+            return Err(MyError::ConfigError(format!("View {} already exists", view.name)));
+        }
+
+        let Some(file_path) = config_file() else {
+            return Err(MyError::ConfigError("No config directory".to_string()));
+        };
+
+        let contents = toml::to_string(&view)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't serialize view: {}", e)))?;
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| MyError::ConfigError(format!("Couldn't create {}: {}", parent.display(), e)))?;
+        }
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&file_path)
+            .and_then(|mut file| write!(file, "\n[[view]]\n{}", contents))
+            .map_err(|e| MyError::ConfigError(format!("Couldn't write {}: {}", file_path.display(), e)))?;
+
+        self.views.push(view);
+
+        Ok(())
+    }
+}
+
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// Switches to the profile `name` for the rest of the run: its config, bookmarks and plugins
+/// live in `profiles/<name>` of the config directory, and nothing outside it is read.
+/// Fails if the profile's directory doesn't exist, so a misspelled name isn't silently
+/// treated as an empty profile.
+pub fn select_profile(name: &str) -> Result<(), MyError> {
+    let Some(base) = dirs::config_dir() else {
+        return Err(MyError::ConfigError("No config directory".to_string()));
+    };
+
+    let dir = base.join("dirman").join("profiles").join(name);
+
+    if name.is_empty() || name.contains(['/', '\\']) || !dir.is_dir() {
+        return Err(MyError::ConfigError(format!("No profile {}, create {} for it", name, dir.display())));
+    }
+
+    PROFILE.set(name.to_string()).map_err(|_| MyError::ConfigError("A profile was already selected".to_string()))
+}
+
+/// The profile selected with `--profile`, if any.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(String::as_str)
+}
+
+/// The names `--profile` accepts, sorted: the directories under `profiles`.
+pub fn profile_names() -> Vec<String> {
+    let Some(dir) = dirs::config_dir().map(|dir| dir.join("dirman").join("profiles")) else { return Vec::new() };
+
+    let mut names = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+
+    names.sort();
+    names
+}
+
+pub fn config_dir() -> Option<PathBuf> {
+    let dir = dirs::config_dir()?.join("dirman");
+
+    match profile() {
+        Some(profile) => Some(dir.join("profiles").join(profile)),
+        None => Some(dir),
+    }
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+pub fn plugins_dir() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("plugins"))
+}
+
+pub fn bookmarks_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("bookmarks.toml"))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("dirman"))
+}
+
+pub fn directory_views_file() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("directory_views.toml"))
+}
+
+pub fn journal_file() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("journal.log"))
+}
@@ -0,0 +1,64 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use crate::{config::{self, View}, my_errors::MyError};
+
+/// The sort order, filters and hidden toggle each directory was last left with, so
+/// coming back brings them back. Directories left with the startup view aren't kept.
+/// With `persist`, they're saved to `directory_views.toml` in the data directory.
+#[derive(Default)]
+pub struct DirViews {
+    views: BTreeMap<String, View>,
+    persist: bool,
+}
+
+impl DirViews {
+    pub fn load(persist: bool) -> Result<Self, MyError> {
+        let Some(file_path) = config::directory_views_file().filter(|path| persist && path.exists()) else {
+            return Ok(DirViews { views: BTreeMap::new(), persist });
+        };
+
+        let contents = fs::read_to_string(&file_path)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't read {}: {}", file_path.display(), e)))?;
+
+        let views = toml::from_str(&contents)
+            .map_err(|e| MyError::ConfigError(format!("Invalid {}: {}", file_path.display(), e.message())))?;
+
+        Ok(DirViews { views, persist })
+    }
+
+    pub fn get(&self, dir: &Path) -> Option<&View> {
+        self.views.get(dir.to_string_lossy().as_ref())
+    }
+
+    /// Keeps `view` for `dir`, or forgets the directory when it's back to `default`.
+    pub fn remember(&mut self, dir: &Path, view: View, default: &View) -> Result<(), MyError> {
+        let key = dir.to_string_lossy().to_string();
+
+        let changed = match view == *default {
+            true => self.views.remove(&key).is_some(),
+            false => self.views.insert(key, view.clone()) != Some(view),
+        };
+
+        match changed && self.persist {
+            true => self.save(),
+            false => Ok(()),
+        }
+    }
+
+    fn save(&self) -> Result<(), MyError> {
+        let Some(file_path) = config::directory_views_file() else {
+            return Err(MyError::ConfigError("No data directory".to_string()));
+        };
+
+        let contents = toml::to_string_pretty(&self.views)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't serialize views: {}", e)))?;
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| MyError::ConfigError(format!("Couldn't create {}: {}", parent.display(), e)))?;
+        }
+
+        fs::write(&file_path, contents)
+            .map_err(|e| MyError::ConfigError(format!("Couldn't write {}: {}", file_path.display(), e)))
+    }
+}
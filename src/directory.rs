@@ -1,49 +1,619 @@
 use core::fmt;
-use std::{env, fs::{self, DirEntry}, path::PathBuf};
+use std::{collections::{BTreeSet, HashSet}, env, fs::{self, DirEntry, File, OpenOptions}, io::{self, Write}, path::{Component, Path, PathBuf}, sync::mpsc::{self, Receiver, RecvTimeoutError}, thread, time::{Duration, Instant, SystemTime}};
 
-use crate::my_errors::MyError;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
 
-#[derive(Debug)]
+use crate::{background::Progress, expansion, filter::ListingFilter, my_errors::MyError, permissions};
+
+#[derive(Debug, Clone)]
 pub struct FileManager {
     curr_path: PathBuf,
+    path_mode: PathMode,
+    flat: Option<FlatListing>,
+    /// Directories shown expanded in place, relative to `curr_path`
+    expanded: BTreeSet<PathBuf>,
+    filter: ListingFilter,
+    sort: SortMode,
+    descending: bool,
+    show_hidden: bool,
+    /// Names left out even when hidden entries are shown
+    always_hidden: Vec<String>,
+    ignore: GlobSet,
+    show_ignored: bool,
+}
+
+/// Order of the listing; ties and every mode but `Name` fall back to name order.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortMode {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Extension,
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl SortMode {
+    pub fn next(self) -> Self {
+        match self {
+            SortMode::Name => SortMode::Size,
+            SortMode::Size => SortMode::Modified,
+            SortMode::Modified => SortMode::Extension,
+            SortMode::Extension => SortMode::Name,
+        }
+    }
+}
+
+/// A listing running on its own thread, so a huge directory or a hung network mount
+/// doesn't freeze the UI and can be given up on. A scan given up on runs to the end
+/// and its result is dropped.
+pub struct DirScan {
+    pub dir: PathBuf,
+    started: Instant,
+    receiver: Receiver<Result<Vec<String>, MyError>>,
+}
+
+impl DirScan {
+    pub fn start(manager: &FileManager) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let manager = manager.clone();
+        let dir = manager.current_path();
+
+        thread::spawn(move || {
+            let _ = sender.send(manager.dir_contents());
+        });
+
+        Self { dir, started: Instant::now(), receiver }
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// The listing, if it's done within `timeout`.
+    pub fn wait(&self, timeout: Duration) -> Option<Result<Vec<String>, MyError>> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(result) => Some(result),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => Some(Err(MyError::FileError(format!("Listing {} stopped unexpectedly", self.dir.display())))),
+        }
+    }
+}
+
+/// Lists files from all subdirectories as paths relative to the current directory.
+#[derive(Debug, Clone, Copy)]
+pub struct FlatListing {
+    pub max_depth: usize,
+    pub max_entries: usize,
+}
+
+/// Logical paths keep symlinked directories in the path, so moving out returns
+/// to where you came from. Physical paths resolve every symlink on the way in.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum PathMode {
+    #[default]
+    Logical,
+    Physical,
 }
 
 pub struct FileMetadata {
     pub file_name: String,
     pub filetype: FileTypeEnum,
     pub size: u64,
+    pub mode: u32,
+    pub modified: Option<SystemTime>,
+    pub owner: Option<u32>,
+    /// Windows file attributes, always 0 elsewhere
+    pub attributes: u32,
+    pub link_target: Option<PathBuf>,
+    /// Unix only
+    pub inode: Option<InodeInfo>,
+    /// A zero-byte file, or a directory with nothing in it, hidden entries included
+    pub empty: bool,
 }
 
-#[derive(Debug)]
+/// Where an entry lives on its file system, for the extended Properties.
+pub struct InodeInfo {
+    pub inode: u64,
+    pub links: u64,
+    pub device: u64,
+    /// The preferred block size for I/O
+    pub block_size: u64,
+    /// Allocated 512-byte blocks, whatever the block size
+    pub blocks: u64,
+}
+
+/// What an entry looked like when an operation on it was started, so the operation
+/// can make sure it still acts on the same thing when it runs.
+pub struct FileSnapshot {
+    /// The entry's name in the listing
+    pub file_name: String,
+    pub path: PathBuf,
+    size: u64,
+    modified: Option<SystemTime>,
+    is_dir: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Staleness {
+    Unchanged,
+    Changed,
+    Vanished,
+}
+
+impl FileSnapshot {
+    pub fn take(file_name: String, path: PathBuf) -> Option<Self> {
+        let metadata = path.symlink_metadata().ok()?;
+
+        Some(Self {
+            file_name,
+            path,
+            size: metadata.len(),
+            modified: metadata.modified().ok(),
+            is_dir: metadata.is_dir(),
+        })
+    }
+
+    /// Stats the entry again and compares it with the snapshot.
+    pub fn staleness(&self) -> Staleness {
+        match self.path.symlink_metadata() {
+            Err(_) => Staleness::Vanished,
+            Ok(metadata) if metadata.len() == self.size
+                && metadata.modified().ok() == self.modified
+                && metadata.is_dir() == self.is_dir => Staleness::Unchanged,
+            Ok(_) => Staleness::Changed,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct DirStats {
+    pub entries: u64,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileTypeEnum {
     File,
     Directory,
     Symlink,
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+impl FileTypeEnum {
+    /// The type of an entry, after following symlinks.
+    #[cfg(unix)]
+    pub fn of(file_type: fs::FileType) -> Self {
+        use std::os::unix::fs::FileTypeExt;
+
+        if file_type.is_file() {
+            FileTypeEnum::File
+        } else if file_type.is_dir() {
+            FileTypeEnum::Directory
+        } else if file_type.is_fifo() {
+            FileTypeEnum::Fifo
+        } else if file_type.is_socket() {
+            FileTypeEnum::Socket
+        } else if file_type.is_block_device() {
+            FileTypeEnum::BlockDevice
+        } else if file_type.is_char_device() {
+            FileTypeEnum::CharDevice
+        } else {
+            FileTypeEnum::Symlink
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn of(file_type: fs::FileType) -> Self {
+        if file_type.is_file() {
+            FileTypeEnum::File
+        } else if file_type.is_dir() {
+            FileTypeEnum::Directory
+        } else {
+            FileTypeEnum::Symlink
+        }
+    }
+
+    /// FIFOs, sockets and device nodes, which can block or never end when read.
+    pub fn is_special(&self) -> bool {
+        matches!(self, FileTypeEnum::Fifo | FileTypeEnum::Socket | FileTypeEnum::BlockDevice | FileTypeEnum::CharDevice)
+    }
+
+    /// Appended to the name in the listing, like `ls -F` does.
+    pub fn indicator(&self) -> &'static str {
+        match self {
+            FileTypeEnum::Fifo => "|",
+            FileTypeEnum::Socket => "=",
+            FileTypeEnum::BlockDevice | FileTypeEnum::CharDevice => "#",
+            _ => "",
+        }
+    }
+}
+
+impl FileMetadata {
+    pub fn is_executable(&self) -> bool {
+        matches!(self.filetype, FileTypeEnum::File) && self.mode & 0o111 != 0
+    }
+
+    /// Bytes actually taken up on disk, as `du` counts them. Unix only.
+    pub fn allocated(&self) -> Option<u64> {
+        self.inode.as_ref().map(|inode| inode.blocks * 512)
+    }
+
+    /// Whether a block or more of the file was never allocated, like the holes of disk
+    /// images and preallocated downloads. Compressing file systems count too.
+    pub fn is_sparse(&self) -> bool {
+        match (&self.inode, self.filetype) {
+            (Some(inode), FileTypeEnum::File) => inode.blocks * 512 + inode.block_size.max(512) <= self.size,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for FileTypeEnum {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            FileTypeEnum::Fifo => write!(f, "FIFO"),
+            FileTypeEnum::BlockDevice => write!(f, "Block Device"),
+            FileTypeEnum::CharDevice => write!(f, "Character Device"),
+            _ => write!(f, "{:?}", self),
+        }
     }
 }
 
 impl Default for FileManager {
     fn default() -> Self {
-        Self {
-            curr_path: env::current_dir().unwrap()
-        }
+        let physical = env::current_dir().unwrap();
+
+        // $PWD keeps the symlinks the shell used to get here, as long as it still points at the same place
+        let logical = env::var_os("PWD")
+            .map(PathBuf::from)
+            .filter(|pwd| pwd.is_absolute() && fs::canonicalize(pwd).ok() == fs::canonicalize(&physical).ok());
+
+        FileManager::new(logical.unwrap_or(physical))
     }
 }
 
 impl FileManager {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            curr_path: path,
+            path_mode: PathMode::default(),
+            flat: None,
+            expanded: BTreeSet::new(),
+            filter: ListingFilter::default(),
+            sort: SortMode::default(),
+            descending: false,
+            show_hidden: true,
+            always_hidden: Vec::new(),
+            ignore: GlobSet::empty(),
+            show_ignored: false,
+        }
+    }
+
+    pub fn path_mode(&self) -> PathMode {
+        self.path_mode
+    }
+
+    pub fn set_path_mode(&mut self, path_mode: PathMode) {
+        self.path_mode = path_mode;
+        self.resolve_if_physical();
+    }
+
+    /// The canonical location of the current directory, if it differs from the displayed path.
+    pub fn resolved_path(&self) -> Option<PathBuf> {
+        fs::canonicalize(&self.curr_path)
+            .ok()
+            .filter(|resolved| *resolved != self.curr_path)
+    }
+
+    fn resolve_if_physical(&mut self) {
+        if self.path_mode == PathMode::Physical {
+            if let Ok(resolved) = fs::canonicalize(&self.curr_path) {
+                self.curr_path = resolved;
+            }
+        }
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.flat.is_some()
+    }
+
+    pub fn set_flat(&mut self, flat: Option<FlatListing>) {
+        self.flat = flat;
+    }
+
+    pub fn filter(&self) -> &ListingFilter {
+        &self.filter
+    }
+
+    pub fn filter_mut(&mut self) -> &mut ListingFilter {
+        &mut self.filter
+    }
+
+    pub fn sort(&self) -> (SortMode, bool) {
+        (self.sort, self.descending)
+    }
+
+    pub fn set_sort(&mut self, sort: SortMode, descending: bool) {
+        self.sort = sort;
+        self.descending = descending;
+    }
+
+    pub fn shows_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) {
+        self.show_hidden = show_hidden;
+    }
+
+    pub fn set_always_hidden(&mut self, names: Vec<String>) {
+        self.always_hidden = names;
+    }
+
+    fn is_always_hidden(&self, name: &str) -> bool {
+        self.always_hidden.iter().any(|hidden| hidden == name)
+    }
+
+    /// Sets the globs of entries to leave out, like `*.pyc` or `__pycache__`.
+    pub fn set_ignore(&mut self, patterns: &[String]) -> Result<(), MyError> {
+        let mut builder = GlobSetBuilder::new();
+
+        for pattern in patterns {
+            let glob = Glob::new(pattern).map_err(|e| MyError::ConfigError(format!("Invalid ignore pattern {}: {}", pattern, e)))?;
+            builder.add(glob);
+        }
+
+        self.ignore = builder.build().map_err(|e| MyError::ConfigError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Whether there are ignore patterns, regardless of whether they're applied.
+    pub fn has_ignore(&self) -> bool {
+        !self.ignore.is_empty()
+    }
+
+    pub fn shows_ignored(&self) -> bool {
+        self.show_ignored
+    }
+
+    pub fn set_show_ignored(&mut self, show_ignored: bool) {
+        self.show_ignored = show_ignored;
+    }
+
+    fn is_ignored(&self, name: &str) -> bool {
+        !self.show_ignored && self.ignore.is_match(name)
+    }
+
     pub fn dir_contents(&self) -> Result<Vec<String>, MyError> {
-        let entries = fs::read_dir(&self.curr_path)
-            .map_err(|_| MyError::FileError("Couldn't fetch directory entries".to_string()))?;
+        if let Some(flat) = self.flat {
+            return self.flat_contents(flat);
+        }
+
+        let names = self.read_names(&self.curr_path)?;
+
+        if self.expanded.is_empty() {
+            return Ok(names);
+        }
+
+        let mut items = Vec::new();
 
-        Ok(entries
+        for name in names {
+            self.push_tree_entry(name, &mut items);
+        }
+
+        Ok(items)
+    }
+
+    fn read_names(&self, dir: &Path) -> Result<Vec<String>, MyError> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| MyError::io(e, dir))?;
+
+        let mut names = entries
             .into_iter()
             .filter_map(|entry| entry.ok().and_then(|e| self.file_filter(e)))
-            .collect::<Vec<String>>())
+            .filter(|name| self.filter.matches(&dir.join(name)))
+            .collect::<Vec<String>>();
+
+        self.sort_names(dir, &mut names);
+
+        Ok(names)
+    }
+
+    fn sort_names(&self, dir: &Path, names: &mut [String]) {
+        names.sort_by_cached_key(|name| name.to_lowercase());
+
+        // The sorts are stable, so entries comparing equal stay in name order
+        match self.sort {
+            SortMode::Name => {},
+            SortMode::Size => names.sort_by_cached_key(|name| {
+                fs::symlink_metadata(dir.join(name)).map(|metadata| metadata.len()).unwrap_or(0)
+            }),
+            SortMode::Modified => names.sort_by_cached_key(|name| {
+                fs::symlink_metadata(dir.join(name)).and_then(|metadata| metadata.modified()).ok()
+            }),
+            SortMode::Extension => names.sort_by_cached_key(|name| {
+                Path::new(name).extension().map(|extension| extension.to_string_lossy().to_lowercase())
+            }),
+        }
+
+        if self.descending {
+            names.reverse();
+        }
+    }
+
+    /// Entries of the parent directory, sorted like the listing but unfiltered,
+    /// along with the name of the current directory in it.
+    pub fn parent_contents(&self) -> Option<(Vec<String>, String)> {
+        let parent = self.curr_path.parent()?;
+        let current = self.curr_path.file_name()?.to_string_lossy().to_string();
+
+        let mut names = fs::read_dir(parent)
+            .ok()?
+            .filter_map(|entry| entry.ok().and_then(|e| self.file_filter(e)))
+            .collect::<Vec<String>>();
+
+        self.sort_names(parent, &mut names);
+
+        Some((names, current))
+    }
+
+    /// Directories below the current one that hold nothing but other empty directories.
+    /// Only the outermost of each such tree is listed, since removing it clears the rest. Hidden directories
+    /// aren't looked into while hidden files aren't shown, but anything in them, like
+    /// a hidden file, still counts as content.
+    pub fn empty_directories(&self) -> Vec<PathBuf> {
+        let mut empty = Vec::new();
+
+        if !self.collect_empty(&self.curr_path, &mut empty) {
+            empty.sort();
+            return empty;
+        }
+
+        // The current directory is empty as a whole, so its children are what's listed
+        fs::read_dir(&self.curr_path)
+            .map(|entries| entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Symlinks below the current directory whose target is gone, sorted by path. Ignore
+    /// files don't apply, since dangling links are often in what's ignored, like build output.
+    pub fn broken_links(&self) -> Vec<PathBuf> {
+        let show_hidden = self.show_hidden;
+        let always_hidden = self.always_hidden.clone();
+        let walker = WalkBuilder::new(&self.curr_path)
+            .standard_filters(false)
+            .hidden(!self.show_hidden)
+            .filter_entry(move |entry| {
+                entry.file_name() != ".git"
+                    && !always_hidden.iter().any(|hidden| entry.file_name() == hidden.as_str())
+                    && (show_hidden || !has_hidden_attribute(|| entry.metadata().ok()))
+            })
+            .build();
+
+        let mut links = walker
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path_is_symlink() && fs::metadata(entry.path()).is_err())
+            .map(|entry| entry.into_path())
+            .collect::<Vec<PathBuf>>();
+
+        links.sort();
+        links
+    }
+
+    // Whether `dir` is empty through and through; if not, its empty subdirectories are pushed instead
+    fn collect_empty(&self, dir: &Path, empty: &mut Vec<PathBuf>) -> bool {
+        let Ok(entries) = fs::read_dir(dir) else { return false };
+        let mut children = Vec::new();
+        let mut has_content = false;
+
+        for entry in entries {
+            let Ok(entry) = entry else {
+                has_content = true;
+                continue;
+            };
+
+            let path = entry.path();
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+            if is_dir && self.file_filter(entry).is_some() && self.collect_empty(&path, empty) {
+                children.push(path);
+            } else {
+                has_content = true;
+            }
+        }
+
+        if has_content {
+            empty.append(&mut children);
+        }
+
+        !has_content
+    }
+
+    // Expanded directories are followed by their entries as relative paths, e.g. `src/main.rs`
+    fn push_tree_entry(&self, relative: String, items: &mut Vec<String>) {
+        let children = match self.expanded.contains(Path::new(&relative)) {
+            true => self.read_names(&self.curr_path.join(&relative)).unwrap_or_default(),
+            false => Vec::new(),
+        };
+
+        items.push(relative.clone());
+
+        for child in children {
+            self.push_tree_entry(format!("{}/{}", relative, child), items);
+        }
+    }
+
+    pub fn is_expanded(&self, relative: &str) -> bool {
+        self.expanded.contains(Path::new(relative))
+    }
+
+    /// Expands a directory in place. Returns false for anything that isn't a directory.
+    pub fn expand(&mut self, relative: &str) -> bool {
+        if !self.curr_path.join(relative).is_dir() {
+            return false;
+        }
+
+        self.expanded.insert(PathBuf::from(relative))
+    }
+
+    /// Collapses a directory along with everything expanded below it.
+    pub fn collapse(&mut self, relative: &str) {
+        let relative = Path::new(relative);
+        self.expanded.retain(|path| !path.starts_with(relative));
+    }
+
+    fn flat_contents(&self, flat: FlatListing) -> Result<Vec<String>, MyError> {
+        if !self.curr_path.is_dir() {
+            return Err(MyError::NotADirectory(self.curr_path.clone()));
+        }
+
+        let show_hidden = self.show_hidden;
+        let always_hidden = self.always_hidden.clone();
+        let ignore = match self.show_ignored {
+            true => GlobSet::empty(),
+            false => self.ignore.clone(),
+        };
+        let walker = WalkBuilder::new(&self.curr_path)
+            .max_depth(Some(flat.max_depth))
+            .hidden(!self.show_hidden)
+            .require_git(false)
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .filter_entry(move |entry| {
+                entry.file_name() != ".git"
+                    && !always_hidden.iter().any(|hidden| entry.file_name() == hidden.as_str())
+                    && !ignore.is_match(entry.file_name())
+                    && (show_hidden || !has_hidden_attribute(|| entry.metadata().ok()))
+            })
+            .build();
+
+        let mut items = walker
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|file_type| !file_type.is_dir()))
+            .filter(|entry| self.filter.matches(entry.path()))
+            .filter_map(|entry| {
+                let relative = entry.path().strip_prefix(&self.curr_path).ok()?;
+                relative.to_str().map(str::to_string)
+            })
+            .take(flat.max_entries)
+            .collect::<Vec<String>>();
+
+        self.sort_names(&self.curr_path, &mut items);
+
+        Ok(items)
     }
 
     pub fn get_current_path(&self) -> String {
@@ -54,41 +624,85 @@ impl FileManager {
     }
 
     pub fn next_path(&mut self, end_dir: String) {
+        self.expanded.clear();
         self.curr_path.push(end_dir);
+        self.resolve_if_physical();
     }
 
     pub fn previous_path(&mut self) {
+        self.expanded.clear();
         self.curr_path.pop();
     }
 
+    pub fn change_path(&mut self, path: PathBuf) {
+        self.expanded.clear();
+        self.curr_path = path;
+        self.resolve_if_physical();
+    }
+
+    pub fn current_path(&self) -> PathBuf {
+        self.curr_path.clone()
+    }
+
+    /// The nearest directory above the current one containing any of `markers`.
+    /// The search starts at the parent, so at a project root it finds the enclosing one.
+    pub fn project_root(&self, markers: &[String]) -> Option<PathBuf> {
+        self.curr_path
+            .ancestors()
+            .skip(1)
+            .find(|dir| markers.iter().any(|marker| dir.join(marker).exists()))
+            .map(Path::to_path_buf)
+    }
+
+    /// Resolves a typed directory against the current one, after expanding `~` and
+    /// environment variables.
+    pub fn resolve_dir(&self, target: &str) -> Result<PathBuf, MyError> {
+        let path = normalize_path(&self.curr_path.join(expansion::expand(target)?));
+
+        if path.is_dir() {
+            Ok(path)
+        } else {
+            Err(MyError::FileError(format!("No such directory {}", target)))
+        }
+    }
+
     fn file_filter(&self, entry: DirEntry) -> Option<String> {
-        entry.file_name().into_string().ok()
+        entry.file_name()
+            .into_string()
+            .ok()
+            .filter(|name| !self.is_always_hidden(name) && !self.is_ignored(name))
+            .filter(|name| self.show_hidden || !(name.starts_with('.') || has_hidden_attribute(|| entry.metadata().ok())))
     }
 
     pub fn get_file_path(&self, file_name: String) -> Result<String, MyError> {
         match self.curr_path.as_path().join(file_name.clone())
             .clone().into_os_string().into_string() {
             Ok(path) => Ok(path),
-            Err(_) => Err(MyError::FileError("Incorrect path or permissions".to_string())),
+            Err(path) => Err(MyError::FileError(format!("{} isn't valid UTF-8", PathBuf::from(path).display()))),
         }
     }
 
     pub fn get_metadata(&self, file_name: String) -> Option<FileMetadata> {
         let path = self.curr_path.as_path().join(file_name.clone());
+        let link_target = fs::read_link(&path).ok();
+
+        if let Ok(metadata) = fs::metadata(&path) {
+            let empty = match metadata.is_dir() {
+                true => fs::read_dir(&path).is_ok_and(|mut entries| entries.next().is_none()),
+                false => metadata.is_file() && metadata.len() == 0,
+            };
 
-        if let Ok(metadata) = fs::metadata(path) {
             Some(FileMetadata {
                 file_name,
-                filetype: {
-                    if metadata.is_file() {
-                        FileTypeEnum::File
-                    } else if metadata.is_dir() {
-                        FileTypeEnum::Directory
-                    } else {
-                        FileTypeEnum::Symlink
-                    }
-                },
+                filetype: FileTypeEnum::of(metadata.file_type()),
                 size: metadata.len(),
+                mode: permissions::mode_of(&metadata),
+                modified: metadata.modified().ok(),
+                owner: permissions::owner_of(&metadata),
+                attributes: permissions::attributes_of(&metadata),
+                link_target,
+                inode: inode_info(&metadata),
+                empty,
             })
         } else {
             None
@@ -96,46 +710,316 @@ impl FileManager {
     }
 
     pub fn delete(&self, file_path: String, file_type: FileTypeEnum) -> Result<(), MyError>{
-        match file_type {
-            FileTypeEnum::File | FileTypeEnum::Symlink => {
-                if let Err(_) = fs::remove_file(file_path) {
-                    return Err(MyError::FileError("Incorrect path or permissions".to_string()));
-                }
-            },
-            FileTypeEnum::Directory => {
-                if let Err(_) = fs::remove_dir(file_path) {
-                    return Err(MyError::FileError("Incorrect path or permissions".to_string()));
-                }
-            },
+        let result = match file_type {
+            FileTypeEnum::Directory => fs::remove_dir_all(&file_path),
+            _ => fs::remove_file(&file_path),
         };
 
-        Ok(())
+        result.map_err(|e| MyError::io(e, file_path))
     }
 
+    /// Renames or moves within the file system, refusing to replace an existing entry.
     pub fn rename(&self, file_path: String, new_file_path: String) -> Result<(), MyError> {
-        if let Err(_) = fs::rename(file_path, new_file_path) {
-            return Err(MyError::FileError("Insufficient privilages".to_string()));
+        if is_taken(Path::new(&file_path), Path::new(&new_file_path)) {
+            return Err(MyError::AlreadyExists(PathBuf::from(new_file_path)));
         }
 
-        Ok(())
+        // The destination is blamed when it's the one that's wrong, otherwise the source
+        fs::rename(&file_path, &new_file_path).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound if Path::new(&file_path).symlink_metadata().is_ok() => MyError::io(e, new_file_path),
+            io::ErrorKind::NotADirectory | io::ErrorKind::CrossesDevices => MyError::io(e, new_file_path),
+            _ => MyError::io(e, file_path),
+        })
+    }
+
+    /// Creates a new file holding `contents`, never overwriting an existing one.
+    pub fn create_with_contents(&self, file_path: String, contents: &[u8], mode: Option<u32>) -> Result<(), MyError> {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file_path)
+            .and_then(|mut file| file.write_all(contents))
+            .map_err(|e| MyError::io(e, &file_path))?;
+
+        match mode {
+            Some(mode) => permissions::set_created_mode(Path::new(&file_path), mode),
+            None => Ok(()),
+        }
+    }
+
+    /// Creates a file, or a directory for a path ending in `/`. Without a `mode` it gets
+    /// the default one less the umask, as any other program would.
+    pub fn create(&self, file_path: String, mode: Option<u32>) -> Result<(), MyError> {
+        if file_path.is_empty() {
+            return Err(MyError::FileError("Input is empty".to_string()));
+        }
+
+        let result = if file_path.ends_with('/') {
+            fs::create_dir(&file_path)
+        } else {
+            OpenOptions::new().write(true).create_new(true).open(&file_path).map(|_| ())
+        };
+
+        result.map_err(|e| MyError::io(e, &file_path))?;
+
+        match mode {
+            Some(mode) => permissions::set_created_mode(Path::new(&file_path), mode),
+            None => Ok(()),
+        }
+    }
+}
+
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                normalized.pop();
+            },
+            other => normalized.push(other),
+        }
+    }
+
+    normalized
+}
+
+// Device names Windows won't create files with, whatever the extension
+const WINDOWS_RESERVED: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL",
+    "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9",
+    "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Checks a name typed for a new or renamed entry: a single component that stays in the
+/// directory it's typed in and that the platform accepts.
+pub fn validate_name(name: &str) -> Result<(), MyError> {
+    let invalid = |reason: &str| Err(MyError::FileError(reason.to_string()));
+
+    if name.is_empty() {
+        return invalid("The name is empty");
+    }
+
+    if name.contains('\0') {
+        return invalid("Names can't contain NUL");
+    }
+
+    if name.contains('/') || (cfg!(windows) && name.contains('\\')) {
+        return invalid("Names can't contain / and stay in the current directory");
+    }
+
+    if name == "." || name == ".." {
+        return invalid("`.` and `..` are taken by the directories themselves");
+    }
+
+    if cfg!(windows) {
+        let stem = name.split('.').next().unwrap_or_default().trim_end().to_uppercase();
+
+        if WINDOWS_RESERVED.contains(&stem.as_str()) {
+            return invalid("The name is reserved on Windows");
+        }
+
+        if name.chars().any(|c| c.is_control() || "<>:\"|?*".contains(c)) {
+            return invalid("Names can't contain control characters or any of < > : \" | ? *");
+        }
+
+        if name.ends_with('.') || name.ends_with(' ') {
+            return invalid("Names can't end with a dot or space on Windows");
+        }
+    }
+
+    Ok(())
+}
+
+/// Whether moving `source` to `destination` would replace something. Changing only the case
+/// of a name on a case-insensitive file system finds the source itself, which doesn't count.
+pub fn is_taken(source: &Path, destination: &Path) -> bool {
+    destination.symlink_metadata().is_ok() && !same_entry(source, destination)
+}
+
+#[cfg(unix)]
+fn same_entry(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (a.symlink_metadata(), b.symlink_metadata()) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_entry(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn inode_info(metadata: &fs::Metadata) -> Option<InodeInfo> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(InodeInfo {
+        inode: metadata.ino(),
+        links: metadata.nlink(),
+        device: metadata.dev(),
+        block_size: metadata.blksize(),
+        blocks: metadata.blocks(),
+    })
+}
+
+#[cfg(not(unix))]
+fn inode_info(_metadata: &fs::Metadata) -> Option<InodeInfo> {
+    None
+}
+
+/// The first of `name (1).ext`, `name (2).ext`, ... that neither exists nor is in `reserved`,
+/// or `path` itself if it's free. Used to keep both files when names collide.
+pub fn unique_path(path: &Path, reserved: &HashSet<PathBuf>) -> PathBuf {
+    let taken = |candidate: &Path| candidate.symlink_metadata().is_ok() || reserved.contains(candidate);
+
+    if !taken(path) {
+        return path.to_path_buf();
+    }
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().map(|extension| format!(".{}", extension.to_string_lossy())).unwrap_or_default();
+
+    (1..)
+        .map(|number| path.with_file_name(format!("{} ({}){}", stem, number, extension)))
+        .find(|candidate| !taken(candidate))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Points the symlink `link` at `target` by renaming a new link over it, so there's
+/// never a moment without the link. `target` is stored as given, relative or not.
+#[cfg(unix)]
+pub fn retarget_symlink(link: &Path, target: &Path) -> Result<(), MyError> {
+    let metadata = link.symlink_metadata().map_err(|e| MyError::io(e, link))?;
+
+    if !metadata.file_type().is_symlink() {
+        return Err(MyError::FileError(format!("{} is not a symlink", link.display())));
     }
 
-    pub fn create(&self, file_path: String) -> Result<(), MyError> {
-        if let Some(last_char) = file_path.chars().last() {
-            if last_char == '/' {
-                if let Err(_) = fs::create_dir(file_path) {
-                    return Err(MyError::FileError("Insufficient privilages or path already exists".to_string()));
-                }
+    let mut temporary = link.as_os_str().to_owned();
+    temporary.push(".retarget");
+    let temporary = unique_path(Path::new(&temporary), &HashSet::new());
+
+    std::os::unix::fs::symlink(target, &temporary).map_err(|e| MyError::io(e, &temporary))?;
+
+    fs::rename(&temporary, link).map_err(|e| {
+        let _ = fs::remove_file(&temporary);
+        MyError::io(e, link)
+    })
+}
+
+#[cfg(not(unix))]
+pub fn retarget_symlink(_link: &Path, _target: &Path) -> Result<(), MyError> {
+    Err(MyError::FileError("Editing link targets is only supported on Unix".to_string()))
+}
+
+/// Counts the entries below `path` and their total size, stopping early
+/// once either limit is exceeded.
+pub fn dir_stats(path: &Path, max_entries: u64, max_size: u64) -> DirStats {
+    let mut stats = DirStats::default();
+
+    for entry in WalkDir::new(path).min_depth(1).into_iter().filter_map(|entry| entry.ok()) {
+        stats.entries += 1;
+
+        if let Ok(metadata) = entry.metadata() {
+            if metadata.is_file() {
+                stats.size += metadata.len();
+            }
+        }
+
+        if stats.entries > max_entries || stats.size > max_size {
+            break;
+        }
+    }
+
+    stats
+}
+
+/// The combined size of a selection, for checking whether it fits somewhere before copying.
+#[derive(Default)]
+pub struct SelectionSize {
+    pub bytes: u64,
+    pub files: u64,
+    pub directories: u64,
+    pub largest_file: u64,
+    /// Entries that couldn't be read, whose size is missing from the total
+    pub unreadable: u64,
+}
+
+/// Adds up everything in and below `paths`, counting each entry once even when a marked
+/// directory contains other marked entries. Symlinks count as themselves, not their target.
+pub fn selection_size(paths: &[PathBuf], progress: &Progress) -> SelectionSize {
+    let mut size = SelectionSize::default();
+    progress.count_items();
+
+    let roots = paths
+        .iter()
+        .filter(|path| !paths.iter().any(|other| other != *path && path.starts_with(other)));
+
+    for root in roots {
+        for entry in WalkDir::new(root) {
+            progress.add(1);
+
+            let Ok(metadata) = entry.and_then(|entry| entry.metadata()) else {
+                size.unreadable += 1;
+                continue;
+            };
+
+            if metadata.is_dir() {
+                size.directories += 1;
             } else {
-                if let Err(_) = fs::write(file_path.clone(), "") {
-                    return Err(MyError::FileError("Insufficient privilages or path already exists".to_string()));
-                }
+                size.files += 1;
+                size.bytes += metadata.len();
+                size.largest_file = size.largest_file.max(metadata.len());
             }
-        } else {
-            return Err(MyError::FileError("Input is empty".to_string()));
         }
+    }
 
-        Ok(())
+    size
+}
+
+/// Streams `source` through `convert` into a new `destination` with the same permissions,
+/// removing `source` afterwards unless `keep` is set. A half-written destination is
+/// removed on failure and an existing one is never overwritten.
+pub fn transcode<F>(source: &Path, destination: &Path, keep: bool, convert: F) -> Result<(), MyError>
+where
+    F: FnOnce(File, File) -> io::Result<()>,
+{
+    let input = File::open(source)
+        .map_err(|e| MyError::io(e, source))?;
+    let permissions = input
+        .metadata()
+        .map_err(|e| MyError::io(e, source))?
+        .permissions();
+
+    let output = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(destination)
+        .map_err(|e| MyError::io(e, destination))?;
+
+    if let Err(e) = convert(input, output) {
+        let _ = fs::remove_file(destination);
+        return Err(MyError::FileError(format!("Couldn't convert {}: {}", source.display(), e)));
     }
+
+    let _ = fs::set_permissions(destination, permissions);
+
+    if !keep {
+        fs::remove_file(source).map_err(|e| MyError::io(e, source))?;
+    }
+
+    Ok(())
 }
 
+/// Whether Windows marks the entry hidden or system. The metadata is only read there,
+/// elsewhere hidden entries are only those starting with a dot.
+fn has_hidden_attribute(metadata: impl FnOnce() -> Option<fs::Metadata>) -> bool {
+    cfg!(windows) && metadata().is_some_and(|metadata| permissions::is_hidden_attribute(permissions::attributes_of(&metadata)))
+}
@@ -0,0 +1,81 @@
+use std::{fs, io, iter, path::{Path, PathBuf}, str::FromStr};
+
+use age::{secrecy::SecretString, Decryptor, Encryptor, Identity, IdentityFile, Recipient};
+
+use crate::{directory, my_errors::MyError};
+
+pub const ENCRYPTED_EXTENSION: &str = "age";
+
+/// What a file is encrypted to: a passphrase, or an age public key (`age1...`).
+pub enum EncryptionKey {
+    Passphrase(String),
+    Recipient(String),
+}
+
+/// Encrypts `path` into `path.age` next to it and returns the new path. The original is kept.
+pub fn encrypt(path: &Path, key: EncryptionKey) -> Result<PathBuf, MyError> {
+    let encryptor = match key {
+        EncryptionKey::Passphrase(passphrase) if passphrase.is_empty() => {
+            return Err(MyError::FileError("Passphrase is empty".to_string()));
+        },
+        EncryptionKey::Passphrase(passphrase) => Encryptor::with_user_passphrase(SecretString::from(passphrase)),
+        EncryptionKey::Recipient(recipient) => {
+            let recipient = age::x25519::Recipient::from_str(recipient.trim())
+                .map_err(|_| MyError::FileError(format!("Invalid age recipient {}", recipient)))?;
+
+            Encryptor::with_recipients(iter::once(&recipient as &dyn Recipient))
+                .map_err(|e| MyError::FileError(e.to_string()))?
+        },
+    };
+
+    let mut destination = path.as_os_str().to_owned();
+    destination.push(".");
+    destination.push(ENCRYPTED_EXTENSION);
+    let destination = PathBuf::from(destination);
+
+    directory::transcode(path, &destination, true, |mut source, destination| {
+        let mut writer = encryptor.wrap_output(destination)?;
+        io::copy(&mut source, &mut writer)?;
+        writer.finish().map(|_| ())
+    })?;
+
+    Ok(destination)
+}
+
+/// Whether an age file was encrypted with a passphrase rather than to a recipient.
+pub fn is_passphrase_encrypted(path: &Path) -> bool {
+    fs::File::open(path)
+        .ok()
+        .and_then(|file| Decryptor::new(file).ok())
+        .is_some_and(|decryptor| decryptor.is_scrypt())
+}
+
+/// Decrypts a `.age` file next to itself, dropping the extension, and returns the new path.
+/// `secret` is the passphrase for passphrase-encrypted files, otherwise the path to an identity file.
+pub fn decrypt(path: &Path, secret: String) -> Result<PathBuf, MyError> {
+    if path.extension().is_none_or(|extension| extension != ENCRYPTED_EXTENSION) {
+        return Err(MyError::FileError("Not an age file".to_string()));
+    }
+
+    let identities: Vec<Box<dyn Identity>> = if is_passphrase_encrypted(path) {
+        vec![Box::new(age::scrypt::Identity::new(SecretString::from(secret)))]
+    } else {
+        IdentityFile::from_file(secret.clone())
+            .map_err(|e| MyError::io(e, &secret))?
+            .into_identities()
+            .map_err(|e| MyError::FileError(e.to_string()))?
+    };
+
+    let destination = path.with_extension("");
+
+    directory::transcode(path, &destination, true, |source, mut destination| {
+        let decryptor = Decryptor::new(source).map_err(io::Error::other)?;
+        let mut reader = decryptor
+            .decrypt(identities.iter().map(|identity| identity.as_ref()))
+            .map_err(io::Error::other)?;
+
+        io::copy(&mut reader, &mut destination).map(|_| ())
+    })?;
+
+    Ok(destination)
+}
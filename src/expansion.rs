@@ -0,0 +1,76 @@
+use std::{env, path::PathBuf};
+
+use crate::my_errors::MyError;
+
+/// Expands a typed path the way a shell would: a leading `~` or `~user`, and `$VAR` or
+/// `${VAR}` anywhere. Unlike a shell, an unset variable or unknown user is an error, so
+/// a typo can't quietly point the path somewhere else. A `$` not followed by a name is
+/// kept as it is.
+pub fn expand(input: &str) -> Result<String, MyError> {
+    let expanded = expand_tilde(input)?;
+    let mut result = String::with_capacity(expanded.len());
+    let mut rest = expanded.as_str();
+
+    while let Some(index) = rest.find('$') {
+        result.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+
+        let (name, remainder) = match rest.strip_prefix('{') {
+            Some(braced) => match braced.split_once('}') {
+                Some((name, remainder)) => (name, remainder),
+                None => return Err(MyError::FileError(format!("Missing }} in {}", input))),
+            },
+            None => {
+                let end = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+                (&rest[..end], &rest[end..])
+            },
+        };
+
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            result.push('$');
+            continue;
+        }
+
+        match env::var(name) {
+            Ok(value) => result.push_str(&value),
+            Err(_) => return Err(MyError::FileError(format!("${} isn't set", name))),
+        }
+
+        rest = remainder;
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
+fn expand_tilde(input: &str) -> Result<String, MyError> {
+    let Some(rest) = input.strip_prefix('~') else { return Ok(input.to_string()) };
+    let (user, path) = rest.split_at(rest.find(['/', '\\']).unwrap_or(rest.len()));
+
+    let home = match user {
+        "" => dirs::home_dir().ok_or_else(|| MyError::FileError("No home directory".to_string()))?,
+        user => user_home(user).ok_or_else(|| MyError::FileError(format!("No user {}", user)))?,
+    };
+
+    Ok(format!("{}{}", home.to_string_lossy(), path))
+}
+
+#[cfg(unix)]
+fn user_home(user: &str) -> Option<PathBuf> {
+    use std::{ffi::{CStr, CString, OsStr}, os::unix::ffi::OsStrExt};
+
+    let name = CString::new(user).ok()?;
+    let entry = unsafe { libc::getpwnam(name.as_ptr()) };
+
+    if entry.is_null() {
+        return None;
+    }
+
+    let dir = unsafe { CStr::from_ptr((*entry).pw_dir) };
+    Some(PathBuf::from(OsStr::from_bytes(dir.to_bytes())))
+}
+
+#[cfg(not(unix))]
+fn user_home(_user: &str) -> Option<PathBuf> {
+    None
+}
@@ -0,0 +1,82 @@
+use std::{path::{Path, PathBuf}, process::{Command, Stdio}, thread};
+
+use crate::my_errors::MyError;
+
+/// A shell command run on behalf of the user, e.g. an opener.
+/// `terminal` commands take over the terminal, so the UI has to be suspended around them.
+pub struct ExternalCommand {
+    pub script: String,
+    pub args: Vec<String>,
+    pub terminal: bool,
+    pub detached: bool,
+    pub dir: Option<PathBuf>,
+    /// Whether a successful exit should be reported too, not just failures
+    pub report_exit: bool,
+}
+
+impl ExternalCommand {
+    /// Builds a command from a template where `{}` stands for the file path.
+    pub fn for_file(template: &str, path: &Path, terminal: bool, detached: bool) -> Self {
+        // The path is passed as $1 so it never has to be quoted into the command string
+        Self {
+            script: template.replace("{}", "\"$1\""),
+            args: vec![path.to_string_lossy().to_string()],
+            terminal,
+            detached,
+            dir: None,
+            report_exit: false,
+        }
+    }
+
+    /// Runs an executable from `dir`. `args` are split by the shell as typed, and the
+    /// terminal waits for Enter afterwards so the program's output can still be read.
+    pub fn executable(path: &Path, args: &str, dir: &Path) -> Self {
+        let script = format!(
+            "\"$1\" {}\nstatus=$?\nprintf '\\n[exited with code %d, press Enter to return]' \"$status\"\nread -r _\nexit \"$status\"",
+            args,
+        );
+
+        Self {
+            script,
+            args: vec![path.to_string_lossy().to_string()],
+            terminal: true,
+            detached: false,
+            dir: Some(dir.to_path_buf()),
+            report_exit: true,
+        }
+    }
+
+    pub fn run(&self) -> Result<(), MyError> {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(&self.script).arg("sh").args(&self.args);
+
+        if let Some(dir) = &self.dir {
+            command.current_dir(dir);
+        }
+
+        if !self.terminal {
+            command.stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+        }
+
+        if self.detached {
+            let mut child = command
+                .spawn()
+                .map_err(|e| MyError::CommandError(format!("Couldn't start command: {}", e)))?;
+
+            // Reap the child in the background so it doesn't linger as a zombie
+            thread::spawn(move || child.wait());
+
+            return Ok(());
+        }
+
+        let status = command
+            .status()
+            .map_err(|e| MyError::CommandError(format!("Couldn't start command: {}", e)))?;
+
+        match status.code() {
+            Some(0) => Ok(()),
+            Some(code) => Err(MyError::CommandError(format!("Command exited with code {}", code))),
+            None => Err(MyError::CommandError("Command was terminated by a signal".to_string())),
+        }
+    }
+}
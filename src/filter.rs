@@ -0,0 +1,217 @@
+use std::{fmt, path::Path, time::SystemTime};
+
+use chrono::{Datelike, Days, Local, NaiveDate, TimeZone};
+use serde::{Deserialize, Serialize};
+
+const DOCUMENT_EXTENSIONS: &[&str] = &[
+    "pdf", "doc", "docx", "odt", "rtf", "txt", "md", "tex", "epub", "xls", "xlsx", "ods", "csv", "ppt", "pptx", "odp",
+];
+
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "tar", "gz", "tgz", "bz2", "xz", "zst", "7z", "rar"];
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "rs", "c", "h", "cpp", "hpp", "cc", "go", "py", "js", "ts", "jsx", "tsx", "java", "kt", "rb", "php", "lua", "sh",
+    "bash", "zsh", "fish", "html", "css", "scss", "json", "toml", "yaml", "yml", "xml", "sql", "rhai",
+];
+
+/// Preset groups of file types, cycled through with `<c>`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Images,
+    Videos,
+    Audio,
+    Documents,
+    Archives,
+    Code,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format!("{:?}", self).to_lowercase())
+    }
+}
+
+impl Category {
+    const ALL: [Category; 6] = [
+        Category::Images,
+        Category::Videos,
+        Category::Audio,
+        Category::Documents,
+        Category::Archives,
+        Category::Code,
+    ];
+
+    /// The category after this one, or None after the last to turn the filter off.
+    pub fn next(current: Option<Category>) -> Option<Category> {
+        match current {
+            None => Some(Category::ALL[0]),
+            Some(category) => {
+                let index = Category::ALL.iter().position(|c| *c == category).unwrap_or(0);
+                Category::ALL.get(index + 1).copied()
+            },
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        let top_level = |name: &str| mime_guess::from_path(path).first().is_some_and(|mime| mime.type_() == name);
+
+        match self {
+            Category::Images => top_level("image"),
+            Category::Videos => top_level("video"),
+            Category::Audio => top_level("audio"),
+            Category::Documents => has_extension(path, DOCUMENT_EXTENSIONS),
+            Category::Archives => has_extension(path, ARCHIVE_EXTENSIONS),
+            Category::Code => has_extension(path, CODE_EXTENSIONS),
+        }
+    }
+}
+
+/// How recently an entry must have been modified to stay listed, cycled through with `<w>`.
+/// Periods are calendar ones in local time, so "week" starts on Monday.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Recency {
+    Today,
+    Week,
+    Month,
+}
+
+impl fmt::Display for Recency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Recency::Today => write!(f, "modified today"),
+            Recency::Week => write!(f, "modified this week"),
+            Recency::Month => write!(f, "modified this month"),
+        }
+    }
+}
+
+impl Recency {
+    const ALL: [Recency; 3] = [Recency::Today, Recency::Week, Recency::Month];
+
+    /// The period after this one, or None after the last to turn the filter off.
+    pub fn next(current: Option<Recency>) -> Option<Recency> {
+        match current {
+            None => Some(Recency::ALL[0]),
+            Some(recency) => {
+                let index = Recency::ALL.iter().position(|r| *r == recency).unwrap_or(0);
+                Recency::ALL.get(index + 1).copied()
+            },
+        }
+    }
+
+    /// The local midnight the period started at.
+    fn start(&self) -> SystemTime {
+        let today = Local::now().date_naive();
+
+        let day = match self {
+            Recency::Today => Some(today),
+            Recency::Week => today.checked_sub_days(Days::new(today.weekday().num_days_from_monday() as u64)),
+            Recency::Month => NaiveDate::from_ymd_opt(today.year(), today.month(), 1),
+        };
+
+        // A midnight skipped by a clock change falls back to the start of the epoch, showing more rather than less
+        day.and_then(|day| day.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+            .map(SystemTime::from)
+            .unwrap_or(SystemTime::UNIX_EPOCH)
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        path.symlink_metadata()
+            .and_then(|metadata| metadata.modified())
+            .is_ok_and(|modified| modified >= self.start())
+    }
+}
+
+/// Limits the listing to one category or to a list of extensions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum KindFilter {
+    Category(Category),
+    Extensions(Vec<String>),
+}
+
+impl fmt::Display for KindFilter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KindFilter::Category(category) => write!(f, "{}", category),
+            KindFilter::Extensions(extensions) => write!(f, ".{}", extensions.join(" .")),
+        }
+    }
+}
+
+impl KindFilter {
+    /// Parses extensions separated by commas or spaces, with or without the leading dot.
+    pub fn parse_extensions(input: &str) -> Option<KindFilter> {
+        let extensions = input
+            .split([',', ' '])
+            .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+            .filter(|ext| !ext.is_empty())
+            .collect::<Vec<String>>();
+
+        match extensions.is_empty() {
+            true => None,
+            false => Some(KindFilter::Extensions(extensions)),
+        }
+    }
+
+    fn matches(&self, path: &Path) -> bool {
+        match self {
+            KindFilter::Category(category) => category.matches(path),
+            KindFilter::Extensions(extensions) => {
+                let Some(extension) = path.extension() else { return false };
+                let extension = extension.to_string_lossy().to_lowercase();
+
+                extensions.contains(&extension)
+            },
+        }
+    }
+
+    pub fn category(&self) -> Option<Category> {
+        match self {
+            KindFilter::Category(category) => Some(*category),
+            KindFilter::Extensions(_) => None,
+        }
+    }
+}
+
+/// Hides listing entries whose name doesn't contain `text` (case-insensitive),
+/// that don't match `kind` or that weren't modified within `modified`. Directories
+/// are never hidden by `kind` or `modified`, so the filtered view can still be navigated.
+#[derive(Debug, Clone, Default)]
+pub struct ListingFilter {
+    pub text: String,
+    pub kind: Option<KindFilter>,
+    pub modified: Option<Recency>,
+}
+
+impl ListingFilter {
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty() || self.kind.is_some() || self.modified.is_some()
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        if !self.text.is_empty() {
+            let name = path.file_name().map(|name| name.to_string_lossy().to_ascii_lowercase()).unwrap_or_default();
+
+            if !name.contains(&self.text.to_ascii_lowercase()) {
+                return false;
+            }
+        }
+
+        if path.is_dir() {
+            return true;
+        }
+
+        self.kind.as_ref().is_none_or(|kind| kind.matches(path))
+            && self.modified.is_none_or(|recency| recency.matches(path))
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    let Some(extension) = path.extension() else { return false };
+    let extension = extension.to_string_lossy().to_lowercase();
+
+    extensions.contains(&extension.as_str())
+}
@@ -0,0 +1,140 @@
+use std::path::Path;
+
+use crate::my_errors::MyError;
+
+#[cfg(target_os = "macos")]
+const TAGS_ATTRIBUTE: &str = "com.apple.metadata:_kMDItemUserTags";
+#[cfg(target_os = "macos")]
+const QUARANTINE_ATTRIBUTE: &str = "com.apple.quarantine";
+
+/// A Finder color tag, in the order of Finder's menu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TagColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+}
+
+impl TagColor {
+    pub const ALL: [TagColor; 7] = [
+        TagColor::Red,
+        TagColor::Orange,
+        TagColor::Yellow,
+        TagColor::Green,
+        TagColor::Blue,
+        TagColor::Purple,
+        TagColor::Gray,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TagColor::Red => "Red",
+            TagColor::Orange => "Orange",
+            TagColor::Yellow => "Yellow",
+            TagColor::Green => "Green",
+            TagColor::Blue => "Blue",
+            TagColor::Purple => "Purple",
+            TagColor::Gray => "Gray",
+        }
+    }
+
+    /// Finder's label number for the color, stored after the tag's name.
+    #[cfg(target_os = "macos")]
+    fn label(&self) -> u8 {
+        match self {
+            TagColor::Gray => 1,
+            TagColor::Green => 2,
+            TagColor::Purple => 3,
+            TagColor::Blue => 4,
+            TagColor::Yellow => 5,
+            TagColor::Red => 6,
+            TagColor::Orange => 7,
+        }
+    }
+}
+
+/// The names of the entry's Finder tags, colored or not.
+#[cfg(target_os = "macos")]
+pub fn tags(path: &Path) -> Vec<String> {
+    read_tags(path)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|tag| tag_name(&tag).to_string())
+        .collect()
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn tags(_path: &Path) -> Vec<String> {
+    Vec::new()
+}
+
+/// Adds or removes a color tag, keeping the entry's other tags. Returns the new tags.
+#[cfg(target_os = "macos")]
+pub fn toggle_color(path: &Path, color: TagColor) -> Result<Vec<String>, MyError> {
+    let mut tags = read_tags(path).map_err(|e| MyError::io(e, path))?;
+
+    match tags.iter().any(|tag| tag_name(tag) == color.name()) {
+        true => tags.retain(|tag| tag_name(tag) != color.name()),
+        false => tags.push(format!("{}\n{}", color.name(), color.label())),
+    }
+
+    let result = match tags.is_empty() {
+        true => xattr::remove(path, TAGS_ATTRIBUTE),
+        false => {
+            let mut value = Vec::new();
+            plist::to_writer_binary(&mut value, &tags)
+                .map_err(|e| MyError::FileError(format!("Couldn't encode tags: {}", e)))?;
+            xattr::set(path, TAGS_ATTRIBUTE, &value)
+        },
+    };
+
+    result.map_err(|e| MyError::io(e, path))?;
+
+    Ok(tags.iter().map(|tag| tag_name(tag).to_string()).collect())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn toggle_color(_path: &Path, _color: TagColor) -> Result<Vec<String>, MyError> {
+    Err(MyError::FileError("Finder tags are only supported on macOS".to_string()))
+}
+
+/// Whether Gatekeeper will check the file before it's first opened, as it does for downloads.
+#[cfg(target_os = "macos")]
+pub fn is_quarantined(path: &Path) -> bool {
+    xattr::get(path, QUARANTINE_ATTRIBUTE).is_ok_and(|value| value.is_some())
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn is_quarantined(_path: &Path) -> bool {
+    false
+}
+
+#[cfg(target_os = "macos")]
+pub fn clear_quarantine(path: &Path) -> Result<(), MyError> {
+    xattr::remove(path, QUARANTINE_ATTRIBUTE).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn clear_quarantine(_path: &Path) -> Result<(), MyError> {
+    Err(MyError::FileError("Quarantine is only supported on macOS".to_string()))
+}
+
+/// The raw tags, each a name optionally followed by a newline and a label number.
+/// A missing attribute, or one that isn't a list of strings, counts as no tags.
+#[cfg(target_os = "macos")]
+fn read_tags(path: &Path) -> std::io::Result<Vec<String>> {
+    let value = xattr::get(path, TAGS_ATTRIBUTE)?;
+
+    Ok(value
+        .and_then(|value| plist::from_bytes::<Vec<String>>(&value).ok())
+        .unwrap_or_default())
+}
+
+#[cfg(target_os = "macos")]
+fn tag_name(tag: &str) -> &str {
+    tag.split_once('\n').map_or(tag, |(name, _)| name)
+}
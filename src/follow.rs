@@ -0,0 +1,111 @@
+use std::{collections::VecDeque, fs::{self, File}, io::{Read, Seek, SeekFrom}, path::{Path, PathBuf}};
+
+use crate::{my_errors::MyError, preview};
+
+// Only the end of the file is loaded when following starts
+const FOLLOW_TAIL_BYTES: u64 = 64 * 1024;
+const FOLLOW_MAX_LINES: usize = 1000;
+// Upper bound on how much is read per poll, so a burst of writes can't stall the UI
+const FOLLOW_MAX_READ: u64 = 1024 * 1024;
+
+/// Streams lines appended to a file, like `tail -f`.
+/// A truncated file is read again from the start.
+pub struct Follower {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+    lines: VecDeque<String>,
+    pub paused: bool,
+}
+
+impl Follower {
+    pub fn new(path: &Path) -> Result<Self, MyError> {
+        let metadata = fs::metadata(path).map_err(|e| MyError::io(e, path))?;
+
+        if !metadata.is_file() {
+            return Err(MyError::FileError("Only files can be followed".to_string()));
+        }
+
+        let mut follower = Self {
+            path: path.to_path_buf(),
+            offset: metadata.len().saturating_sub(FOLLOW_TAIL_BYTES),
+            partial: Vec::new(),
+            lines: VecDeque::new(),
+            paused: false,
+        };
+
+        let starts_mid_file = follower.offset > 0;
+        follower.poll()?;
+
+        // Starting in the middle of the file almost always cuts the first line in half
+        if starts_mid_file {
+            follower.lines.pop_front();
+        }
+
+        Ok(follower)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The lines read so far, including an unterminated last line.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = self.lines.iter().cloned().collect::<Vec<String>>();
+
+        if !self.partial.is_empty() {
+            lines.push(preview::sanitize_line(&String::from_utf8_lossy(&self.partial)));
+        }
+
+        lines
+    }
+
+    /// Reads whatever was appended since the last poll. Returns whether anything changed.
+    pub fn poll(&mut self) -> Result<bool, MyError> {
+        if self.paused {
+            return Ok(false);
+        }
+
+        let mut file = File::open(&self.path).map_err(|e| MyError::io(e, &self.path))?;
+        let len = file.metadata().map_err(|e| MyError::io(e, &self.path))?.len();
+
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+            self.lines.clear();
+        }
+
+        if len == self.offset {
+            return Ok(false);
+        }
+
+        let mut appended = Vec::new();
+        file.seek(SeekFrom::Start(self.offset))
+            .and_then(|_| file.take(FOLLOW_MAX_READ).read_to_end(&mut appended))
+            .map_err(|e| MyError::io(e, &self.path))?;
+
+        self.offset += appended.len() as u64;
+        self.push(&appended);
+
+        Ok(true)
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        self.partial.extend_from_slice(bytes);
+
+        let Some(last_newline) = self.partial.iter().rposition(|byte| *byte == b'\n') else { return };
+        let rest = self.partial.split_off(last_newline + 1);
+
+        for line in self.partial.split(|byte| *byte == b'\n') {
+            self.lines.push_back(preview::sanitize_line(&String::from_utf8_lossy(line)));
+        }
+
+        // `split` yields an empty slice after the trailing newline
+        self.lines.pop_back();
+        self.partial = rest;
+
+        while self.lines.len() > FOLLOW_MAX_LINES {
+            self.lines.pop_front();
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+use crate::my_errors::MyError;
+
+pub struct RepoStatus {
+    pub name: String,
+    pub branch: String,
+    pub dirty: bool,
+}
+
+/// The most recent commit touching a file or directory.
+pub struct LastCommit {
+    pub hash: String,
+    pub author: String,
+    pub time: SystemTime,
+    pub summary: String,
+}
+
+/// Keeps the git status of the current directory up to date. `git` is only run again
+/// when the directory changes or the repository's HEAD, index or the directory itself
+/// were modified since the last check.
+#[derive(Default)]
+pub struct GitWatcher {
+    path: PathBuf,
+    git_dir: Option<PathBuf>,
+    stamp: Vec<Option<SystemTime>>,
+    status: Option<RepoStatus>,
+    /// Porcelain codes of changed files in the repository, by absolute path
+    files: HashMap<PathBuf, String>,
+    /// Last commits looked up so far, `None` for paths git has no commits for
+    commits: HashMap<PathBuf, Option<LastCommit>>,
+    fetching: HashMap<PathBuf, Receiver<Option<LastCommit>>>,
+}
+
+impl GitWatcher {
+    pub fn status(&self) -> Option<&RepoStatus> {
+        self.status.as_ref()
+    }
+
+    /// The porcelain code of a changed file as of the last refresh, or `*` for a
+    /// directory with changes inside. `None` for clean entries.
+    pub fn file_status(&self, path: &Path) -> Option<&str> {
+        if let Some(code) = self.files.get(path) {
+            return Some(code);
+        }
+
+        self.files.keys().any(|changed| changed.starts_with(path)).then_some("*")
+    }
+
+    pub fn refresh(&mut self, path: &Path) {
+        let stamp = self.stamp_for(path);
+
+        if self.path == path && self.stamp == stamp {
+            return;
+        }
+
+        self.path = path.to_path_buf();
+        self.status = None;
+        self.git_dir = None;
+        self.files.clear();
+        self.commits.clear();
+
+        if let Some((top_level, git_dir)) = repository_dirs(path) {
+            self.files = changed_files(path, &top_level);
+            self.status = Some(RepoStatus {
+                name: top_level.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                branch: branch(path).unwrap_or_else(|| "unknown".to_string()),
+                dirty: !self.files.is_empty(),
+            });
+            self.git_dir = Some(git_dir);
+        }
+
+        self.stamp = self.stamp_for(path);
+    }
+
+    /// The last commit touching `path`, looked up in the background the first time it's
+    /// asked for. `None` while that's still running, `Some(None)` outside a repository
+    /// and for paths without commits.
+    pub fn last_commit(&mut self, path: &Path) -> Option<Option<&LastCommit>> {
+        if self.status.is_none() {
+            return Some(None);
+        }
+
+        if !self.commits.contains_key(path) && !self.fetching.contains_key(path) {
+            let (sender, receiver) = mpsc::channel();
+            let target = path.to_path_buf();
+
+            thread::spawn(move || {
+                let _ = sender.send(last_commit(&target));
+            });
+
+            self.fetching.insert(path.to_path_buf(), receiver);
+        }
+
+        self.commits.get(path).map(Option::as_ref)
+    }
+
+    pub fn is_fetching(&self) -> bool {
+        !self.fetching.is_empty()
+    }
+
+    /// Caches the lookups that finished since the last call.
+    pub fn collect_commits(&mut self) {
+        let mut finished = Vec::new();
+
+        for (path, receiver) in &self.fetching {
+            match receiver.try_recv() {
+                Ok(commit) => finished.push((path.clone(), commit)),
+                Err(TryRecvError::Disconnected) => finished.push((path.clone(), None)),
+                Err(TryRecvError::Empty) => {},
+            }
+        }
+
+        for (path, commit) in finished {
+            self.fetching.remove(&path);
+            self.commits.insert(path, commit);
+        }
+    }
+
+    fn stamp_for(&self, path: &Path) -> Vec<Option<SystemTime>> {
+        let modified = |path: &Path| fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+
+        let mut stamp = vec![modified(path)];
+
+        if let Some(git_dir) = &self.git_dir {
+            stamp.push(modified(&git_dir.join("HEAD")));
+            stamp.push(modified(&git_dir.join("index")));
+        }
+
+        stamp
+    }
+}
+
+fn git(path: &Path, args: &[&str]) -> Option<String> {
+    let output = Command::new("git").arg("-C").arg(path).args(args).output().ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+}
+
+fn repository_dirs(path: &Path) -> Option<(PathBuf, PathBuf)> {
+    let output = git(path, &["rev-parse", "--show-toplevel", "--absolute-git-dir"])?;
+    let mut lines = output.lines();
+
+    Some((PathBuf::from(lines.next()?), PathBuf::from(lines.next()?)))
+}
+
+// A detached HEAD shows the abbreviated commit instead of a branch name
+fn branch(path: &Path) -> Option<String> {
+    git(path, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .or_else(|| git(path, &["rev-parse", "--short", "HEAD"]))
+}
+
+// Paths in porcelain output are relative to the top level, untracked directories end with `/`
+fn changed_files(path: &Path, top_level: &Path) -> HashMap<PathBuf, String> {
+    let Some(output) = git(path, &["status", "--porcelain", "-z"]) else { return HashMap::new() };
+
+    let mut files = HashMap::new();
+    let mut entries = output.split('\0');
+
+    while let Some(entry) = entries.next() {
+        let (Some(code), Some(name)) = (entry.get(..2), entry.get(3..)) else { continue };
+
+        // Renames and copies are followed by the original path
+        if code.starts_with(['R', 'C']) {
+            entries.next();
+        }
+
+        files.insert(top_level.join(name.trim_end_matches('/')), code.to_string());
+    }
+
+    files
+}
+
+fn last_commit(path: &Path) -> Option<LastCommit> {
+    let (dir, name) = split(path)?;
+    let output = git(dir, &["log", "-1", "--format=%h%x00%an%x00%at%x00%s", "--", name])?;
+    let mut fields = output.split('\0');
+
+    Some(LastCommit {
+        hash: fields.next().filter(|hash| !hash.is_empty())?.to_string(),
+        author: fields.next()?.to_string(),
+        time: SystemTime::UNIX_EPOCH + Duration::from_secs(fields.next()?.parse().ok()?),
+        summary: fields.next().unwrap_or_default().to_string(),
+    })
+}
+
+/// The two-letter `git status --porcelain` code of a file, e.g. `M ` when its
+/// changes are staged or `??` when it is untracked. `None` for clean files.
+pub fn file_status(path: &Path) -> Option<String> {
+    let (dir, name) = split(path)?;
+    let output = git(dir, &["status", "--porcelain", "--", name])?;
+
+    output.lines().next().and_then(|line| line.get(..2)).map(str::to_string)
+}
+
+pub fn stage(path: &Path) -> Result<(), MyError> {
+    run_on_file(path, &["add"])
+}
+
+pub fn unstage(path: &Path) -> Result<(), MyError> {
+    run_on_file(path, &["restore", "--staged"])
+}
+
+/// Throws away unstaged changes, restoring the file from the index.
+pub fn discard(path: &Path) -> Result<(), MyError> {
+    run_on_file(path, &["restore", "--worktree"])
+}
+
+/// The unified diff of a file against HEAD, covering staged and unstaged changes.
+pub fn diff(path: &Path) -> Result<String, MyError> {
+    let Some((dir, name)) = split(path) else {
+        return Err(MyError::FileError("Incorrect path".to_string()));
+    };
+
+    git(dir, &["diff", "--no-color", "--no-ext-diff", "HEAD", "--", name])
+        .ok_or_else(|| MyError::CommandError(format!("Couldn't run git diff on {}", path.display())))
+}
+
+fn run_on_file(path: &Path, args: &[&str]) -> Result<(), MyError> {
+    let Some((dir, name)) = split(path) else {
+        return Err(MyError::FileError("Incorrect path".to_string()));
+    };
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .arg("--")
+        .arg(name)
+        .output()
+        .map_err(|e| MyError::command("git", e))?;
+
+    if !output.status.success() {
+        let message = String::from_utf8_lossy(&output.stderr).lines().next().unwrap_or_default().to_string();
+        return Err(MyError::CommandError(format!("git {} failed: {}", args[0], message)));
+    }
+
+    Ok(())
+}
+
+fn split(path: &Path) -> Option<(&Path, &str)> {
+    Some((path.parent()?, path.file_name()?.to_str()?))
+}
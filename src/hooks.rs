@@ -0,0 +1,147 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use globset::Glob;
+use serde::Deserialize;
+
+// More than this wouldn't fit in the notification area anyway
+const MAX_OUTPUT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookEvent {
+    Enter,
+    Leave,
+}
+
+impl HookEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            HookEvent::Enter => "enter",
+            HookEvent::Leave => "leave",
+        }
+    }
+}
+
+/// A shell command run in a directory when it's entered or left. `path` is a glob on the
+/// directory's full path (`~` expands to the home directory) and `marker` a file or
+/// directory it has to contain; both are optional.
+#[derive(Clone, Deserialize)]
+pub struct Hook {
+    pub on: HookEvent,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub marker: Option<String>,
+    pub command: String,
+}
+
+impl Hook {
+    fn applies(&self, event: HookEvent, dir: &Path) -> bool {
+        self.on == event
+            && self.marker.as_ref().is_none_or(|marker| dir.join(marker).exists())
+            && self.path.as_ref().is_none_or(|pattern| matches_path(pattern, dir))
+    }
+}
+
+/// Runs hooks in the background, so slow commands like `git fetch` don't hold up the UI,
+/// and collects what they print.
+pub struct HookRunner {
+    hooks: Vec<Hook>,
+    sender: Sender<String>,
+    receiver: Receiver<String>,
+    running: usize,
+}
+
+impl HookRunner {
+    pub fn new(hooks: Vec<Hook>) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Self { hooks, sender, receiver, running: 0 }
+    }
+
+    pub fn run(&mut self, event: HookEvent, dir: &Path) {
+        for hook in self.hooks.iter().filter(|hook| hook.applies(event, dir)) {
+            let sender = self.sender.clone();
+            let command = hook.command.clone();
+            let dir = dir.to_path_buf();
+
+            thread::spawn(move || {
+                let _ = sender.send(run_hook(&command, event, &dir));
+            });
+
+            self.running += 1;
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running > 0
+    }
+
+    /// Output of the hooks that finished since the last call, leaving out silent ones.
+    pub fn try_output(&mut self) -> Vec<String> {
+        let mut outputs = Vec::new();
+
+        while let Ok(output) = self.receiver.try_recv() {
+            self.running = self.running.saturating_sub(1);
+
+            if !output.is_empty() {
+                outputs.push(output);
+            }
+        }
+
+        outputs
+    }
+}
+
+/// The last lines the command printed, or why it failed when it printed nothing.
+fn run_hook(command: &str, event: HookEvent, dir: &Path) -> String {
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .env("DIRMAN_EVENT", event.name())
+        .env("DIRMAN_DIR", dir)
+        .stdin(Stdio::null())
+        .output();
+
+    let output = match output {
+        Ok(output) => output,
+        Err(e) => return format!("Hook \"{}\" couldn't start: {}", command, e),
+    };
+
+    let printed = [&output.stdout, &output.stderr]
+        .iter()
+        .map(|stream| String::from_utf8_lossy(stream).to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+
+    let lines = printed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<&str>>();
+
+    match (lines.is_empty(), output.status.code()) {
+        (true, Some(0)) => String::new(),
+        (true, Some(code)) => format!("Hook \"{}\" exited with code {}", command, code),
+        (true, None) => format!("Hook \"{}\" was terminated by a signal", command),
+        (false, _) => lines[lines.len().saturating_sub(MAX_OUTPUT_LINES)..].join(" · "),
+    }
+}
+
+fn matches_path(pattern: &str, dir: &Path) -> bool {
+    let pattern = match (pattern.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(pattern),
+    };
+
+    match Glob::new(&pattern.to_string_lossy()) {
+        Ok(glob) => glob.compile_matcher().is_match(dir),
+        Err(_) => false,
+    }
+}
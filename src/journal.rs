@@ -0,0 +1,120 @@
+use std::{env, fs::{self, OpenOptions}, io::Write, path::PathBuf};
+
+use chrono::Local;
+
+use crate::{config, my_errors::MyError};
+
+/// Append-only log of every mutating file operation, one tab-separated entry per line:
+/// time, user, operation, source, destination and result.
+pub struct Journal {
+    file_path: Option<PathBuf>,
+}
+
+#[derive(Clone)]
+pub struct JournalEntry {
+    pub time: String,
+    pub user: String,
+    pub operation: String,
+    pub source: String,
+    pub destination: String,
+    pub result: String,
+}
+
+impl Default for Journal {
+    fn default() -> Self {
+        Self { file_path: config::journal_file() }
+    }
+}
+
+impl JournalEntry {
+    pub fn new(operation: &str, source: &str, destination: Option<&str>, result: &Result<(), MyError>) -> Self {
+        Self {
+            time: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            user: env::var("USER")
+                .or_else(|_| env::var("LOGNAME"))
+                .unwrap_or_else(|_| "unknown".to_string()),
+            operation: operation.to_string(),
+            source: source.to_string(),
+            destination: destination.unwrap_or_default().to_string(),
+            result: match result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => e.to_string(),
+            },
+        }
+    }
+
+    pub fn succeeded(&self) -> bool {
+        self.result == "ok"
+    }
+
+    fn to_line(&self) -> String {
+        [&self.time, &self.user, &self.operation, &self.source, &self.destination, &self.result]
+            .iter()
+            .map(|field| escape(field))
+            .collect::<Vec<String>>()
+            .join("\t")
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let fields = line.split('\t').map(unescape).collect::<Vec<String>>();
+        let [time, user, operation, source, destination, result] = <[String; 6]>::try_from(fields).ok()?;
+
+        Some(Self { time, user, operation, source, destination, result })
+    }
+}
+
+impl Journal {
+    pub fn record(&self, entry: &JournalEntry) -> Result<(), MyError> {
+        let Some(file_path) = &self.file_path else { return Ok(()) };
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| MyError::io(e, parent))?;
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .map_err(|e| MyError::io(e, file_path))?;
+
+        writeln!(file, "{}", entry.to_line()).map_err(|e| MyError::io(e, file_path))
+    }
+
+    /// Returns the journal, newest entry first.
+    pub fn entries(&self) -> Result<Vec<JournalEntry>, MyError> {
+        let Some(file_path) = &self.file_path else { return Ok(Vec::new()) };
+
+        if !file_path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let contents = fs::read_to_string(file_path).map_err(|e| MyError::io(e, file_path))?;
+
+        Ok(contents.lines().rev().filter_map(JournalEntry::from_line).collect())
+    }
+}
+
+fn escape(field: &str) -> String {
+    field.replace('\\', "\\\\").replace('\t', "\\t").replace('\n', "\\n")
+}
+
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
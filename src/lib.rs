@@ -0,0 +1,40 @@
+pub mod ui;
+pub mod directory;
+pub mod my_errors;
+pub mod config;
+pub mod plugins;
+pub mod bookmarks;
+pub mod zoxide;
+pub mod git;
+pub mod search;
+pub mod preview;
+pub mod follow;
+pub mod matcher;
+pub mod filter;
+pub mod marks;
+pub mod batch;
+pub mod clipboard;
+pub mod external;
+pub mod opener;
+pub mod journal;
+pub mod undo;
+pub mod permissions;
+pub mod finder;
+pub mod compression;
+pub mod archive;
+pub mod encryption;
+pub mod checksum;
+pub mod background;
+pub mod hooks;
+pub mod dir_views;
+pub mod trash;
+pub mod mounts;
+pub mod expansion;
+pub mod cli;
+pub mod properties;
+pub mod xattrs;
+pub mod registers;
+
+pub use directory::FileManager;
+pub use my_errors::MyError;
+pub use ui::{app::App, driver::Driver};
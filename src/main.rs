@@ -1,15 +1,54 @@
-mod ui;
-mod directory;
-mod my_errors;
+use std::{env, io::{self, Read}, process};
 
-use std::io;
-
-use crate::ui::app::App;
+use dirman::{cli, config, App};
 
 fn main() -> io::Result<()>{
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    // Commands print something and exit without starting the UI
+    match args.first().map(String::as_str) {
+        Some("completions") => match cli::completions(args.get(1).map_or("", String::as_str)) {
+            Ok(script) => {
+                print!("{}", script);
+                return Ok(());
+            },
+            Err(e) => {
+                eprintln!("dirman: {}", e);
+                process::exit(2);
+            },
+        },
+        Some("profiles") => {
+            config::profile_names().iter().for_each(|name| println!("{}", name));
+            return Ok(());
+        },
+        _ => {},
+    }
+
+    // Picked before anything is loaded, since every config path depends on it
+    let profile = args
+        .iter()
+        .position(|arg| arg == "--profile")
+        .and_then(|index| args.get(index + 1).cloned())
+        .or_else(|| args.iter().find_map(|arg| arg.strip_prefix("--profile=").map(str::to_string)));
+
+    if let Some(profile) = profile {
+        if let Err(e) = config::select_profile(&profile) {
+            eprintln!("dirman: {}", e);
+            process::exit(2);
+        }
+    }
+
+    let mut app = App::default();
+
+    // Piped data is read before the UI starts; key presses then come from the terminal itself
+    if args.iter().any(|arg| arg == "--create-from-stdin") {
+        let mut contents = Vec::new();
+        io::stdin().read_to_end(&mut contents)?;
+        app.create_from_stdin(contents);
+    }
+
     let mut terminal = ratatui::init();
-    let app_result = App::default().run(&mut terminal);
+    let app_result = app.run(&mut terminal);
     ratatui::restore();
     app_result
 }
-
@@ -0,0 +1,61 @@
+use std::{collections::{btree_map::Entry, BTreeMap}, fs, path::{Path, PathBuf}};
+
+use crate::directory;
+
+/// Marked entries together with their sizes, directories counted recursively,
+/// so the total can be shown without walking them again on every frame.
+#[derive(Default)]
+pub struct Marks {
+    paths: BTreeMap<PathBuf, u64>,
+}
+
+impl Marks {
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.contains_key(path)
+    }
+
+    pub fn insert(&mut self, path: PathBuf) {
+        if let Entry::Vacant(entry) = self.paths.entry(path) {
+            let size = entry_size(entry.key());
+            entry.insert(size);
+        }
+    }
+
+    pub fn remove(&mut self, path: &Path) -> bool {
+        self.paths.remove(path).is_some()
+    }
+
+    pub fn extend(&mut self, paths: impl IntoIterator<Item = PathBuf>) {
+        for path in paths {
+            self.insert(path);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PathBuf> {
+        self.paths.keys()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.paths.values().sum()
+    }
+}
+
+fn entry_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => directory::dir_stats(path, u64::MAX, u64::MAX).size,
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use globset::Glob;
+use serde::Deserialize;
+
+/// Matches files by extension, MIME type (`image/*` wildcards allowed) or
+/// glob pattern on the file name. A matcher with no criteria matches nothing.
+#[derive(Clone, Default, Deserialize)]
+pub struct FileMatcher {
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default)]
+    pub mime: Option<String>,
+    #[serde(default)]
+    pub pattern: Option<String>,
+}
+
+impl FileMatcher {
+    pub fn matches(&self, path: &Path) -> bool {
+        self.matches_extension(path) || self.matches_mime(path) || self.matches_pattern(path)
+    }
+
+    fn matches_extension(&self, path: &Path) -> bool {
+        let Some(extension) = path.extension() else { return false };
+        let extension = extension.to_string_lossy().to_lowercase();
+
+        self.extensions.iter().any(|ext| ext.to_lowercase() == extension)
+    }
+
+    fn matches_mime(&self, path: &Path) -> bool {
+        let Some(pattern) = &self.mime else { return false };
+        let Some(mime) = mime_guess::from_path(path).first() else { return false };
+
+        match pattern.strip_suffix("/*") {
+            Some(top_level) => mime.type_() == top_level,
+            None => mime.essence_str() == pattern,
+        }
+    }
+
+    fn matches_pattern(&self, path: &Path) -> bool {
+        let Some(pattern) = &self.pattern else { return false };
+        let Some(file_name) = path.file_name() else { return false };
+
+        match Glob::new(pattern) {
+            Ok(glob) => glob.compile_matcher().is_match(file_name),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::path::Path;
+
+// File system types served over the network, as Linux and macOS name them
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const NETWORK_TYPES: &[&str] = &[
+    "nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs", "sshfs", "osxfuse", "macfuse", "afpfs", "webdav",
+    "davfs", "fuse.davfs", "fuse.rclone", "9p", "afs", "ceph", "glusterfs", "fuse.glusterfs",
+];
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn is_network(fs_type: &str) -> bool {
+    NETWORK_TYPES.contains(&fs_type)
+}
+
+/// The type of the network file system `path` is on, e.g. `nfs4` or `cifs`, or `None`
+/// for local ones. Works from the mount table, so a hung mount can't block it.
+#[cfg(target_os = "linux")]
+pub fn network_fs(path: &Path) -> Option<String> {
+    let mounts = std::fs::read_to_string("/proc/self/mounts").ok()?;
+
+    // The deepest mount point containing the path is the one it's on
+    let (_, fs_type) = mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let mount_point = unescape(fields.nth(1)?);
+            let fs_type = fields.next()?;
+
+            path.starts_with(&mount_point).then(|| (mount_point.len(), fs_type.to_string()))
+        })
+        .max_by_key(|(depth, _)| *depth)?;
+
+    is_network(&fs_type).then_some(fs_type)
+}
+
+/// Asks the file system itself, since macOS has no mount table to read.
+#[cfg(target_os = "macos")]
+pub fn network_fs(path: &Path) -> Option<String> {
+    use std::{ffi::{CStr, CString}, mem, os::unix::ffi::OsStrExt};
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    let mut stats: libc::statfs = unsafe { mem::zeroed() };
+
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+
+    let fs_type = unsafe { CStr::from_ptr(stats.f_fstypename.as_ptr()) }.to_string_lossy().to_string();
+
+    is_network(&fs_type).then_some(fs_type)
+}
+
+/// Only UNC paths like `\\server\share` are recognized elsewhere; mapped drives aren't.
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn network_fs(path: &Path) -> Option<String> {
+    use std::path::{Component, Prefix};
+
+    match path.components().next() {
+        Some(Component::Prefix(prefix)) if matches!(prefix.kind(), Prefix::UNC(..) | Prefix::VerbatimUNC(..)) => Some("smb".to_string()),
+        _ => None,
+    }
+}
+
+/// Mount points have spaces and other separators escaped as octal, e.g. `\040`.
+#[cfg(target_os = "linux")]
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::new();
+    let mut rest = field;
+
+    while let Some(index) = rest.find('\\') {
+        unescaped.push_str(&rest[..index]);
+
+        match rest.get(index + 1..index + 4).and_then(|code| u8::from_str_radix(code, 8).ok()) {
+            Some(byte) => {
+                unescaped.push(byte as char);
+                rest = &rest[index + 4..];
+            },
+            None => {
+                unescaped.push('\\');
+                rest = &rest[index + 1..];
+            },
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
@@ -1,15 +1,83 @@
-use std::fmt;
+use std::{fmt, io, path::PathBuf};
 
 #[derive(Debug)]
 pub enum MyError {
-    FileError(String)
+    NotFound(PathBuf),
+    PermissionDenied(PathBuf),
+    AlreadyExists(PathBuf),
+    /// A rename between two file systems, which has to be a copy instead
+    CrossDevice(PathBuf),
+    NotADirectory(PathBuf),
+    IsADirectory(PathBuf),
+    DirectoryNotEmpty(PathBuf),
+    ReadOnly(PathBuf),
+    StorageFull(PathBuf),
+    /// Any other I/O failure on the path
+    Io(PathBuf, io::Error),
+    /// A file operation that can't go ahead for reasons other than the file system
+    FileError(String),
+    PluginError(String),
+    ConfigError(String),
+    CommandError(String),
 }
 
-impl fmt::Display for MyError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl MyError {
+    /// The precise error for an I/O failure on `path`.
+    pub fn io(error: io::Error, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        match error.kind() {
+            io::ErrorKind::NotFound => MyError::NotFound(path),
+            io::ErrorKind::PermissionDenied => MyError::PermissionDenied(path),
+            io::ErrorKind::AlreadyExists => MyError::AlreadyExists(path),
+            io::ErrorKind::CrossesDevices => MyError::CrossDevice(path),
+            io::ErrorKind::NotADirectory => MyError::NotADirectory(path),
+            io::ErrorKind::IsADirectory => MyError::IsADirectory(path),
+            io::ErrorKind::DirectoryNotEmpty => MyError::DirectoryNotEmpty(path),
+            io::ErrorKind::ReadOnlyFilesystem => MyError::ReadOnly(path),
+            io::ErrorKind::StorageFull => MyError::StorageFull(path),
+            _ => MyError::Io(path, error),
+        }
+    }
+
+    /// Why an external program couldn't be started.
+    pub fn command(program: &str, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::NotFound => MyError::CommandError(format!("{} is not installed", program)),
+            _ => MyError::CommandError(format!("Couldn't start {}: {}", program, error)),
+        }
+    }
+
+    /// What kind of error this is, e.g. `File Error`.
+    pub fn category(&self) -> &'static str {
         match self {
-            MyError::FileError(msg) => write!(f, "File Error: {}", msg),
+            MyError::PluginError(_) => "Plugin Error",
+            MyError::ConfigError(_) => "Config Error",
+            MyError::CommandError(_) => "Command Error",
+            _ => "File Error",
+        }
+    }
+
+    /// The error without its category, for wrapping it into another message.
+    pub fn message(&self) -> String {
+        match self {
+            MyError::NotFound(path) => format!("{} doesn't exist", path.display()),
+            MyError::PermissionDenied(path) => format!("Permission denied for {}, check its owner and permissions", path.display()),
+            MyError::AlreadyExists(path) => format!("{} already exists", path.display()),
+            MyError::CrossDevice(path) => format!("{} is on another file system, copy it instead", path.display()),
+            MyError::NotADirectory(path) => format!("{} is not a directory", path.display()),
+            MyError::IsADirectory(path) => format!("{} is a directory", path.display()),
+            MyError::DirectoryNotEmpty(path) => format!("{} isn't empty", path.display()),
+            MyError::ReadOnly(path) => format!("{} is on a read-only file system", path.display()),
+            MyError::StorageFull(path) => format!("No space left for {}", path.display()),
+            MyError::Io(path, error) => format!("{}: {}", path.display(), error),
+            MyError::FileError(msg) | MyError::PluginError(msg) | MyError::ConfigError(msg) | MyError::CommandError(msg) => msg.clone(),
         }
     }
 }
 
+impl fmt::Display for MyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.category(), self.message())
+    }
+}
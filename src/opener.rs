@@ -0,0 +1,65 @@
+use std::{env, path::Path};
+
+use crate::{config::{OpenerCommand, OpenerConfig, OpenerRule}, external::ExternalCommand};
+
+#[cfg(target_os = "macos")]
+const SYSTEM_OPENER: &str = "open {}";
+#[cfg(not(target_os = "macos"))]
+const SYSTEM_OPENER: &str = "xdg-open {}";
+
+// Used when neither the config nor the environment names an editor
+const FALLBACK_EDITOR: &str = "vi";
+
+/// Picks the command used to open a file: the first matching rule from the
+/// config, or the configured default, or the system opener when no rule applies.
+/// Editing works the same way with the configured editor, `$VISUAL` or `$EDITOR`.
+pub struct Opener {
+    rules: Vec<OpenerRule>,
+    default: Option<OpenerCommand>,
+    editor: Option<OpenerCommand>,
+}
+
+impl Opener {
+    pub fn new(config: &OpenerConfig) -> Self {
+        Self {
+            rules: config.rules.clone(),
+            default: config.default.clone(),
+            editor: config.editor.clone(),
+        }
+    }
+
+    pub fn resolve(&self, path: &Path) -> ExternalCommand {
+        match (self.rules.iter().find(|rule| rule.matcher.matches(path)), &self.default) {
+            (Some(rule), _) => ExternalCommand::for_file(&with_path(&rule.command), path, rule.terminal, rule.detached && !rule.terminal),
+            (None, Some(default)) => command_for(default, path),
+            (None, None) => ExternalCommand::for_file(SYSTEM_OPENER, path, false, true),
+        }
+    }
+
+    pub fn editor(&self, path: &Path) -> ExternalCommand {
+        if let Some(editor) = &self.editor {
+            return command_for(editor, path);
+        }
+
+        // Editors from the environment are assumed to run in the terminal, like vi
+        let editor = ["VISUAL", "EDITOR"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|editor| !editor.trim().is_empty())
+            .unwrap_or_else(|| FALLBACK_EDITOR.to_string());
+
+        ExternalCommand::for_file(&with_path(&editor), path, true, false)
+    }
+}
+
+fn command_for(command: &OpenerCommand, path: &Path) -> ExternalCommand {
+    ExternalCommand::for_file(&with_path(&command.command), path, command.terminal, command.detached && !command.terminal)
+}
+
+/// Appends the path placeholder to commands written without one, e.g. `code --wait`.
+fn with_path(command: &str) -> String {
+    match command.contains("{}") {
+        true => command.to_string(),
+        false => format!("{} {{}}", command),
+    }
+}
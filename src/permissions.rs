@@ -0,0 +1,557 @@
+use std::{collections::HashMap, path::Path, str::FromStr, sync::OnceLock};
+
+use walkdir::WalkDir;
+
+use crate::my_errors::MyError;
+
+const MODE_MASK: u32 = 0o7777;
+const EXEC_BITS: u32 = 0o111;
+
+/// A mode to apply, parsed either from octal or from chmod's symbolic expressions.
+/// Octal takes `644`, `644/755` (files/directories) or a trailing `X`, which like chmod
+/// only keeps execute bits on directories and on files that are already executable by
+/// someone. Symbolic takes comma-separated clauses like `u+rwx,go+rx`, `a-w` or `g=u`.
+#[derive(Clone)]
+pub enum ModeSpec {
+    Octal {
+        file_mode: u32,
+        dir_mode: u32,
+        conditional_exec: bool,
+    },
+    Symbolic(Vec<Clause>),
+}
+
+/// One clause of a symbolic mode: the bits of the classes in `who` get `operations`
+/// applied in turn, as in `go+rx-w`.
+#[derive(Clone)]
+pub struct Clause {
+    who: u32,
+    operations: Vec<(char, PermissionBits)>,
+}
+
+/// The right-hand side of a symbolic operation: permission letters or a class to copy.
+#[derive(Clone)]
+pub enum PermissionBits {
+    Letters(String),
+    CopyOf(u32),
+}
+
+// Bits each class letter covers, including setuid, setgid and sticky
+const USER_BITS: u32 = 0o4700;
+const GROUP_BITS: u32 = 0o2070;
+const OTHER_BITS: u32 = 0o1007;
+
+impl FromStr for ModeSpec {
+    type Err = MyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.contains(['+', '-', '=']) {
+            return s.split(',').map(parse_clause).collect::<Result<Vec<Clause>, MyError>>().map(ModeSpec::Symbolic);
+        }
+
+        let (s, conditional_exec) = match s.strip_suffix('X') {
+            Some(rest) => (rest, true),
+            None => (s, false),
+        };
+
+        let (file_mode, dir_mode) = match s.split_once('/') {
+            Some((file, dir)) => (parse_octal(file)?, parse_octal(dir)?),
+            None => {
+                let mode = parse_octal(s)?;
+                (mode, mode)
+            }
+        };
+
+        Ok(ModeSpec::Octal { file_mode, dir_mode, conditional_exec })
+    }
+}
+
+impl ModeSpec {
+    pub fn mode_for(&self, current: u32, is_dir: bool) -> u32 {
+        match self {
+            ModeSpec::Octal { dir_mode, .. } if is_dir => *dir_mode,
+            ModeSpec::Octal { file_mode, conditional_exec: true, .. } if current & EXEC_BITS == 0 => file_mode & !EXEC_BITS,
+            ModeSpec::Octal { file_mode, .. } => *file_mode,
+            ModeSpec::Symbolic(clauses) => clauses.iter().fold(current, |mode, clause| clause.apply(mode, is_dir)),
+        }
+    }
+}
+
+impl Clause {
+    fn apply(&self, mut mode: u32, is_dir: bool) -> u32 {
+        for (operator, permissions) in &self.operations {
+            let bits = permissions.bits(mode, is_dir) & self.who;
+
+            mode = match operator {
+                '+' => mode | bits,
+                '-' => mode & !bits,
+                _ => (mode & !self.who) | bits,
+            };
+        }
+
+        mode
+    }
+}
+
+impl PermissionBits {
+    fn bits(&self, mode: u32, is_dir: bool) -> u32 {
+        match self {
+            PermissionBits::Letters(letters) => letters.chars().fold(0, |bits, letter| bits | match letter {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => EXEC_BITS,
+                'X' if is_dir || mode & EXEC_BITS != 0 => EXEC_BITS,
+                's' => 0o6000,
+                't' => 0o1000,
+                _ => 0,
+            }),
+            // The class's rwx bits, repeated for every class so `who` can pick them out
+            PermissionBits::CopyOf(class) => {
+                let rwx = match *class {
+                    USER_BITS => (mode >> 6) & 0o7,
+                    GROUP_BITS => (mode >> 3) & 0o7,
+                    _ => mode & 0o7,
+                };
+
+                rwx * 0o111
+            },
+        }
+    }
+}
+
+// A clause without classes applies to all of them; unlike chmod, the umask isn't consulted
+fn parse_clause(clause: &str) -> Result<Clause, MyError> {
+    let invalid = || MyError::FileError(format!("Invalid mode {}", clause));
+    let split = clause.find(['+', '-', '=']).ok_or_else(invalid)?;
+    let (classes, mut rest) = clause.split_at(split);
+
+    let who = match classes {
+        "" => USER_BITS | GROUP_BITS | OTHER_BITS,
+        classes => classes.chars().try_fold(0, |who, class| class_bits(class).map(|bits| who | bits)).ok_or_else(invalid)?,
+    };
+
+    let mut operations = Vec::new();
+
+    while let Some(operator) = rest.chars().next() {
+        let end = rest[1..].find(['+', '-', '=']).map_or(rest.len(), |index| index + 1);
+        let letters = &rest[1..end];
+
+        let permissions = match letters {
+            "u" | "g" | "o" => PermissionBits::CopyOf(class_bits(letters.chars().next().unwrap_or_default()).ok_or_else(invalid)?),
+            letters if letters.chars().all(|letter| "rwxXst".contains(letter)) => PermissionBits::Letters(letters.to_string()),
+            _ => return Err(invalid()),
+        };
+
+        operations.push((operator, permissions));
+        rest = &rest[end..];
+    }
+
+    Ok(Clause { who, operations })
+}
+
+fn class_bits(class: char) -> Option<u32> {
+    match class {
+        'u' => Some(USER_BITS),
+        'g' => Some(GROUP_BITS),
+        'o' => Some(OTHER_BITS),
+        'a' => Some(USER_BITS | GROUP_BITS | OTHER_BITS),
+        _ => None,
+    }
+}
+
+fn parse_octal(s: &str) -> Result<u32, MyError> {
+    let invalid = || MyError::FileError(format!("Invalid mode {}", s));
+
+    if s.is_empty() || s.len() > 4 {
+        return Err(invalid());
+    }
+
+    u32::from_str_radix(s, 8).map_err(|_| invalid())
+}
+
+/// Applies `spec` to `path`, and to everything below it when `recursive` is set.
+/// Keeps going past failures and returns how many entries were changed.
+pub fn set_mode(path: &Path, spec: &ModeSpec, recursive: bool) -> Result<usize, MyError> {
+    let max_depth = if recursive { usize::MAX } else { 0 };
+
+    // Children first, so removing a directory's execute bit doesn't lock us out of it
+    let walker = WalkDir::new(path).contents_first(true).max_depth(max_depth);
+
+    let mut changed = 0;
+    let mut failed = 0;
+    let mut first_error = None;
+
+    for entry in walker {
+        let result = match entry {
+            Ok(entry) if entry.path_is_symlink() => continue,
+            Ok(entry) => set_entry_mode(entry.path(), spec, entry.file_type().is_dir()),
+            Err(e) => Err(MyError::FileError(e.to_string())),
+        };
+
+        match result {
+            Ok(_) => changed += 1,
+            Err(e) => {
+                failed += 1;
+                first_error.get_or_insert(e);
+            },
+        }
+    }
+
+    if let Some(e) = first_error {
+        return Err(MyError::FileError(format!("Couldn't change {} of {} entries, first: {}", failed, failed + changed, e.message())));
+    }
+
+    Ok(changed)
+}
+
+/// Flips a file between executable and not, like `chmod +x`/`-x`. Execute bits are
+/// only added where the matching read bit is set. Returns the new mode.
+#[cfg(unix)]
+pub fn toggle_executable(path: &Path) -> Result<u32, MyError> {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let metadata = fs::metadata(path).map_err(|e| MyError::io(e, path))?;
+
+    if !metadata.is_file() {
+        return Err(MyError::FileError("Only files can be made executable".to_string()));
+    }
+
+    let current = metadata.permissions().mode() & MODE_MASK;
+    let mode = if current & EXEC_BITS != 0 {
+        current & !EXEC_BITS
+    } else {
+        current | ((current & 0o444) >> 2)
+    };
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|e| MyError::io(e, path))?;
+
+    Ok(mode)
+}
+
+#[cfg(not(unix))]
+pub fn toggle_executable(_path: &Path) -> Result<u32, MyError> {
+    Err(MyError::FileError("Permissions are only supported on Unix".to_string()))
+}
+
+#[cfg(unix)]
+fn set_entry_mode(path: &Path, spec: &ModeSpec, is_dir: bool) -> Result<(), MyError> {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    let metadata = fs::metadata(path).map_err(|e| MyError::io(e, path))?;
+    let mode = spec.mode_for(metadata.permissions().mode() & MODE_MASK, is_dir);
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode)).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(unix))]
+fn set_entry_mode(_path: &Path, _spec: &ModeSpec, _is_dir: bool) -> Result<(), MyError> {
+    Err(MyError::FileError("Permissions are only supported on Unix".to_string()))
+}
+
+#[cfg(unix)]
+pub fn mode_of(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & MODE_MASK
+}
+
+#[cfg(not(unix))]
+pub fn mode_of(_metadata: &std::fs::Metadata) -> u32 {
+    0
+}
+
+/// Splits a mode off the end of a name typed into the Create prompt, as in
+/// `script.sh 755`. Only a last word of three or four octal digits counts as one.
+pub fn split_mode(input: &str) -> (&str, Option<u32>) {
+    match input.rsplit_once(' ') {
+        Some((name, mode)) if !name.trim().is_empty() && (3..=4).contains(&mode.len()) && mode.chars().all(|c| c.is_digit(8)) => {
+            (name.trim_end(), u32::from_str_radix(mode, 8).ok())
+        },
+        _ => (input, None),
+    }
+}
+
+/// The umask of the process. It's read once, so call this before other threads start
+/// creating files, since reading it means setting it for a moment.
+#[cfg(unix)]
+pub fn umask() -> Option<u32> {
+    static UMASK: OnceLock<u32> = OnceLock::new();
+
+    Some(*UMASK.get_or_init(|| unsafe {
+        let mask = libc::umask(0o022);
+        libc::umask(mask);
+        mask as u32
+    }))
+}
+
+#[cfg(not(unix))]
+pub fn umask() -> Option<u32> {
+    None
+}
+
+/// The mode new entries get when none is given: `666`/`777` less the umask.
+pub fn default_mode(is_dir: bool) -> Option<u32> {
+    umask().map(|mask| (if is_dir { 0o777 } else { 0o666 }) & !mask)
+}
+
+/// Gives a new entry the mode that was asked for. Setting it afterwards is what keeps
+/// the umask from taking bits away, and allows setuid, setgid and sticky bits.
+#[cfg(unix)]
+pub fn set_created_mode(path: &Path, mode: u32) -> Result<(), MyError> {
+    use std::{fs, os::unix::fs::PermissionsExt};
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & MODE_MASK)).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(unix))]
+pub fn set_created_mode(_path: &Path, _mode: u32) -> Result<(), MyError> {
+    Err(MyError::FileError("Permissions are only supported on Unix".to_string()))
+}
+
+#[cfg(unix)]
+pub fn owner_of(metadata: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(metadata.uid())
+}
+
+#[cfg(not(unix))]
+pub fn owner_of(_metadata: &std::fs::Metadata) -> Option<u32> {
+    None
+}
+
+const FILE_ATTRIBUTE_READONLY: u32 = 0x1;
+const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+const FILE_ATTRIBUTE_SYSTEM: u32 = 0x4;
+const FILE_ATTRIBUTE_ARCHIVE: u32 = 0x20;
+
+/// A Windows file attribute that can be flipped from Properties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Attribute {
+    ReadOnly,
+    Hidden,
+    Archive,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 3] = [Attribute::ReadOnly, Attribute::Hidden, Attribute::Archive];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::ReadOnly => "read-only",
+            Attribute::Hidden => "hidden",
+            Attribute::Archive => "archive",
+        }
+    }
+
+    pub fn key(&self) -> char {
+        match self {
+            Attribute::ReadOnly => 'r',
+            Attribute::Hidden => 'h',
+            Attribute::Archive => 'a',
+        }
+    }
+
+    pub fn is_set(&self, attributes: u32) -> bool {
+        attributes & self.bit() != 0
+    }
+
+    fn bit(&self) -> u32 {
+        match self {
+            Attribute::ReadOnly => FILE_ATTRIBUTE_READONLY,
+            Attribute::Hidden => FILE_ATTRIBUTE_HIDDEN,
+            Attribute::Archive => FILE_ATTRIBUTE_ARCHIVE,
+        }
+    }
+}
+
+#[cfg(windows)]
+pub fn attributes_of(metadata: &std::fs::Metadata) -> u32 {
+    use std::os::windows::fs::MetadataExt;
+
+    metadata.file_attributes()
+}
+
+#[cfg(not(windows))]
+pub fn attributes_of(_metadata: &std::fs::Metadata) -> u32 {
+    0
+}
+
+/// Whether Explorer hides the entry, which it does for both hidden and system files.
+pub fn is_hidden_attribute(attributes: u32) -> bool {
+    attributes & (FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM) != 0
+}
+
+/// Lists the set attributes, e.g. `read-only, archive`.
+pub fn attribute_string(attributes: u32) -> String {
+    let names = [
+        (FILE_ATTRIBUTE_READONLY, "read-only"),
+        (FILE_ATTRIBUTE_HIDDEN, "hidden"),
+        (FILE_ATTRIBUTE_SYSTEM, "system"),
+        (FILE_ATTRIBUTE_ARCHIVE, "archive"),
+    ];
+
+    let set = names
+        .iter()
+        .filter(|(bit, _)| attributes & bit != 0)
+        .map(|(_, name)| *name)
+        .collect::<Vec<&str>>();
+
+    match set.is_empty() {
+        true => "none".to_string(),
+        false => set.join(", "),
+    }
+}
+
+/// Flips one attribute of `path`, leaving the others alone. Returns the new attributes.
+#[cfg(windows)]
+pub fn toggle_attribute(path: &Path, attribute: Attribute) -> Result<u32, MyError> {
+    use std::{fs, io, os::windows::ffi::OsStrExt};
+
+    const FILE_ATTRIBUTE_NORMAL: u32 = 0x80;
+    // Plus temporary, offline and not content indexed. Anything else, like the directory
+    // bit, can't be set this way
+    const SETTABLE: u32 = FILE_ATTRIBUTE_READONLY | FILE_ATTRIBUTE_HIDDEN | FILE_ATTRIBUTE_SYSTEM | FILE_ATTRIBUTE_ARCHIVE
+        | 0x100 | 0x1000 | 0x2000;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn SetFileAttributesW(file_name: *const u16, attributes: u32) -> i32;
+    }
+
+    let metadata = fs::symlink_metadata(path).map_err(|e| MyError::io(e, path))?;
+    let attributes = attributes_of(&metadata) ^ attribute.bit();
+
+    let file_name = path.as_os_str().encode_wide().chain(Some(0)).collect::<Vec<u16>>();
+    let settable = match attributes & SETTABLE {
+        0 => FILE_ATTRIBUTE_NORMAL,
+        settable => settable,
+    };
+
+    if unsafe { SetFileAttributesW(file_name.as_ptr(), settable) } == 0 {
+        return Err(MyError::io(io::Error::last_os_error(), path));
+    }
+
+    Ok(attributes)
+}
+
+#[cfg(not(windows))]
+pub fn toggle_attribute(_path: &Path, _attribute: Attribute) -> Result<u32, MyError> {
+    Err(MyError::FileError("Attributes are only supported on Windows".to_string()))
+}
+
+/// The login name for a user id from `/etc/passwd`, read once, or the id itself.
+pub fn user_name(uid: u32) -> String {
+    static USERS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+
+    let users = USERS.get_or_init(|| read_id_names("/etc/passwd"));
+    users.get(&uid).cloned().unwrap_or_else(|| uid.to_string())
+}
+
+/// The name for a group id from `/etc/group`, read once, or the id itself.
+pub fn group_name(gid: u32) -> String {
+    static GROUPS: OnceLock<HashMap<u32, String>> = OnceLock::new();
+
+    let groups = GROUPS.get_or_init(|| read_id_names("/etc/group"));
+    groups.get(&gid).cloned().unwrap_or_else(|| gid.to_string())
+}
+
+// Both files list the name first and the id third
+fn read_id_names(file: &str) -> HashMap<u32, String> {
+    std::fs::read_to_string(file)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let id = fields.nth(1)?.parse().ok()?;
+            Some((id, name.to_string()))
+        })
+        .collect()
+}
+
+/// The id of a user given by name or id, as typed for a new owner.
+#[cfg(unix)]
+pub fn user_id(user: &str) -> Result<u32, MyError> {
+    use std::ffi::CString;
+
+    if let Ok(uid) = user.parse() {
+        return Ok(uid);
+    }
+
+    let name = CString::new(user).map_err(|_| MyError::FileError(format!("No user {}", user)))?;
+    let entry = unsafe { libc::getpwnam(name.as_ptr()) };
+
+    match entry.is_null() {
+        true => Err(MyError::FileError(format!("No user {}", user))),
+        false => Ok(unsafe { (*entry).pw_uid }),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn user_id(_user: &str) -> Result<u32, MyError> {
+    Err(MyError::FileError("Owners are only supported on Unix".to_string()))
+}
+
+/// The id of a group given by name or id.
+#[cfg(unix)]
+pub fn group_id(group: &str) -> Result<u32, MyError> {
+    use std::ffi::CString;
+
+    if let Ok(gid) = group.parse() {
+        return Ok(gid);
+    }
+
+    let name = CString::new(group).map_err(|_| MyError::FileError(format!("No group {}", group)))?;
+    let entry = unsafe { libc::getgrnam(name.as_ptr()) };
+
+    match entry.is_null() {
+        true => Err(MyError::FileError(format!("No group {}", group))),
+        false => Ok(unsafe { (*entry).gr_gid }),
+    }
+}
+
+#[cfg(not(unix))]
+pub fn group_id(_group: &str) -> Result<u32, MyError> {
+    Err(MyError::FileError("Groups are only supported on Unix".to_string()))
+}
+
+#[cfg(unix)]
+pub fn group_of(metadata: &std::fs::Metadata) -> Option<u32> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some(metadata.gid())
+}
+
+#[cfg(not(unix))]
+pub fn group_of(_metadata: &std::fs::Metadata) -> Option<u32> {
+    None
+}
+
+/// Changes the owner, the group or both, following symlinks like the mode does.
+/// Giving a file away usually takes root, while the group can be any the user is in.
+#[cfg(unix)]
+pub fn set_owner(path: &Path, uid: Option<u32>, gid: Option<u32>) -> Result<(), MyError> {
+    std::os::unix::fs::chown(path, uid, gid).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(unix))]
+pub fn set_owner(_path: &Path, _uid: Option<u32>, _gid: Option<u32>) -> Result<(), MyError> {
+    Err(MyError::FileError("Owners are only supported on Unix".to_string()))
+}
+
+/// Renders the permission bits the way `ls -l` does, e.g. `rwxr-xr-x`.
+pub fn mode_string(mode: u32) -> String {
+    let mut symbols = String::with_capacity(9);
+
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        symbols.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        symbols.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        symbols.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+
+    symbols
+}
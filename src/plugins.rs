@@ -0,0 +1,180 @@
+use std::{fs, path::Path, process::Command};
+
+use rhai::{Array, Dynamic, Engine, FuncArgs, Scope, AST};
+
+use crate::{config, my_errors::MyError};
+
+// Upper bound on script operations per hook call, so a runaway plugin can't freeze the UI
+const MAX_OPERATIONS: u64 = 1_000_000;
+const PLUGIN_EXTENSION: &str = "rhai";
+
+/// Loads `*.rhai` scripts from the plugins directory and dispatches hooks to them.
+///
+/// A plugin may define any of the following functions:
+/// `on_select(path)`, `on_enter_directory(path)`, `actions()`, `run_action(action, path)`,
+/// `columns()` and `column(name, path)`.
+pub struct PluginManager {
+    engine: Engine,
+    plugins: Vec<Plugin>,
+}
+
+struct Plugin {
+    name: String,
+    ast: AST,
+    actions: Vec<String>,
+    columns: Vec<String>,
+}
+
+impl Default for PluginManager {
+    fn default() -> Self {
+        Self {
+            engine: PluginManager::new_engine(),
+            plugins: Vec::new(),
+        }
+    }
+}
+
+impl PluginManager {
+    pub fn load() -> (Self, Vec<MyError>) {
+        match config::plugins_dir() {
+            Some(dir) => PluginManager::load_from(&dir),
+            None => (PluginManager::default(), Vec::new()),
+        }
+    }
+
+    pub fn load_from(dir: &Path) -> (Self, Vec<MyError>) {
+        let mut manager = PluginManager::default();
+        let mut errors = Vec::new();
+
+        let Ok(entries) = fs::read_dir(dir) else { return (manager, errors) };
+
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == PLUGIN_EXTENSION))
+            .collect::<Vec<_>>();
+        paths.sort();
+
+        for path in paths {
+            match manager.load_plugin(&path) {
+                Ok(plugin) => manager.plugins.push(plugin),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        (manager, errors)
+    }
+
+    pub fn action_names(&self) -> Vec<String> {
+        self.plugins
+            .iter()
+            .flat_map(|plugin| plugin.actions.clone())
+            .collect()
+    }
+
+    pub fn on_select(&self, path: &str) -> Result<(), MyError> {
+        for plugin in &self.plugins {
+            self.call(plugin, "on_select", (path.to_string(),))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn on_enter_directory(&self, path: &str) -> Result<(), MyError> {
+        for plugin in &self.plugins {
+            self.call(plugin, "on_enter_directory", (path.to_string(),))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn run_action(&self, action: &str, path: &str) -> Result<(), MyError> {
+        let Some(plugin) = self.plugins.iter().find(|p| p.actions.iter().any(|a| a == action)) else {
+            return Err(MyError::PluginError(format!("Unknown action {}", action)));
+        };
+
+        self.call(plugin, "run_action", (action.to_string(), path.to_string()))?;
+
+        Ok(())
+    }
+
+    pub fn columns(&self, path: &str) -> Vec<(String, String)> {
+        let mut columns = Vec::new();
+
+        for plugin in &self.plugins {
+            for column in &plugin.columns {
+                let value = match self.call(plugin, "column", (column.clone(), path.to_string())) {
+                    Ok(Some(value)) => value.to_string(),
+                    Ok(None) => continue,
+                    Err(e) => e.to_string(),
+                };
+
+                columns.push((column.clone(), value));
+            }
+        }
+
+        columns
+    }
+
+    fn new_engine() -> Engine {
+        let mut engine = Engine::new();
+
+        // Scripts printing straight to stdout would corrupt the terminal UI
+        engine.on_print(|_| {});
+        engine.on_debug(|_, _, _| {});
+        engine.set_max_operations(MAX_OPERATIONS);
+
+        engine.register_fn("shell", |command: &str| -> String {
+            match Command::new("sh").arg("-c").arg(command).output() {
+                Ok(output) => String::from_utf8_lossy(&output.stdout).trim_end().to_string(),
+                Err(_) => String::default(),
+            }
+        });
+
+        engine
+    }
+
+    fn load_plugin(&self, path: &Path) -> Result<Plugin, MyError> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let ast = self.engine
+            .compile_file(path.to_path_buf())
+            .map_err(|e| MyError::PluginError(format!("{}: {}", name, e)))?;
+
+        let mut plugin = Plugin { name, ast, actions: Vec::new(), columns: Vec::new() };
+        plugin.actions = self.call_list(&plugin, "actions")?;
+        plugin.columns = self.call_list(&plugin, "columns")?;
+
+        Ok(plugin)
+    }
+
+    fn call_list(&self, plugin: &Plugin, fn_name: &str) -> Result<Vec<String>, MyError> {
+        let Some(value) = self.call(plugin, fn_name, ())? else { return Ok(Vec::new()) };
+
+        let list = value
+            .try_cast::<Array>()
+            .ok_or_else(|| MyError::PluginError(format!("{}: {}() must return an array", plugin.name, fn_name)))?;
+
+        Ok(list.into_iter().map(|item| item.to_string()).collect())
+    }
+
+    fn call(&self, plugin: &Plugin, fn_name: &str, args: impl FuncArgs) -> Result<Option<Dynamic>, MyError> {
+        let mut arg_values = Vec::new();
+        args.parse(&mut arg_values);
+
+        let defined = plugin.ast
+            .iter_functions()
+            .any(|f| f.name == fn_name && f.params.len() == arg_values.len());
+
+        if !defined {
+            return Ok(None);
+        }
+
+        self.engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &plugin.ast, fn_name, arg_values)
+            .map(Some)
+            .map_err(|e| MyError::PluginError(format!("{}: {}", plugin.name, e)))
+    }
+}
@@ -0,0 +1,247 @@
+use std::{collections::HashMap, fs::{self, File}, io::{BufRead, BufReader, Read}, path::{Path, PathBuf}, process::Command, time::SystemTime};
+
+use crate::{archive::{self, ArchiveFormat}, config::{PreviewConfig, PreviewCommand}, directory::FileTypeEnum, git, ui::format::{format_quantity, format_size}};
+
+const PREVIEW_MAX_LINES: usize = 500;
+// Even a forced preview never reads more than this, so a single huge line can't stall the UI
+const PREVIEW_MAX_BYTES: u64 = 1024 * 1024;
+// Files are treated as binary if a NUL byte shows up this early
+const BINARY_PROBE_LEN: usize = 8192;
+const TAB_WIDTH: usize = 4;
+
+/// Produces preview lines for the selected entry: the output of a matching
+/// preview command, a directory listing, an archive's top-level entries, or the
+/// beginning of the file.
+/// Binary files and files above `max_size` are skipped unless forced.
+/// File previews are kept for the `cache_entries` most recently shown files.
+pub struct Previewer {
+    commands: Vec<PreviewCommand>,
+    max_size: u64,
+    forced: Option<PathBuf>,
+    cache: HashMap<PathBuf, CachedPreview>,
+    cache_entries: usize,
+    // Bumped on every lookup, so the entry with the lowest `used` is the least recently shown
+    clock: u64,
+}
+
+pub enum Preview {
+    Lines(Vec<String>),
+    Skipped { binary: bool, size: u64 },
+    /// FIFOs, sockets and devices, which are never read
+    Special(FileTypeEnum),
+}
+
+/// A preview stays valid while the file keeps the modification time and size it had.
+struct CachedPreview {
+    modified: SystemTime,
+    size: u64,
+    lines: Vec<String>,
+    used: u64,
+}
+
+impl Previewer {
+    pub fn new(config: &PreviewConfig) -> Self {
+        Self {
+            commands: config.commands.clone(),
+            max_size: config.max_size,
+            forced: None,
+            cache: HashMap::new(),
+            cache_entries: config.cache_entries,
+            clock: 0,
+        }
+    }
+
+    /// Previews `path` even if it's binary or too large.
+    pub fn force(&mut self, path: &Path) {
+        self.forced = Some(path.to_path_buf());
+    }
+
+    pub fn is_forced(&self, path: &Path) -> bool {
+        self.forced.as_deref() == Some(path)
+    }
+
+    pub fn preview(&mut self, path: &Path) -> Preview {
+        let Ok(metadata) = fs::metadata(path) else { return Preview::Lines(Vec::new()) };
+
+        if metadata.is_dir() {
+            return Preview::Lines(directory_preview(path));
+        }
+
+        let file_type = FileTypeEnum::of(metadata.file_type());
+
+        if file_type.is_special() {
+            return Preview::Special(file_type);
+        }
+
+        let command = self.commands.iter().find(|c| c.matcher.matches(path)).map(|c| c.command.clone());
+        let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+
+        // A zip lists its entries up front, while a tar has to be read through, so large ones wait for `v` like any file
+        let archive = ArchiveFormat::for_path(path)
+            .filter(|format| *format == ArchiveFormat::Zip || self.is_forced(path) || metadata.len() <= self.max_size);
+
+        if let Some(format) = archive.filter(|_| command.is_none()) {
+            if let Some(lines) = self.cached(path, modified, metadata.len()) {
+                return Preview::Lines(lines);
+            }
+
+            let lines = archive_preview(path, format);
+            self.remember(path, modified, metadata.len(), lines.clone());
+
+            return Preview::Lines(lines);
+        }
+
+        if command.is_none() && !self.is_forced(path) && metadata.len() > self.max_size {
+            return Preview::Skipped { binary: is_binary(path), size: metadata.len() };
+        }
+
+        if let Some(lines) = self.cached(path, modified, metadata.len()) {
+            return Preview::Lines(lines);
+        }
+
+        let lines = match command {
+            Some(command) => command_preview(&command, path),
+            // Binary files aren't cached, since a forced preview of one mustn't outlast the forcing
+            None if is_binary(path) => match self.is_forced(path) {
+                true => return Preview::Lines(file_preview(path)),
+                false => return Preview::Skipped { binary: true, size: metadata.len() },
+            },
+            None => file_preview(path),
+        };
+
+        self.remember(path, modified, metadata.len(), lines.clone());
+
+        Preview::Lines(lines)
+    }
+
+    fn cached(&mut self, path: &Path, modified: SystemTime, size: u64) -> Option<Vec<String>> {
+        self.clock += 1;
+
+        let cached = self.cache.get_mut(path).filter(|cached| cached.modified == modified && cached.size == size)?;
+        cached.used = self.clock;
+
+        Some(cached.lines.clone())
+    }
+
+    fn remember(&mut self, path: &Path, modified: SystemTime, size: u64, lines: Vec<String>) {
+        if self.cache_entries == 0 {
+            return;
+        }
+
+        if self.cache.len() >= self.cache_entries && !self.cache.contains_key(path) {
+            let oldest = self.cache.iter().min_by_key(|(_, cached)| cached.used).map(|(path, _)| path.clone());
+
+            if let Some(oldest) = oldest {
+                self.cache.remove(&oldest);
+            }
+        }
+
+        self.cache.insert(path.to_path_buf(), CachedPreview { modified, size, lines, used: self.clock });
+    }
+
+    /// The file's diff against HEAD. Not cached, since commits and staging change
+    /// it without touching the file.
+    pub fn diff(&self, path: &Path) -> Preview {
+        let lines = match git::diff(path) {
+            Ok(diff) if diff.is_empty() => vec!["No changes against HEAD".to_string()],
+            Ok(diff) => diff.lines().take(PREVIEW_MAX_LINES).map(sanitize_line).collect(),
+            Err(e) => vec![e.to_string()],
+        };
+
+        Preview::Lines(lines)
+    }
+}
+
+fn command_preview(command: &str, path: &Path) -> Vec<String> {
+    // The path is passed as $1 so it never has to be quoted into the command string
+    let script = command.replace("{}", "\"$1\"");
+
+    let output = match Command::new("sh").arg("-c").arg(script).arg("sh").arg(path).output() {
+        Ok(output) => output,
+        Err(e) => return vec![format!("Couldn't run preview command: {}", e)],
+    };
+
+    let text = if output.stdout.is_empty() { output.stderr } else { output.stdout };
+
+    String::from_utf8_lossy(&text)
+        .lines()
+        .take(PREVIEW_MAX_LINES)
+        .map(sanitize_line)
+        .collect()
+}
+
+fn directory_preview(path: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(path) else { return vec!["Couldn't read directory".to_string()] };
+
+    let mut names = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+    names.sort();
+    names.truncate(PREVIEW_MAX_LINES);
+
+    names
+}
+
+fn archive_preview(path: &Path, format: ArchiveFormat) -> Vec<String> {
+    let contents = match archive::contents(path, format) {
+        Ok(contents) => contents,
+        Err(e) => return vec![e.message()],
+    };
+
+    let mut lines = vec![
+        format!("{}, {} uncompressed", format_quantity(contents.files, "file", "files"), format_size(contents.size)),
+        String::new(),
+    ];
+
+    lines.extend(contents.top_level.iter().take(PREVIEW_MAX_LINES).map(|name| sanitize_line(name)));
+    lines
+}
+
+fn is_binary(path: &Path) -> bool {
+    let Ok(file) = File::open(path) else { return false };
+
+    let mut probe = Vec::with_capacity(BINARY_PROBE_LEN);
+    if file.take(BINARY_PROBE_LEN as u64).read_to_end(&mut probe).is_err() {
+        return false;
+    }
+
+    probe.contains(&0)
+}
+
+fn file_preview(path: &Path) -> Vec<String> {
+    let Ok(file) = File::open(path) else { return vec!["Couldn't open file".to_string()] };
+
+    BufReader::new(file.take(PREVIEW_MAX_BYTES))
+        .split(b'\n')
+        .take(PREVIEW_MAX_LINES)
+        .map_while(|line| line.ok())
+        .map(|line| sanitize_line(&String::from_utf8_lossy(&line)))
+        .collect()
+}
+
+// Drops ANSI escape sequences and control characters that would garble the terminal
+pub(crate) fn sanitize_line(line: &str) -> String {
+    let mut sanitized = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\u{1b}' => {
+                if chars.peek() == Some(&'[') {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            },
+            '\t' => sanitized.push_str(&" ".repeat(TAB_WIDTH)),
+            c if c.is_control() => {},
+            c => sanitized.push(c),
+        }
+    }
+
+    sanitized
+}
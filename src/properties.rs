@@ -0,0 +1,136 @@
+use std::{fs::{self, File, FileTimes}, path::Path, time::SystemTime};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+
+use crate::{my_errors::MyError, permissions::{self, ModeSpec}, xattrs};
+
+/// How times are shown and typed in the Properties dialog.
+pub const TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// A property of an entry that can be edited as text.
+#[derive(Clone, PartialEq)]
+pub enum Property {
+    Mode,
+    Owner,
+    Group,
+    Modified,
+    Accessed,
+    Xattr(String),
+    /// Typed as `name=value`
+    NewXattr,
+}
+
+impl Property {
+    pub fn label(&self) -> String {
+        match self {
+            Property::Mode => "Mode".to_string(),
+            Property::Owner => "Owner".to_string(),
+            Property::Group => "Group".to_string(),
+            Property::Modified => "Modified".to_string(),
+            Property::Accessed => "Accessed".to_string(),
+            Property::Xattr(name) => name.clone(),
+            Property::NewXattr => "New xattr".to_string(),
+        }
+    }
+
+    /// What the journal records the change as.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            Property::Mode => "Chmod",
+            Property::Owner | Property::Group => "Chown",
+            Property::Modified | Property::Accessed => "Touch",
+            Property::Xattr(_) | Property::NewXattr => "Set xattr",
+        }
+    }
+}
+
+/// A property's current value. Values that can't be edited as text are shown but kept.
+pub struct CurrentValue {
+    pub property: Property,
+    pub value: String,
+    pub editable: bool,
+}
+
+/// The editable properties of `path` as they are now; which ones there are depends on
+/// the platform.
+pub fn current(path: &Path) -> Result<Vec<CurrentValue>, MyError> {
+    let metadata = fs::metadata(path).map_err(|e| MyError::io(e, path))?;
+    let mut values = Vec::new();
+    let mut push = |property, value: String, editable| values.push(CurrentValue { property, value, editable });
+
+    if cfg!(unix) {
+        push(Property::Mode, format!("{:o}", permissions::mode_of(&metadata)), true);
+    }
+
+    if let Some(uid) = permissions::owner_of(&metadata) {
+        push(Property::Owner, permissions::user_name(uid), true);
+    }
+
+    if let Some(gid) = permissions::group_of(&metadata) {
+        push(Property::Group, permissions::group_name(gid), true);
+    }
+
+    push(Property::Modified, format_time(metadata.modified()), true);
+    push(Property::Accessed, format_time(metadata.accessed()), true);
+
+    if cfg!(unix) {
+        for (name, value) in xattrs::list(path) {
+            match value {
+                Some(value) => push(Property::Xattr(name), value, true),
+                None => push(Property::Xattr(name), "(binary)".to_string(), false),
+            }
+        }
+
+        push(Property::NewXattr, String::new(), true);
+    }
+
+    Ok(values)
+}
+
+/// Sets one property of `path` from its typed value. An empty value removes an
+/// extended attribute.
+pub fn apply(path: &Path, property: &Property, value: &str) -> Result<(), MyError> {
+    let value = value.trim();
+
+    match property {
+        Property::Mode => permissions::set_mode(path, &value.parse::<ModeSpec>()?, false).map(|_| ()),
+        Property::Owner => permissions::set_owner(path, Some(permissions::user_id(value)?), None),
+        Property::Group => permissions::set_owner(path, None, Some(permissions::group_id(value)?)),
+        Property::Modified => set_time(path, FileTimes::new().set_modified(parse_time(value)?)),
+        Property::Accessed => set_time(path, FileTimes::new().set_accessed(parse_time(value)?)),
+        Property::Xattr(name) if value.is_empty() => xattrs::remove(path, name),
+        Property::Xattr(name) => xattrs::set(path, name, value),
+        Property::NewXattr => match value.split_once('=') {
+            Some((name, value)) if !name.trim().is_empty() => xattrs::set(path, name.trim(), value.trim()),
+            _ => Err(MyError::FileError(format!("Type a new xattr as name=value, not {}", value))),
+        },
+    }
+}
+
+fn format_time(time: std::io::Result<SystemTime>) -> String {
+    match time {
+        Ok(time) => DateTime::<Local>::from(time).format(TIME_FORMAT).to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+// Seconds can be left out
+fn parse_time(value: &str) -> Result<SystemTime, MyError> {
+    let invalid = || MyError::FileError(format!("Invalid time {}, use YYYY-MM-DD HH:MM:SS", value));
+
+    let naive = NaiveDateTime::parse_from_str(value, TIME_FORMAT)
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .map_err(|_| invalid())?;
+
+    // A time skipped by a clock change doesn't exist locally; one repeated by it is taken the first time
+    let local = Local.from_local_datetime(&naive).earliest().ok_or_else(invalid)?;
+
+    Ok(SystemTime::from(local))
+}
+
+// Only the times given are changed, the other is left as it is
+fn set_time(path: &Path, times: FileTimes) -> Result<(), MyError> {
+    let file = File::open(path).map_err(|e| MyError::io(e, path))?;
+
+    file.set_times(times).map_err(|e| MyError::io(e, path))
+}
@@ -0,0 +1,57 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::batch::BatchKind;
+
+/// The register yanks, cuts and pastes use unless another one is picked with `"` first.
+pub const UNNAMED: char = '"';
+
+/// Entries staged to be copied (yanked) or moved (cut) and pasted later, like vim's
+/// registers: the unnamed one plus `a`–`z`, so several groups can wait for different
+/// destinations at once.
+#[derive(Default)]
+pub struct Registers {
+    registers: BTreeMap<char, Register>,
+}
+
+pub struct Register {
+    pub kind: BatchKind,
+    pub paths: Vec<PathBuf>,
+}
+
+impl Registers {
+    pub fn is_name(name: char) -> bool {
+        name == UNNAMED || name.is_ascii_lowercase()
+    }
+
+    /// Replaces what the register held.
+    pub fn store(&mut self, name: char, kind: BatchKind, paths: Vec<PathBuf>) {
+        self.registers.insert(name, Register { kind, paths });
+    }
+
+    pub fn get(&self, name: char) -> Option<&Register> {
+        self.registers.get(&name)
+    }
+
+    /// Drops the entries that no longer exist, and the register once none are left.
+    pub fn retain_existing(&mut self, name: char) {
+        if let Some(register) = self.registers.get_mut(&name) {
+            register.paths.retain(|path| path.symlink_metadata().is_ok());
+
+            if register.paths.is_empty() {
+                self.registers.remove(&name);
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registers.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.registers.len()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&char, &Register)> {
+        self.registers.iter()
+    }
+}
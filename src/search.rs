@@ -0,0 +1,102 @@
+use std::{fs, io, path::{Path, PathBuf}, process::Command};
+
+use walkdir::WalkDir;
+
+use crate::{config::SearchConfig, my_errors::MyError};
+
+// Files are treated as binary if a NUL byte shows up this early
+const BINARY_PROBE_LEN: usize = 8192;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum SearchKind {
+    Name,
+    Content,
+}
+
+/// Recursively searches `root`, using fd (names) or ripgrep (contents) when enabled
+/// and installed, and falling back to the built-in walker otherwise.
+pub fn search(root: &Path, pattern: &str, kind: SearchKind, config: &SearchConfig) -> Result<Vec<PathBuf>, MyError> {
+    let external = match kind {
+        SearchKind::Name if config.fd => run_external(fd_command(root, pattern, config.max_results)),
+        SearchKind::Content if config.ripgrep => run_external(rg_command(root, pattern)),
+        _ => None,
+    };
+
+    let mut results = match external {
+        Some(results) => results?,
+        None => builtin_search(root, pattern, kind, config.max_results),
+    };
+
+    results.sort();
+    results.truncate(config.max_results);
+
+    Ok(results)
+}
+
+fn fd_command(root: &Path, pattern: &str, max_results: usize) -> Command {
+    let mut command = Command::new("fd");
+    command
+        .args(["--fixed-strings", "--ignore-case", "--color", "never"])
+        .arg("--max-results")
+        .arg(max_results.to_string())
+        .arg("--")
+        .arg(pattern)
+        .arg(root);
+
+    command
+}
+
+fn rg_command(root: &Path, pattern: &str) -> Command {
+    let mut command = Command::new("rg");
+    command
+        .args(["--files-with-matches", "--fixed-strings", "--ignore-case", "--no-messages", "--color", "never"])
+        .arg("--")
+        .arg(pattern)
+        .arg(root);
+
+    command
+}
+
+// Returns None when the tool isn't installed, so the caller can fall back to the walker
+fn run_external(mut command: Command) -> Option<Result<Vec<PathBuf>, MyError>> {
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return None,
+        Err(e) => return Some(Err(MyError::CommandError(format!("Couldn't run search backend: {}", e)))),
+    };
+
+    let results = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+
+    Some(Ok(results))
+}
+
+fn builtin_search(root: &Path, pattern: &str, kind: SearchKind, max_results: usize) -> Vec<PathBuf> {
+    let pattern = pattern.to_lowercase();
+
+    WalkDir::new(root)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|entry| !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| match kind {
+            SearchKind::Name => entry.file_name().to_string_lossy().to_lowercase().contains(&pattern),
+            SearchKind::Content => entry.file_type().is_file() && file_contains(entry.path(), &pattern),
+        })
+        .take(max_results)
+        .map(|entry| entry.into_path())
+        .collect()
+}
+
+fn file_contains(path: &Path, pattern: &str) -> bool {
+    let Ok(contents) = fs::read(path) else { return false };
+
+    if contents.iter().take(BINARY_PROBE_LEN).any(|byte| *byte == 0) {
+        return false;
+    }
+
+    String::from_utf8_lossy(&contents).to_lowercase().contains(pattern)
+}
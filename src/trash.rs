@@ -0,0 +1,152 @@
+use std::path::PathBuf;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::{fs, path::Path};
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use chrono::{Duration, Local, NaiveDateTime};
+
+use crate::my_errors::MyError;
+
+#[cfg(all(unix, not(target_os = "macos")))]
+use crate::{batch, directory};
+
+#[cfg(all(unix, not(target_os = "macos")))]
+const INFO_EXTENSION: &str = "trashinfo";
+
+/// What emptying old items out of the trash removed.
+#[derive(Default)]
+pub struct PurgeSummary {
+    pub items: u64,
+    pub bytes: u64,
+    /// Items that were due but couldn't be removed
+    pub failed: u64,
+}
+
+/// What's in the trash, as the status bar shows it.
+#[derive(Default)]
+pub struct TrashSize {
+    pub items: u64,
+    pub bytes: u64,
+}
+
+/// The freedesktop trash of the current user.
+pub fn trash_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("Trash"))
+}
+
+/// Permanently removes everything trashed more than `days` days ago from the freedesktop
+/// trash in the data directory (`~/.local/share/Trash`), the one Linux and BSD desktops
+/// share. Items without a readable deletion date are left alone.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn purge_older_than(days: u64) -> Result<PurgeSummary, MyError> {
+    let cutoff = Local::now().naive_local() - Duration::days(days as i64);
+
+    purge_where(|info| deletion_date(info).is_some_and(|date| date < cutoff))
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn purge_older_than(_days: u64) -> Result<PurgeSummary, MyError> {
+    Err(MyError::FileError("Emptying the trash is only supported for the freedesktop trash on Linux and BSD".to_string()))
+}
+
+/// Permanently removes everything in the freedesktop trash.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn empty() -> Result<PurgeSummary, MyError> {
+    purge_where(|_| true)
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn empty() -> Result<PurgeSummary, MyError> {
+    Err(MyError::FileError("Emptying the trash is only supported for the freedesktop trash on Linux and BSD".to_string()))
+}
+
+/// Counts the items in the freedesktop trash and the bytes they take up.
+#[cfg(all(unix, not(target_os = "macos")))]
+pub fn size() -> Result<TrashSize, MyError> {
+    let mut size = TrashSize::default();
+
+    for (_, item) in trashed_items()? {
+        size.items += 1;
+        size.bytes += item_size(&item);
+    }
+
+    Ok(size)
+}
+
+#[cfg(not(all(unix, not(target_os = "macos"))))]
+pub fn size() -> Result<TrashSize, MyError> {
+    Err(MyError::FileError("The trash size is only known for the freedesktop trash on Linux and BSD".to_string()))
+}
+
+// Removes the items whose info file `due` accepts
+#[cfg(all(unix, not(target_os = "macos")))]
+fn purge_where(due: impl Fn(&Path) -> bool) -> Result<PurgeSummary, MyError> {
+    let mut summary = PurgeSummary::default();
+
+    for (info, item) in trashed_items()? {
+        if !due(&info) {
+            continue;
+        }
+
+        let bytes = item_size(&item);
+
+        // The info file goes last, so a failed removal is retried next time
+        let removed = match item.symlink_metadata().is_ok() {
+            true => batch::remove_path(&item).is_ok(),
+            false => true,
+        };
+
+        if removed && fs::remove_file(&info).is_ok() {
+            summary.items += 1;
+            summary.bytes += bytes;
+        } else {
+            summary.failed += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+// Each `.trashinfo` file with the item it describes, which may be gone already
+#[cfg(all(unix, not(target_os = "macos")))]
+fn trashed_items() -> Result<Vec<(PathBuf, PathBuf)>, MyError> {
+    let Some(trash) = trash_dir() else { return Ok(Vec::new()) };
+    let info_dir = trash.join("info");
+
+    if !info_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&info_dir).map_err(|e| MyError::io(e, &info_dir))?;
+
+    let items = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|info| info.extension().is_some_and(|extension| extension == INFO_EXTENSION))
+        .filter_map(|info| {
+            let item = trash.join("files").join(info.file_stem()?);
+            Some((info, item))
+        })
+        .collect();
+
+    Ok(items)
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn item_size(item: &Path) -> u64 {
+    match item.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => directory::dir_stats(item, u64::MAX, u64::MAX).size,
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    }
+}
+
+/// `DeletionDate` from a `.trashinfo` file, in local time as the spec has it.
+#[cfg(all(unix, not(target_os = "macos")))]
+fn deletion_date(info: &Path) -> Option<NaiveDateTime> {
+    let contents = fs::read_to_string(info).ok()?;
+    let value = contents.lines().find_map(|line| line.trim().strip_prefix("DeletionDate="))?;
+
+    NaiveDateTime::parse_from_str(value.trim(), "%Y-%m-%dT%H:%M:%S").ok()
+}
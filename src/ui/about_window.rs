@@ -0,0 +1,67 @@
+use std::{env::consts, path::PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::config;
+
+/// Version and environment details worth pasting into a bug report.
+pub struct AboutWindow;
+
+impl AboutWindow {
+    pub fn render_about(area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let profile = if cfg!(debug_assertions) { "debug" } else { "release" };
+
+        let lines = vec![
+            Line::from(format!(" dirman {}", env!("CARGO_PKG_VERSION")).bold()),
+            Line::from(""),
+            Line::from(vec![
+                " Build: ".blue(),
+                format!("{} build for {} {}", profile, consts::OS, consts::ARCH).into(),
+            ]),
+            Line::from(vec![
+                " Config: ".blue(),
+                describe_path(config::config_file(), "not found, using defaults").into(),
+            ]),
+            Line::from(vec![
+                " Plugins: ".blue(),
+                describe_path(config::plugins_dir(), "not found, no plugins").into(),
+            ]),
+            Line::from(vec![
+                " Data: ".blue(),
+                describe_path(config::data_dir(), "not created yet").into(),
+            ]),
+        ];
+
+        let bottom_title = Line::from(vec![
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" About "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        Paragraph::new(lines)
+            .block(block)
+            .left_aligned()
+            .render(area, buf);
+    }
+}
+
+fn describe_path(path: Option<PathBuf>, missing: &str) -> String {
+    match path {
+        Some(path) if path.exists() => path.display().to_string(),
+        Some(path) => format!("{} ({})", path.display(), missing),
+        None => "unknown on this system".to_string(),
+    }
+}
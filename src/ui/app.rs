@@ -1,29 +1,120 @@
 use core::fmt;
-use std::{io, vec};
+use std::{collections::{HashSet, VecDeque}, fs, io, ops::Range, path::{Path, PathBuf}, time::Duration, vec};
 use strum::IntoEnumIterator;
 
-use crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use globset::Glob;
 use ratatui::{
     buffer::Buffer,
     layout::{Constraint, Layout, Rect},
     style::{Color, Style, Stylize},
-    symbols::border, text::Line, widgets::{Block, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget}, DefaultTerminal
+    symbols::border, text::{Line, Span}, widgets::{Block, HighlightSpacing, List, ListItem, ListState, Paragraph, StatefulWidget, Widget}, DefaultTerminal
 };
 use strum_macros::EnumIter;
 
-use crate::directory::FileManager;
+use crate::{archive::{self, ArchiveFormat}, background::{BackgroundTask, FinishedTask}, batch::{self, BatchKind, BatchOutcome, BatchPlan, CopyOptions}, clipboard, bookmarks::Bookmarks, checksum::{self, Verification}, compression::{self, Codec}, config::{self, ColumnConfig, Config, CustomAction, MainLayout, View, ViewMemory}, dir_views::DirViews, encryption::{self, EncryptionKey, ENCRYPTED_EXTENSION}, expansion, finder::{self, TagColor}, directory::{self, DirScan, DirStats, SelectionSize, FileManager, FileSnapshot, FlatListing, PathMode, SortMode, Staleness}, external::ExternalCommand, filter::{Category, KindFilter, Recency}, follow::Follower, git::{self, GitWatcher}, hooks::{HookEvent, HookRunner}, journal::{Journal, JournalEntry}, marks::Marks, mounts, my_errors::MyError, opener::Opener, permissions::{self, Attribute, ModeSpec}, plugins::PluginManager, preview::{Preview, Previewer}, properties, registers::{self, Registers}, search::{self, SearchKind}, trash::{self, PurgeSummary, TrashSize}, undo::{UndoHistory, UndoOperation}, zoxide};
 
-use super::{help::HelpWindow, user_input::UserInput};
+use super::{about_window::AboutWindow, batch_window::BatchWindow, bookmark_window::BookmarkWindow, columns::{fit, Column}, checksum_window::ChecksumWindow, error_window::ErrorWindow, format::{format_count, format_duration, format_quantity, format_size, highlight_matches, shorten_path, MATCH_STYLE}, help::{self, HelpWindow}, journal_window::JournalWindow, preview_pane::PreviewPane, properties_window::PropertiesWindow, prompt::PromptKind, search_window::SearchWindow, sequence::{Chord, SequenceMatcher, SequenceStep}, setup_window::{self, SetupWindow}, task_window::{RunningTask, TaskWindow}, undo_window::UndoWindow, user_input::UserInput};
 
 //Styles
-const SELECTED_STYLE: Style = Style::new().bg(Color::Rgb(0x3f, 0x44, 0x50));
+pub const SELECTED_STYLE: Style = Style::new().bg(Color::Rgb(0x3f, 0x44, 0x50));
+// How often followed files and background tasks are checked while waiting for input
+const TICK_INTERVAL: Duration = Duration::from_millis(250);
+// Finished tasks kept for the task panel
+const MAX_FINISHED_TASKS: usize = 20;
+// Listed in place of the entries of a directory that can't be read
+const UNLISTABLE: &str = "Couldn't list directory";
+// Listings taking longer than this finish in the background, and can be stopped with Esc
+const SCAN_WAIT: Duration = Duration::from_millis(100);
+const SCANNING: &str = "Scanning…";
+// Frames of the spinner in the Files title while a directory is being listed, one per tick
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+// The largest file FAT32, the usual file system on flash drives, can hold
+const FAT32_MAX_FILE: u64 = 4 * 1024 * 1024 * 1024 - 1;
 
 pub struct App {
+    config: Config,
     dir: FileManager,
     file_list: FileList,
+    marked: Marks,
     select_list: SelectList,
     user_input: UserInput,
-    bookmarked: Bookmarked,
+    sequence: SequenceMatcher,
+    /// The entry an operation just created or renamed, selected once the listing has it
+    pending_selection: Option<String>,
+    registers: Registers,
+    /// The register picked with `"` for the next yank, cut or paste
+    register: Option<char>,
+    picking_register: bool,
+    /// The register being pasted, emptied once a cut is pasted
+    pasting_register: Option<char>,
+    bookmarks: Bookmarks,
+    bookmark_window: BookmarkWindow,
+    search_window: SearchWindow,
+    previewer: Previewer,
+    preview_pane: PreviewPane,
+    follower: Option<Follower>,
+    hooks: HookRunner,
+    /// The directory last entered: leave hooks run for it and its view is remembered when it's left
+    entered_dir: PathBuf,
+    /// A listing that didn't finish right away, and the directory last listed before it
+    scan: Option<DirScan>,
+    listed_dir: PathBuf,
+    /// The type of network file system the current directory is on, if it's on one
+    network_fs: Option<String>,
+    dir_views: DirViews,
+    /// The view at startup, given to directories that haven't been left with another
+    default_view: View,
+    /// Output of directory hooks, shown below errors
+    notice: String,
+    diff_paths: HashSet<PathBuf>,
+    opener: Opener,
+    journal: Journal,
+    journal_window: JournalWindow,
+    undo_history: UndoHistory,
+    undo_window: UndoWindow,
+    plugins: PluginManager,
+    suspended_command: Option<ExternalCommand>,
+    chmod_recursive: bool,
+    compress_codec: Codec,
+    keep_original: bool,
+    passphrase_input: bool,
+    tasks: Vec<BackgroundTask>,
+    verification: Option<BackgroundTask<Vec<Verification>>>,
+    /// Emptying items out of the trash, all of them or those trashed over so many days ago
+    trash_purge: Option<(BackgroundTask<PurgeSummary>, Option<u64>)>,
+    trash_measurement: Option<BackgroundTask<TrashSize>>,
+    trash_size: Option<TrashSize>,
+    size_calculation: Option<BackgroundTask<SelectionSize>>,
+    size_description: String,
+    finished_tasks: VecDeque<FinishedTask>,
+    task_window: TaskWindow,
+    /// The mode help was opened from, whose bindings it lists
+    help_mode: AppMode,
+    /// What `/` searches the listing for, kept after confirming for `n` and `N`
+    list_search: String,
+    /// The selection before the search started, where matches are looked for from
+    list_search_origin: usize,
+    checksum_window: ChecksumWindow,
+    batch_kind: BatchKind,
+    batch_window: BatchWindow,
+    /// The last batch that ran, kept until it's done to report and retry its failures
+    batch_outcome: Option<BatchOutcome>,
+    git: GitWatcher,
+    delete_confirmation: DeleteConfirmation,
+    /// The entry a pending rename or delete was started on
+    pending_target: Option<FileSnapshot>,
+    pending_move: Option<PendingMove>,
+    create_seed: CreateSeed,
+    /// Whether a directory being created is entered right away
+    enter_created: bool,
+    typed_name: TypedName,
+    stdin_content: Option<Vec<u8>>,
+    details: bool,
+    /// Inode, link count, blocks and device in Properties
+    extended_properties: bool,
+    setup_window: SetupWindow,
+    properties_window: PropertiesWindow,
     app_mode: AppMode,
     error_msg: String,
 }
@@ -33,24 +124,120 @@ struct FileList {
     state: ListState,
 }
 
+impl FileList {
+    /// The entries on screen in `height` rows, scrolled the way the list widget would to keep
+    /// the selection in view. Like the widget, pulls a selection past the end back to the last
+    /// entry, and keeps the scroll position for the next frame.
+    fn visible_rows(&mut self, height: usize) -> Range<usize> {
+        let Some(last) = self.items.len().checked_sub(1) else {
+            *self.state.offset_mut() = 0;
+            return 0..0;
+        };
+
+        if self.state.selected().is_some_and(|selected| selected > last) {
+            self.state.select(Some(last));
+        }
+
+        let mut offset = self.state.offset().min(last);
+
+        if let Some(selected) = self.state.selected().filter(|_| height > 0) {
+            if selected < offset {
+                offset = selected;
+            } else if selected >= offset + height {
+                offset = selected + 1 - height;
+            }
+        }
+
+        *self.state.offset_mut() = offset;
+        offset..(offset + height).min(self.items.len())
+    }
+}
+
 struct SelectList {
     items: Vec<String>,
     state: ListState,
 }
 
-struct Bookmarked {
-    full_path: String,
-    file_name: String,
+/// A rename or move whose destination is taken, waiting for both to be kept or the name changed.
+struct PendingMove {
+    rename: bool,
+    source: String,
+    destination: String,
+}
+
+/// How a pending delete has to be confirmed: `y` for files and small
+/// directories, the directory's name for large ones.
+#[derive(Default)]
+enum DeleteConfirmation {
+    #[default]
+    Simple,
+    TypedName(DirStats),
+}
+
+/// What a newly created file starts out with, cycled with `<Tab>` while naming it.
+#[derive(Default, Clone, Copy, PartialEq)]
+enum CreateSeed {
+    #[default]
+    Empty,
+    Clipboard,
+    Stdin,
+}
+
+/// What the create and rename prompts say about the typed name, worked out as it's typed
+/// rather than on every frame.
+#[derive(Default)]
+struct TypedName {
+    /// The mode a created entry will get, spelled out
+    mode: String,
+    error: Option<String>,
+}
+
+/// A panel of the main screen that can have the keyboard, cycled with `<Tab>`/`<Shift-Tab>`.
+/// Windows and prompts opened on top take the keys from whichever panel has them.
+#[derive(Clone, Copy, PartialEq)]
+enum Panel {
+    Files,
+    Preview,
+}
+
+impl Panel {
+    const ORDER: [Panel; 2] = [Panel::Files, Panel::Preview];
+
+    fn mode(&self) -> AppMode {
+        match self {
+            Panel::Files => AppMode::Files,
+            Panel::Preview => AppMode::Preview,
+        }
+    }
 }
 
 #[derive(Debug, EnumIter)]
 enum FileAction {
+    Open,
+    Edit,
+    Copy,
+    Move,
+    SendTo,
     Delete,
     Rename,
-    Bookmark,
+    EditLinkTarget,
+    Properties,
+    Yank,
+    Cut,
+    Run,
+    Compress,
+    Decompress,
+    Extract,
+    Encrypt,
+    Decrypt,
+    Verify,
+    Stage,
+    Unstage,
+    Discard,
+    CalculateSize,
 }
 
-#[derive(PartialEq, PartialOrd)]
+#[derive(Clone, Copy, PartialEq, PartialOrd)]
 pub enum AppMode {
     Exit,
     Files,
@@ -59,15 +246,42 @@ pub enum AppMode {
     Delete,
     Create,
     Help,
-}
-
-impl Default for Bookmarked {
-    fn default() -> Self {
-        Self {
-            full_path: String::default(),
-            file_name: String::default(),
-        }
-    }
+    Bookmarks,
+    RenameBookmark,
+    Command,
+    Search,
+    SearchResults,
+    Journal,
+    Undo,
+    Permissions,
+    Preview,
+    PreviewSearch,
+    RunArgs,
+    Compress,
+    Decompress,
+    Encrypt,
+    Decrypt,
+    Checksums,
+    Discard,
+    Filter,
+    ExtensionFilter,
+    Views,
+    MarkGlob,
+    CreateCollision,
+    MoveCollision,
+    BatchDestination,
+    BatchPreview,
+    Tasks,
+    Attributes,
+    FinderTags,
+    About,
+    ErrorDetails,
+    ListSearch,
+    LinkTarget,
+    Setup,
+    RelocateBookmark,
+    Properties,
+    EmptyTrash,
 }
 
 impl fmt::Display for FileAction {
@@ -81,9 +295,28 @@ impl std::str::FromStr for FileAction {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
+            "Open" => Ok(FileAction::Open),
+            "Edit" => Ok(FileAction::Edit),
             "Delete" => Ok(FileAction::Delete),
             "Rename" => Ok(FileAction::Rename),
-            "Bookmark" => Ok(FileAction::Bookmark),
+            "EditLinkTarget" => Ok(FileAction::EditLinkTarget),
+            "Properties" => Ok(FileAction::Properties),
+            "Yank" => Ok(FileAction::Yank),
+            "Cut" => Ok(FileAction::Cut),
+            "Run" => Ok(FileAction::Run),
+            "Compress" => Ok(FileAction::Compress),
+            "Decompress" => Ok(FileAction::Decompress),
+            "Extract" => Ok(FileAction::Extract),
+            "Encrypt" => Ok(FileAction::Encrypt),
+            "Decrypt" => Ok(FileAction::Decrypt),
+            "Verify" => Ok(FileAction::Verify),
+            "Stage" => Ok(FileAction::Stage),
+            "Unstage" => Ok(FileAction::Unstage),
+            "Discard" => Ok(FileAction::Discard),
+            "Copy" => Ok(FileAction::Copy),
+            "Move" => Ok(FileAction::Move),
+            "SendTo" => Ok(FileAction::SendTo),
+            "CalculateSize" => Ok(FileAction::CalculateSize),
             _ => Err(()),
         }
     }
@@ -100,76 +333,712 @@ impl Default for SelectList {
 
 impl Default for App {
     fn default() -> Self {
-        let dir = FileManager::default();
+        App::new(FileManager::default())
+    }
+}
+
+impl App {
+    pub fn new(mut dir: FileManager) -> Self {
+        // Read before any background thread could be creating files
+        permissions::umask();
+
+        let (plugins, plugin_errors) = PluginManager::load();
+        let mut error_msg = match plugin_errors.first() {
+            Some(e) => e.to_string(),
+            None => String::default(),
+        };
+
+        let first_run = Config::is_missing();
+
+        let config = match Config::load() {
+            Ok(config) => config,
+            Err(e) => {
+                error_msg = e.to_string();
+                Config::default()
+            }
+        };
+
+        let bookmarks = match Bookmarks::load() {
+            Ok(bookmarks) => bookmarks,
+            Err(e) => {
+                error_msg = e.to_string();
+                Bookmarks::default()
+            }
+        };
+
+        if config.paths.physical {
+            dir.set_path_mode(PathMode::Physical);
+        }
+
+        dir.set_always_hidden(config.listing.always_hidden.clone());
+
+        if let Err(e) = dir.set_ignore(&config.listing.ignore) {
+            error_msg = e.to_string();
+        }
+
+        dir.set_show_hidden(config.listing.show_hidden);
+
         let items = match dir.dir_contents() {
             Ok(contents) => contents,
-            Err(_) => vec!["No such directory".to_string()],
+            Err(e) => {
+                error_msg = e.to_string();
+                vec![UNLISTABLE.to_string()]
+            },
+        };
+
+        let mut git = GitWatcher::default();
+        git.refresh(&dir.current_path());
+
+        let mut hooks = HookRunner::new(config.hooks.clone());
+        let entered_dir = dir.current_path();
+        hooks.run(HookEvent::Enter, &entered_dir);
+
+        let dir_views = match DirViews::load(config.listing.remember_views == ViewMemory::Persist) {
+            Ok(dir_views) => dir_views,
+            Err(e) => {
+                error_msg = e.to_string();
+                DirViews::default()
+            }
         };
 
-        Self {
+        let previewer = Previewer::new(&config.preview);
+        let opener = Opener::new(&config.opener);
+        let details = config.listing.details;
+
+        let mut app = Self {
+            config,
             dir,
-            file_list: FileList { items, state: ListState::default() },
+            file_list: FileList { items, state: ListState::default().with_selected(Some(0)) },
+            marked: Marks::default(),
             select_list: SelectList::default(),
             user_input: UserInput::default(),
-            bookmarked: Bookmarked::default(),
-            app_mode: AppMode::Files,
-            error_msg: String::default(),
-        }
+            sequence: SequenceMatcher::default(),
+            pending_selection: None,
+            registers: Registers::default(),
+            register: None,
+            picking_register: false,
+            pasting_register: None,
+            bookmarks,
+            bookmark_window: BookmarkWindow::default(),
+            search_window: SearchWindow::default(),
+            previewer,
+            preview_pane: PreviewPane::default(),
+            follower: None,
+            hooks,
+            listed_dir: entered_dir.clone(),
+            network_fs: mounts::network_fs(&entered_dir),
+            entered_dir,
+            scan: None,
+            dir_views,
+            default_view: View::default(),
+            notice: String::new(),
+            diff_paths: HashSet::new(),
+            opener,
+            journal: Journal::default(),
+            journal_window: JournalWindow::default(),
+            undo_history: UndoHistory::default(),
+            undo_window: UndoWindow::default(),
+            plugins,
+            suspended_command: None,
+            chmod_recursive: false,
+            compress_codec: Codec::Gzip,
+            keep_original: true,
+            passphrase_input: true,
+            tasks: Vec::new(),
+            verification: None,
+            trash_purge: None,
+            trash_measurement: None,
+            trash_size: None,
+            size_calculation: None,
+            size_description: String::new(),
+            finished_tasks: VecDeque::new(),
+            task_window: TaskWindow::default(),
+            help_mode: AppMode::Files,
+            list_search: String::new(),
+            list_search_origin: 0,
+            checksum_window: ChecksumWindow::default(),
+            batch_kind: BatchKind::Copy,
+            batch_window: BatchWindow::default(),
+            batch_outcome: None,
+            git,
+            delete_confirmation: DeleteConfirmation::default(),
+            pending_target: None,
+            pending_move: None,
+            create_seed: CreateSeed::default(),
+            enter_created: false,
+            typed_name: TypedName::default(),
+            stdin_content: None,
+            details,
+            extended_properties: false,
+            setup_window: SetupWindow::default(),
+            properties_window: PropertiesWindow::default(),
+            app_mode: if first_run { AppMode::Setup } else { AppMode::Files },
+            error_msg,
+        };
+
+        // Directories fall back to how the listing starts out, and the first one may have been left differently before
+        app.default_view = app.current_view();
+        app.recall_dir_view(&app.dir.current_path());
+        app.start_trash_purge();
+        app.check_bookmarks();
+
+        app
     }
-}
 
-impl App {
-    pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        self.file_list.state.select(Some(0));
+    /// Opens the Create prompt with `contents` (e.g. piped into `--create-from-stdin`)
+    /// as the new file's initial content.
+    pub fn create_from_stdin(&mut self, contents: Vec<u8>) {
+        self.stdin_content = Some(contents);
+        self.create_seed = CreateSeed::Stdin;
+        self.user_input = UserInput::default();
+        self.app_mode = AppMode::Create;
+    }
 
-        while self.app_mode != AppMode::Exit {
+    pub fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while self.is_running() {
             terminal.draw(|frame| frame.render_widget(&mut self, frame.area()))?;
             self.handle_events()?;
 
-            self.file_list.items = match self.dir.dir_contents() {
-                Ok(contents) => contents,
-                Err(_) => vec!["No such directory".to_string()],
-            };
+            if let Some(command) = self.take_suspended_command() {
+                self.run_suspended(terminal, command)?;
+            }
         }
         
         Ok(())
     }
 
+    /// Terminal commands can't run while the UI owns the screen, so they are
+    /// queued here for whoever drives the app to run with the terminal restored.
+    pub fn take_suspended_command(&mut self) -> Option<ExternalCommand> {
+        self.suspended_command.take()
+    }
+
+    fn run_suspended(&mut self, terminal: &mut DefaultTerminal, command: ExternalCommand) -> io::Result<()> {
+        ratatui::restore();
+        let result = command.run();
+        *terminal = ratatui::init();
+        terminal.clear()?;
+
+        match result {
+            Ok(()) if command.report_exit => self.push_notice("Exited with code 0"),
+            Ok(()) => {},
+            Err(e) => self.error_msg = e.to_string(),
+        }
+
+        Ok(())
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.app_mode != AppMode::Exit
+    }
+
     fn handle_events(&mut self) -> io::Result<()> {
-        match event::read()? {
-            event::Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+        // Followed files and background tasks have to be checked periodically, so input can't be waited on forever
+        let needs_ticks = self.follower.is_some() || !self.tasks.is_empty() || self.verification.is_some() || self.hooks.is_running()
+            || self.trash_purge.is_some() || self.trash_measurement.is_some() || self.size_calculation.is_some() || self.git.is_fetching() || self.scan.is_some()
+            || self.sequence.is_pending();
+
+        if needs_ticks && !event::poll(TICK_INTERVAL)? {
+            self.tick();
+            return Ok(());
+        }
+
+        let event = event::read()?;
+        self.handle_event(event);
+
+        Ok(())
+    }
+
+    /// Picks up changes that happen without input, such as lines appended to a followed
+    /// file or background tasks finishing.
+    pub fn tick(&mut self) {
+        self.expire_sequence();
+        self.finish_tasks();
+        self.finish_verification();
+        self.finish_trash_purge();
+        self.finish_trash_measurement();
+        self.finish_size_calculation();
+        self.git.collect_commits();
+        self.collect_hook_output();
+
+        if self.scan.is_some() {
+            self.refresh_files();
+        }
+
+        let Some(follower) = &mut self.follower else { return };
+
+        if let Err(e) = follower.poll() {
+            self.error_msg = e.to_string();
+            self.stop_following();
+        }
+    }
+
+    fn finish_verification(&mut self) {
+        // Results wait until nothing else is going on, so the popup doesn't cut into a prompt
+        if self.app_mode != AppMode::Files {
+            return;
+        }
+
+        let Some(task) = &self.verification else { return };
+        let Some(result) = task.try_result() else { return };
+
+        let finished = task.finished(result.as_ref().err().map(|e| e.to_string()));
+        let sidecar = task.source.file_name().unwrap_or_default().to_string_lossy().to_string();
+        self.push_finished(finished);
+
+        match result {
+            Ok(results) => {
+                self.checksum_window.sidecar = sidecar;
+                self.checksum_window.results = results;
+                self.checksum_window.state.select(Some(0));
+                self.app_mode = AppMode::Checksums;
+            },
+            Err(e) => self.error_msg = e.to_string(),
+        }
+
+        self.verification = None;
+    }
+
+    fn start_trash_purge(&mut self) {
+        let Some(days) = self.config.delete.purge_trash_after_days else {
+            self.measure_trash();
+            return;
+        };
+        let trash = trash::trash_dir().unwrap_or_default();
+
+        self.trash_purge = Some((BackgroundTask::spawn("Empty trash", trash, move || trash::purge_older_than(days)), Some(days)));
+    }
+
+    /// Asks before emptying the whole trash, with what's in it.
+    fn confirm_empty_trash(&mut self) {
+        if self.trash_purge.is_some() {
+            return;
+        }
+
+        match &self.trash_size {
+            Some(size) if size.items > 0 => {
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::EmptyTrash;
+            },
+            Some(_) => self.push_notice("The trash is already empty"),
+            // Not measured yet, or a trash this platform doesn't support, which emptying reports
+            None => self.empty_trash(),
+        }
+    }
+
+    fn empty_trash(&mut self) {
+        let trash = trash::trash_dir().unwrap_or_default();
+
+        self.trash_purge = Some((BackgroundTask::spawn("Empty trash", trash, trash::empty), None));
+    }
+
+    fn finish_trash_purge(&mut self) {
+        let Some((task, days)) = &self.trash_purge else { return };
+        let Some(result) = task.try_result() else { return };
+        let age = match days {
+            Some(days) => format!(" trashed over {} days ago", days),
+            None => String::new(),
+        };
+
+        match result {
+            Ok(summary) if summary.items > 0 || summary.failed > 0 => {
+                let mut notice = format!("Emptied {} ({}){}", format_quantity(summary.items, "item", "items"), format_size(summary.bytes), age);
+
+                if summary.failed > 0 {
+                    notice.push_str(&format!(", {} couldn't be removed", summary.failed));
+                }
+
+                self.push_notice(&notice);
+            },
+            Ok(_) => {},
+            Err(e) => self.error_msg = e.to_string(),
+        }
+
+        self.trash_purge = None;
+        self.measure_trash();
+    }
+
+    fn measure_trash(&mut self) {
+        let trash = trash::trash_dir().unwrap_or_default();
+
+        self.trash_measurement = Some(BackgroundTask::spawn("Measure trash", trash, trash::size));
+    }
+
+    // Platforms without a supported trash simply don't show its size
+    fn finish_trash_measurement(&mut self) {
+        let Some(task) = &self.trash_measurement else { return };
+        let Some(result) = task.try_result() else { return };
+
+        self.trash_size = result.ok();
+        self.trash_measurement = None;
+    }
+
+    /// Totals the marked entries, or the selected one, in the background; the result
+    /// arrives as a notice.
+    fn calculate_size(&mut self) {
+        let paths = self.target_paths();
+        let description = match self.marked.len() {
+            0 => self.selected_file_name(),
+            count => format_quantity(count as u64, "marked item", "marked items"),
+        };
+
+        let source = match paths.as_slice() {
+            [path] => path.clone(),
+            _ => self.dir.current_path(),
+        };
+
+        self.size_calculation = Some(BackgroundTask::spawn_with_progress("Size", source, move |progress| {
+            Ok(directory::selection_size(&paths, progress))
+        }));
+        self.size_description = description;
+    }
+
+    fn finish_size_calculation(&mut self) {
+        let Some(task) = &self.size_calculation else { return };
+        let Some(result) = task.try_result() else { return };
+
+        match result {
+            Ok(size) => {
+                let mut notice = format!(
+                    "{}: {} ({} B), {} in {}, largest {}",
+                    self.size_description,
+                    format_size(size.bytes),
+                    format_count(size.bytes),
+                    format_quantity(size.files, "file", "files"),
+                    format_quantity(size.directories, "directory", "directories"),
+                    format_size(size.largest_file),
+                );
+
+                if size.largest_file > FAT32_MAX_FILE {
+                    notice.push_str(", too large for FAT32");
+                }
+
+                if size.unreadable > 0 {
+                    notice.push_str(&format!(", {} couldn't be read", format_quantity(size.unreadable, "entry", "entries")));
+                }
+
+                self.push_notice(&notice);
+            },
+            Err(e) => self.error_msg = e.to_string(),
+        }
+
+        self.size_calculation = None;
+    }
+
+    fn finish_tasks(&mut self) {
+        let mut index = 0;
+
+        while index < self.tasks.len() {
+            let Some(result) = self.tasks[index].try_result() else {
+                index += 1;
+                continue;
+            };
+
+            let task = self.tasks.remove(index);
+
+            // Moves that had to copy across file systems
+            if let Some(destination) = result.as_ref().ok().filter(|_| task.operation == "Move") {
+                self.undo_history.push(UndoOperation::Move { from: task.source.clone(), to: destination.clone() });
+            }
+
+            let source = task.source.to_string_lossy().to_string();
+            let destination = result.as_ref().map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+            let result = result.map(|_| ());
+
+            // Deletions produce nothing
+            self.record(&task.operation, &source, Some(destination.as_str()).filter(|destination| !destination.is_empty()), &result);
+            self.push_finished(task.finished(result.as_ref().err().map(|e| e.to_string())));
+
+            if let Err(e) = &result {
+                self.error_msg = e.to_string();
+            }
+
+            if self.batch_outcome.as_mut().is_some_and(|outcome| outcome.finish(&task.source, &result)) {
+                self.report_batch();
+            }
+
+            self.refresh_files();
+        }
+    }
+
+    /// Stops the running task selected in the task panel, if its work can be stopped.
+    fn cancel_task(&mut self) {
+        let Some(index) = self.task_window.state.selected() else { return };
+
+        let progress = match self.tasks.get(index) {
+            Some(task) => &task.progress,
+            None if index == self.tasks.len() => match &self.verification {
+                Some(task) => &task.progress,
+                None => return,
+            },
+            None => return,
+        };
+
+        if !progress.cancel() {
+            self.error_msg = MyError::FileError("Only deletions can be stopped".to_string()).to_string();
+        }
+    }
+
+    fn push_finished(&mut self, task: FinishedTask) {
+        if self.finished_tasks.len() == MAX_FINISHED_TASKS {
+            self.finished_tasks.pop_front();
+        }
+
+        self.finished_tasks.push_back(task);
+    }
+
+    pub fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
                 self.handle_key_event(key_event);
+
+                if matches!(self.app_mode, AppMode::Create | AppMode::Rename) {
+                    self.check_typed_name();
+                }
             }
             _ => {}
         }
 
-        Ok(())
+        // Listings on network file systems are only refreshed on request, for another directory
+        // or to show what was just created or renamed
+        if !self.is_cautious() || self.scan.is_some() || self.listed_dir != self.dir.current_path() || self.pending_selection.is_some() {
+            self.refresh_files();
+            self.apply_pending_selection();
+        }
+    }
+
+    /// Has the cursor follow `path` once the listing is refreshed, if it's listed here.
+    fn select_after_refresh(&mut self, path: &Path) {
+        if let Ok(relative) = path.strip_prefix(self.dir.current_path()) {
+            self.pending_selection = Some(relative.to_string_lossy().to_string());
+        }
+    }
+
+    // An entry created inside a new directory selects that directory, while one that isn't
+    // listed, say because a filter hides it, leaves the cursor where it was
+    fn apply_pending_selection(&mut self) {
+        let Some(name) = self.pending_selection.take() else { return };
+        let outermost = name.split(std::path::MAIN_SEPARATOR).next().unwrap_or_default();
+
+        let index = self.file_list.items.iter().position(|item| *item == name)
+            .or_else(|| self.file_list.items.iter().position(|item| item == outermost));
+
+        if let Some(index) = index {
+            self.file_list.state.select(Some(index));
+            self.selection_changed();
+        }
+    }
+
+    /// The panel with the keyboard, or None while a window or prompt has it.
+    fn focused_panel(&self) -> Option<Panel> {
+        match self.app_mode {
+            AppMode::Files => Some(Panel::Files),
+            AppMode::Preview | AppMode::PreviewSearch => Some(Panel::Preview),
+            _ => None,
+        }
+    }
+
+    fn focus(&mut self, panel: Panel) {
+        self.app_mode = panel.mode();
+    }
+
+    fn cycle_focus(&mut self, forward: bool) {
+        let Some(panel) = self.focused_panel() else { return };
+        let index = Panel::ORDER.iter().position(|candidate| *candidate == panel).unwrap_or(0);
+        let count = Panel::ORDER.len();
+
+        let next = match forward {
+            true => (index + 1) % count,
+            false => (index + count - 1) % count,
+        };
+
+        self.focus(Panel::ORDER[next]);
+    }
+
+    fn is_cautious(&self) -> bool {
+        self.config.network.cautious && self.network_fs.is_some()
+    }
+
+    fn refresh_files(&mut self) {
+        let current_path = self.dir.current_path();
+
+        // A scan of this directory still running is only checked on, anything else starts over
+        let (scan, wait) = match self.scan.take() {
+            Some(scan) if scan.dir == current_path => (scan, Duration::ZERO),
+            _ => (DirScan::start(&self.dir), SCAN_WAIT),
+        };
+
+        self.file_list.items = match scan.wait(wait) {
+            Some(Ok(contents)) => {
+                self.listed_dir = current_path.clone();
+                contents
+            },
+            Some(Err(e)) => {
+                self.error_msg = e.to_string();
+                vec![UNLISTABLE.to_string()]
+            },
+            None => {
+                self.scan = Some(scan);
+                vec![SCANNING.to_string()]
+            },
+        };
+
+        self.git.refresh(&current_path);
+    }
+
+    /// Gives up on the running scan and goes back to the directory listed before it,
+    /// or to the parent when it's the same directory that became slow to list.
+    fn cancel_scan(&mut self) {
+        let Some(scan) = self.scan.take() else { return };
+
+        match self.listed_dir == scan.dir {
+            true => self.dir.previous_path(),
+            false => self.dir.change_path(self.listed_dir.clone()),
+        }
+
+        self.error_msg = MyError::FileError(format!("Stopped listing {}", scan.dir.display())).to_string();
+        self.relist();
+        self.directory_changed();
     }
 
     // Handling key press events
 
+    fn is_text_input(&self) -> bool {
+        matches!(
+            self.app_mode,
+            AppMode::Rename | AppMode::Delete | AppMode::Create | AppMode::RenameBookmark | AppMode::RelocateBookmark
+                | AppMode::Command | AppMode::Search | AppMode::Permissions | AppMode::PreviewSearch | AppMode::RunArgs
+                | AppMode::Compress | AppMode::Decompress | AppMode::Encrypt | AppMode::Decrypt | AppMode::Discard | AppMode::EmptyTrash
+                | AppMode::Filter | AppMode::ExtensionFilter | AppMode::Views | AppMode::MarkGlob | AppMode::CreateCollision | AppMode::MoveCollision | AppMode::BatchDestination
+                | AppMode::Attributes | AppMode::FinderTags | AppMode::ListSearch | AppMode::LinkTarget | AppMode::Properties
+        )
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) {
         match key_event.code {
-            KeyCode::Char('q') => self.app_mode = AppMode::Exit,
-            KeyCode::Char('?') => self.app_mode = AppMode::Help,
+            KeyCode::Char('q') if !self.is_text_input() => self.quit(),
+            KeyCode::Char('?') if !self.is_text_input() && self.app_mode != AppMode::Help => {
+                self.help_mode = self.app_mode;
+                self.app_mode = AppMode::Help;
+            },
             code => {
                 match self.app_mode {
                     AppMode::Files => {
                         match code {
+                            KeyCode::Char(name) if self.picking_register => self.pick_register(name),
+                            _ if self.picking_register => self.picking_register = false,
+                            code if self.feed_sequence(code) => {},
                             KeyCode::Up | KeyCode::Char('k') => self.select_previous_file(),
                             KeyCode::Down | KeyCode::Char('j') => self.select_next_file(),
-                            KeyCode::Char('a') => self.app_mode = AppMode::Create,
+                            KeyCode::Char('a') => {
+                                self.create_seed = CreateSeed::Empty;
+                                self.enter_created = false;
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::Create;
+                            },
                             KeyCode::Char('m') => self.move_into(),
+                            KeyCode::Char('o') => self.open_file(),
+                            KeyCode::Char('E') => self.edit_file(),
+                            KeyCode::Char('v') => self.force_preview(),
+                            KeyCode::Char('p') => self.focus(Panel::Preview),
+                            KeyCode::Char('x') => self.toggle_executable(),
+                            KeyCode::Char('W') => self.enter_attributes(),
+                            KeyCode::Char('t') => self.enter_finder_tags(),
+                            KeyCode::Char('d') => self.toggle_diff(),
+                            KeyCode::Char('"') => self.picking_register = true,
+                            KeyCode::Char('y') => self.stage(BatchKind::Copy),
+                            KeyCode::Char('X') => self.stage(BatchKind::Move),
+                            KeyCode::Char(' ') => self.toggle_mark(),
+                            KeyCode::Esc if self.scan.is_some() => self.cancel_scan(),
+                            KeyCode::Esc => self.marked.clear(),
+                            KeyCode::Char('A') => self.mark_all(),
+                            KeyCode::Char('I') => self.invert_marks(),
+                            KeyCode::Char('+') => {
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::MarkGlob;
+                            },
                             KeyCode::Char('-') => self.move_out(),
-                            KeyCode::Char('b') => self.move_bookmarked(),
+                            KeyCode::Char('r') if key_event.modifiers.contains(KeyModifiers::CONTROL) => self.refresh_files(),
+                            KeyCode::Char('r') => self.move_to_project_root(),
+                            KeyCode::Char('L') => self.toggle_flat_listing(),
+                            KeyCode::Char('R') => self.retry_failed_batch(),
+                            KeyCode::Char('/') => {
+                                self.user_input = UserInput::default();
+                                self.list_search.clear();
+                                self.list_search_origin = self.file_list.state.selected().unwrap_or(0);
+                                self.app_mode = AppMode::ListSearch;
+                            },
+                            KeyCode::Char('n') => self.next_list_match(true),
+                            KeyCode::Char('N') => self.next_list_match(false),
+                            KeyCode::Char('|') => {
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::Filter;
+                                self.set_text_filter(String::new());
+                            },
+                            KeyCode::Char('c') => self.cycle_category_filter(),
+                            KeyCode::Char('w') => self.cycle_recency_filter(),
+                            KeyCode::Char('s') => {
+                                let (sort, descending) = self.dir.sort();
+                                self.dir.set_sort(sort.next(), descending);
+                                self.relist();
+                            },
+                            KeyCode::Char('S') => {
+                                let (sort, descending) = self.dir.sort();
+                                self.dir.set_sort(sort, !descending);
+                                self.relist();
+                            },
+                            KeyCode::Char('.') => {
+                                self.dir.set_show_hidden(!self.dir.shows_hidden());
+                                self.relist();
+                            },
+                            KeyCode::Char(',') => {
+                                self.dir.set_show_ignored(!self.dir.shows_ignored());
+                                self.relist();
+                            },
+                            KeyCode::Char('V') => self.app_mode = AppMode::Views,
+                            KeyCode::Char('D') => self.details = !self.details,
+                            KeyCode::Home => self.select_first_file(),
+                            KeyCode::End | KeyCode::Char('G') => self.select_last_file(),
+                            KeyCode::Char('i') => self.extended_properties = !self.extended_properties,
+                            KeyCode::Char('e') => {
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::ExtensionFilter;
+                            },
+                            // Miller columns move between directories sideways instead of expanding in place
+                            KeyCode::Right | KeyCode::Char('l') if self.config.listing.layout == MainLayout::Miller => self.move_into(),
+                            KeyCode::Left | KeyCode::Char('h') if self.config.listing.layout == MainLayout::Miller => self.move_out(),
+                            KeyCode::Right | KeyCode::Char('l') => self.expand_selected(),
+                            KeyCode::Left | KeyCode::Char('h') => self.collapse_selected(),
+                            KeyCode::Tab => self.cycle_focus(true),
+                            KeyCode::BackTab => self.cycle_focus(false),
+                            KeyCode::Char('P') => self.toggle_path_mode(),
+                            KeyCode::Char('b') => self.paste_register(),
+                            KeyCode::Char('B') => self.open_bookmarks(),
+                            KeyCode::Char(digit @ '1'..='9') => self.jump_to_slot(digit as u8 - b'0'),
+                            KeyCode::Char('J') => self.open_journal(),
+                            KeyCode::Char('T') => self.open_tasks(),
+                            KeyCode::Char('Z') => self.confirm_empty_trash(),
+                            KeyCode::Char('u') => self.open_undo_history(),
+                            KeyCode::Char(':') => {
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::Command;
+                            },
+                            KeyCode::Char('f') => self.enter_search(SearchKind::Name),
+                            KeyCode::Char('F') => self.enter_search(SearchKind::Content),
+                            KeyCode::Enter if !self.error_msg.is_empty() => self.app_mode = AppMode::ErrorDetails,
                             KeyCode::Enter => self.enter_select_menu(),
                             _ => {}
                         }
                     },
                     AppMode::Select => {
                         match code {
+                            code if self.feed_sequence(code) => {},
                             KeyCode::Up | KeyCode::Char('k') => self.select_previous_action(),
                             KeyCode::Down | KeyCode::Char('j') => self.select_next_action(),
+                            KeyCode::Home => self.select_list.state.select_first(),
+                            KeyCode::End | KeyCode::Char('G') => {
+                                self.select_list.state.select(Some(self.select_list.items.len().saturating_sub(1)));
+                            },
                             KeyCode::Enter => self.select_menu(),
                             KeyCode::Esc => self.exit_select_menu(),
                             _ => {}
@@ -177,9 +1046,32 @@ impl App {
                     },
                     AppMode::Rename => {
                         match code {
-                            KeyCode::Enter => {
-                                self.rename_file();
-                                self.app_mode = AppMode::Files;
+                            KeyCode::Enter => match self.validate_typed_name() {
+                                Err(e) => self.error_msg = e.to_string(),
+                                Ok(_) => match self.check_pending_target() {
+                                    Staleness::Unchanged => {
+                                        self.app_mode = AppMode::Files;
+                                        self.rename_file();
+                                    },
+                                    Staleness::Changed => {},
+                                    Staleness::Vanished => self.app_mode = AppMode::Files,
+                                },
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::LinkTarget => {
+                        match code {
+                            KeyCode::Enter => match self.check_pending_target() {
+                                Staleness::Unchanged => {
+                                    self.app_mode = AppMode::Files;
+                                    self.retarget_link();
+                                },
+                                Staleness::Changed => {},
+                                Staleness::Vanished => self.app_mode = AppMode::Files,
                             },
                             KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
                             KeyCode::Backspace => self.user_input.delete_char(),
@@ -189,172 +1081,2370 @@ impl App {
                     },
                     AppMode::Delete => {
                         match code {
-                            KeyCode::Enter => {
-                                if self.user_input.get_input_value() == "y" {
-                                    self.delete_file()
-                                }
-
-                                self.app_mode = AppMode::Files;
+                            KeyCode::Enter if self.delete_confirmed() => match self.check_pending_target() {
+                                Staleness::Unchanged => {
+                                    self.delete_file();
+                                    self.app_mode = AppMode::Files;
+                                },
+                                Staleness::Changed => {},
+                                Staleness::Vanished => self.app_mode = AppMode::Files,
                             },
+                            KeyCode::Enter => self.app_mode = AppMode::Files,
                             KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
                             KeyCode::Backspace => self.user_input.delete_char(),
                             KeyCode::Esc => self.app_mode = AppMode::Select,
                             _ => {}
                         }
                     }
-                    AppMode::Help => {
+                    AppMode::EmptyTrash => {
                         match code {
+                            KeyCode::Enter => {
+                                if self.user_input.get_input_value() == "y" {
+                                    self.empty_trash();
+                                }
+
+                                self.app_mode = AppMode::Files;
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
                             KeyCode::Esc => self.app_mode = AppMode::Files,
-                            _ => {},
+                            _ => {}
+                        }
+                    },
+                    AppMode::Discard => {
+                        match code {
+                            KeyCode::Enter => {
+                                if self.user_input.get_input_value() == "y" {
+                                    self.run_git(FileAction::Discard);
+                                }
+
+                                self.app_mode = AppMode::Files;
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Help => {
+                        match code {
+                            KeyCode::Char('i') => self.app_mode = AppMode::About,
+                            KeyCode::Esc => self.app_mode = self.help_mode,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Setup => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.setup_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.setup_window.state.select_next(),
+                            KeyCode::Char(' ') | KeyCode::Left | KeyCode::Right | KeyCode::Char('h') | KeyCode::Char('l') => {
+                                self.setup_window.toggle_selected();
+                            },
+                            KeyCode::Enter => self.finish_setup(true),
+                            KeyCode::Esc => self.finish_setup(false),
+                            _ => {}
+                        }
+                    },
+                    AppMode::About => {
+                        if code == KeyCode::Esc {
+                            self.app_mode = AppMode::Files;
+                        }
+                    },
+                    AppMode::ErrorDetails => {
+                        if matches!(code, KeyCode::Esc | KeyCode::Enter) {
+                            self.error_msg.clear();
+                            self.notice.clear();
+                            self.app_mode = AppMode::Files;
                         }
                     },
                     AppMode::Create => {
                         match code {
                             KeyCode::Enter => {
-                                self.create_file();
+                                let name = self.typed_create_name().map(|(name, _)| name).unwrap_or_default();
+
+                                if let Err(e) = self.validate_typed_name() {
+                                    self.error_msg = e.to_string();
+                                } else if self.dir.current_path().join(name).symlink_metadata().is_ok() {
+                                    self.app_mode = AppMode::CreateCollision;
+                                } else {
+                                    self.create_file(false);
+                                    self.app_mode = AppMode::Files;
+                                }
+                            },
+                            // Directories have nothing to seed, so for them it's whether to enter the new one
+                            KeyCode::Tab if self.typed_create_name().is_ok_and(|(name, _)| name.ends_with('/')) => {
+                                self.enter_created = !self.enter_created;
+                            },
+                            KeyCode::Tab => {
+                                self.create_seed = match self.create_seed {
+                                    CreateSeed::Empty => CreateSeed::Clipboard,
+                                    CreateSeed::Clipboard if self.stdin_content.is_some() => CreateSeed::Stdin,
+                                    _ => CreateSeed::Empty,
+                                };
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Bookmarks => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.bookmark_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.bookmark_window.state.select_next(),
+                            KeyCode::Enter => self.jump_to_bookmark(),
+                            KeyCode::Char('a') => self.add_bookmark(),
+                            KeyCode::Char('d') => self.remove_bookmark(),
+                            KeyCode::Char('r') => self.enter_bookmark_rename(),
+                            KeyCode::Char('R') => self.enter_bookmark_relocate(),
+                            KeyCode::Char(digit @ '0'..='9') => {
+                                let Some(index) = self.bookmark_window.state.selected() else { return };
+
+                                self.bookmarks.set_slot(index, digit as u8 - b'0');
+                                self.save_bookmarks();
+                            },
+                            KeyCode::Char('m') => self.send_to_bookmark(BatchKind::Move),
+                            KeyCode::Char('c') => self.send_to_bookmark(BatchKind::Copy),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::RenameBookmark => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.rename_bookmark();
+                                self.app_mode = AppMode::Bookmarks;
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Bookmarks,
+                            _ => {}
+                        }
+                    },
+                    AppMode::RelocateBookmark => {
+                        match code {
+                            KeyCode::Enter => self.relocate_bookmark(),
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Bookmarks,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Command => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.app_mode = AppMode::Files;
+                                self.run_command();
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Search => {
+                        match code {
+                            KeyCode::Enter => self.run_search(),
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::SearchResults => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.search_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.search_window.state.select_next(),
+                            KeyCode::Enter => self.jump_to_search_result(),
+                            KeyCode::Char('o') => {
+                                if let Some(path) = self.search_window.selected().cloned() {
+                                    self.open_path(path);
+                                }
+                            },
+                            KeyCode::Char('E') => {
+                                if let Some(path) = self.search_window.selected().cloned() {
+                                    self.edit_path(path);
+                                }
+                            },
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Journal => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.journal_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.journal_window.state.select_next(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Tasks => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.task_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.task_window.state.select_next(),
+                            KeyCode::Char('c') => self.finished_tasks.clear(),
+                            KeyCode::Char('x') => self.cancel_task(),
+                            KeyCode::Esc | KeyCode::Char('T') => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Checksums => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.checksum_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.checksum_window.state.select_next(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Undo => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.undo_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.undo_window.state.select_next(),
+                            KeyCode::Enter => self.undo_selected(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Permissions => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.change_permissions();
                                 self.app_mode = AppMode::Files;
                             },
+                            KeyCode::Tab => self.chmod_recursive = !self.chmod_recursive,
                             KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
                             KeyCode::Backspace => self.user_input.delete_char(),
                             KeyCode::Esc => self.app_mode = AppMode::Select,
                             _ => {}
                         }
                     },
-                    AppMode::Exit => {},
-                }
-            }
+                    AppMode::Properties => {
+                        match code {
+                            KeyCode::Tab | KeyCode::Down => self.properties_window.focus_next(true),
+                            KeyCode::BackTab | KeyCode::Up => self.properties_window.focus_next(false),
+                            KeyCode::Enter => self.apply_properties(),
+                            KeyCode::Char(to_insert) => {
+                                if let Some(input) = self.properties_window.focused_input() {
+                                    input.enter_char(to_insert);
+                                }
+                            },
+                            KeyCode::Backspace => {
+                                if let Some(input) = self.properties_window.focused_input() {
+                                    input.delete_char();
+                                }
+                            },
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::RunArgs => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.run_executable();
+                                self.exit_select_menu();
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Compress => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.compress_file();
+                                self.exit_select_menu();
+                            },
+                            KeyCode::Char('g') => self.compress_codec = Codec::Gzip,
+                            KeyCode::Char('z') => self.compress_codec = Codec::Zstd,
+                            KeyCode::Tab => self.keep_original = !self.keep_original,
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Decompress => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.decompress_file();
+                                self.exit_select_menu();
+                            },
+                            KeyCode::Tab => self.keep_original = !self.keep_original,
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Encrypt => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.encrypt_file();
+                                self.exit_select_menu();
+                            },
+                            KeyCode::Tab => self.passphrase_input = !self.passphrase_input,
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Decrypt => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.decrypt_file();
+                                self.exit_select_menu();
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Preview => {
+                        match code {
+                            KeyCode::Down | KeyCode::Char('j') => self.preview_pane.scroll_down(1),
+                            KeyCode::Up | KeyCode::Char('k') => self.preview_pane.scroll_up(1),
+                            KeyCode::PageDown | KeyCode::Char(' ') => self.preview_pane.page_down(),
+                            KeyCode::PageUp => self.preview_pane.page_up(),
+                            KeyCode::Home | KeyCode::Char('g') => self.preview_pane.scroll_to_top(),
+                            KeyCode::End | KeyCode::Char('G') => self.preview_pane.scroll_to_bottom(),
+                            KeyCode::Char('w') => self.preview_pane.wrap = !self.preview_pane.wrap,
+                            KeyCode::Char('l') => self.preview_pane.line_numbers = !self.preview_pane.line_numbers,
+                            KeyCode::Char('/') => {
+                                self.user_input = UserInput::default();
+                                self.app_mode = AppMode::PreviewSearch;
+                            },
+                            KeyCode::Char('n') => self.find_in_preview(true),
+                            KeyCode::Char('N') => self.find_in_preview(false),
+                            KeyCode::Char('f') => self.toggle_follow(),
+                            KeyCode::Char('d') => self.toggle_diff(),
+                            KeyCode::Char('s') => self.toggle_follow_pause(),
+                            KeyCode::Tab => self.cycle_focus(true),
+                            KeyCode::BackTab => self.cycle_focus(false),
+                            KeyCode::Esc | KeyCode::Char('p') => self.focus(Panel::Files),
+                            _ => {}
+                        }
+                    },
+                    AppMode::Filter => {
+                        match code {
+                            KeyCode::Enter => self.app_mode = AppMode::Files,
+                            KeyCode::Char(to_insert) => {
+                                self.user_input.enter_char(to_insert);
+                                self.set_text_filter(self.user_input.get_input_value());
+                            },
+                            KeyCode::Backspace => {
+                                self.user_input.delete_char();
+                                self.set_text_filter(self.user_input.get_input_value());
+                            },
+                            KeyCode::Esc => {
+                                self.app_mode = AppMode::Files;
+                                self.set_text_filter(String::new());
+                            },
+                            _ => {}
+                        }
+                    },
+                    AppMode::ExtensionFilter => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.app_mode = AppMode::Files;
+                                self.set_kind_filter(KindFilter::parse_extensions(&self.user_input.get_input_value()));
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::CreateCollision => {
+                        match code {
+                            KeyCode::Char('b') => {
+                                self.create_file(true);
+                                self.app_mode = AppMode::Files;
+                            },
+                            KeyCode::Esc | KeyCode::Char('n') => self.app_mode = AppMode::Create,
+                            _ => {}
+                        }
+                    },
+                    AppMode::MoveCollision => {
+                        match code {
+                            KeyCode::Char('b') => self.move_keeping_both(),
+                            KeyCode::Esc | KeyCode::Char('n') => {
+                                let rename = self.pending_move.take().is_some_and(|pending| pending.rename);
+                                self.app_mode = if rename { AppMode::Rename } else { AppMode::Files };
+                            },
+                            _ => {}
+                        }
+                    },
+                    AppMode::Attributes => {
+                        match code {
+                            KeyCode::Char(key) => {
+                                if let Some(attribute) = Attribute::ALL.into_iter().find(|attribute| attribute.key() == key) {
+                                    self.toggle_attribute(attribute);
+                                }
+                            },
+                            KeyCode::Esc | KeyCode::Enter => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::FinderTags => {
+                        match code {
+                            KeyCode::Char(digit @ '1'..='7') => self.toggle_tag_color(TagColor::ALL[(digit as u8 - b'1') as usize]),
+                            KeyCode::Char('x') => self.clear_quarantine(),
+                            KeyCode::Esc | KeyCode::Enter => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::BatchDestination => {
+                        match code {
+                            KeyCode::Enter => {
+                                let destination = self.user_input.get_input_value();
+
+                                match self.dir.resolve_dir(&destination) {
+                                    Ok(path) => self.plan_batch(Some(&path)),
+                                    Err(e) => {
+                                        self.error_msg = e.to_string();
+                                        self.app_mode = AppMode::Files;
+                                    },
+                                }
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Select,
+                            _ => {}
+                        }
+                    },
+                    AppMode::BatchPreview => {
+                        match code {
+                            KeyCode::Up | KeyCode::Char('k') => self.batch_window.state.select_previous(),
+                            KeyCode::Down | KeyCode::Char('j') => self.batch_window.state.select_next(),
+                            KeyCode::Char('b') => self.toggle_keep_both(),
+                            KeyCode::Char('p') => self.toggle_preserve(),
+                            KeyCode::Char('l') => self.cycle_symlink_policy(),
+                            KeyCode::Char('u') => self.cycle_update_policy(),
+                            KeyCode::Enter | KeyCode::Char('y') if self.batch_window.plan.as_ref().is_some_and(BatchPlan::needs_symlink_choice) => {
+                                self.error_msg = MyError::FileError("Choose how to copy symlinks with <l> first".to_string()).to_string();
+                            },
+                            KeyCode::Enter | KeyCode::Char('y') => {
+                                self.app_mode = AppMode::Files;
+                                self.run_batch();
+                            },
+                            KeyCode::Esc => {
+                                self.app_mode = AppMode::Files;
+                                self.batch_window.plan = None;
+                            },
+                            _ => {}
+                        }
+                    },
+                    AppMode::MarkGlob => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.app_mode = AppMode::Files;
+                                self.mark_matching(&self.user_input.get_input_value());
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Views => {
+                        match code {
+                            KeyCode::Char(key) => {
+                                self.app_mode = AppMode::Files;
+
+                                match self.config.views.iter().find(|view| view.key == Some(key)).cloned() {
+                                    Some(view) => self.apply_view(view),
+                                    None => self.error_msg = MyError::ConfigError(format!("No view on key {}", key)).to_string(),
+                                }
+                            },
+                            KeyCode::Esc => self.app_mode = AppMode::Files,
+                            _ => {}
+                        }
+                    },
+                    AppMode::ListSearch => {
+                        match code {
+                            KeyCode::Enter => self.app_mode = AppMode::Files,
+                            KeyCode::Char(to_insert) => {
+                                self.user_input.enter_char(to_insert);
+                                self.update_list_search();
+                            },
+                            KeyCode::Backspace => {
+                                self.user_input.delete_char();
+                                self.update_list_search();
+                            },
+                            KeyCode::Esc => {
+                                self.app_mode = AppMode::Files;
+                                self.user_input = UserInput::default();
+                                self.update_list_search();
+                            },
+                            _ => {}
+                        }
+                    },
+                    AppMode::PreviewSearch => {
+                        match code {
+                            KeyCode::Enter => {
+                                self.app_mode = AppMode::Preview;
+
+                                if !self.preview_pane.search(self.user_input.get_input_value()) {
+                                    self.error_msg = MyError::FileError("No matches in preview".to_string()).to_string();
+                                }
+                            },
+                            KeyCode::Char(to_insert) => self.user_input.enter_char(to_insert),
+                            KeyCode::Backspace => self.user_input.delete_char(),
+                            KeyCode::Esc => self.app_mode = AppMode::Preview,
+                            _ => {}
+                        }
+                    },
+                    AppMode::Exit => {},
+                }
+            }
+        }
+    }
+
+    fn select_previous_file(&mut self) {
+        self.file_list.state.select_previous();
+        self.selection_changed();
+    }
+
+    fn select_next_file(&mut self) {
+        self.file_list.state.select_next();
+        self.selection_changed();
+    }
+
+    fn select_first_file(&mut self) {
+        self.file_list.state.select_first();
+        self.selection_changed();
+    }
+
+    fn select_last_file(&mut self) {
+        self.file_list.state.select(Some(self.file_list.items.len().saturating_sub(1)));
+        self.selection_changed();
+    }
+
+    /// Whether `code` was taken by a multi-key binding like `gg`, either completing it or
+    /// waiting for the rest.
+    fn feed_sequence(&mut self, code: KeyCode) -> bool {
+        let KeyCode::Char(key) = code else {
+            self.sequence.cancel();
+            return false;
+        };
+
+        match self.sequence.feed(key) {
+            SequenceStep::Pending => true,
+            SequenceStep::Complete(chord) => {
+                match chord {
+                    Chord::Top if self.app_mode == AppMode::Select => self.select_list.state.select_first(),
+                    // The action menu has no directories to go to
+                    _ if self.app_mode == AppMode::Select => {},
+                    Chord::Top => self.select_first_file(),
+                    Chord::Home => self.change_directory(""),
+                    Chord::Root => self.change_directory(std::path::MAIN_SEPARATOR_STR),
+                    Chord::SearchResults => self.return_to_search_results(),
+                }
+
+                true
+            },
+            SequenceStep::Unmatched => false,
+        }
+    }
+
+    // A lone `g` that nothing followed in time still goes back to the search results, as it did before `gs`
+    fn expire_sequence(&mut self) {
+        if self.sequence.expire().is_some_and(|keys| keys == "g") && self.app_mode == AppMode::Files {
+            self.return_to_search_results();
+        }
+    }
+
+    // Jumps to the first match at or after where the search started, or back there
+    // when nothing matches, so the selection follows what's typed
+    fn update_list_search(&mut self) {
+        self.list_search = self.user_input.get_input_value();
+
+        if !self.find_list_match(self.list_search_origin, true) {
+            self.file_list.state.select(Some(self.list_search_origin));
+            self.selection_changed();
+        }
+    }
+
+    fn next_list_match(&mut self, forward: bool) {
+        if self.list_search.is_empty() {
+            return;
+        }
+
+        let count = self.file_list.items.len().max(1);
+        let selected = self.file_list.state.selected().unwrap_or(0);
+        let start = match forward {
+            true => (selected + 1) % count,
+            false => (selected + count - 1) % count,
+        };
+
+        if !self.find_list_match(start, forward) {
+            self.error_msg = MyError::FileError(format!("No matches for \"{}\"", self.list_search)).to_string();
+        }
+    }
+
+    // Searches forwards or backwards from `start`, wrapping around the ends
+    fn find_list_match(&mut self, start: usize, forward: bool) -> bool {
+        if self.list_search.is_empty() || self.file_list.items.is_empty() {
+            return false;
+        }
+
+        let count = self.file_list.items.len();
+        let found = (0..count)
+            .map(|offset| match forward {
+                true => (start + offset) % count,
+                false => (start + count - offset % count) % count,
+            })
+            .find(|index| self.matches_list_search(&self.file_list.items[*index]));
+
+        if let Some(index) = found {
+            self.file_list.state.select(Some(index));
+            self.selection_changed();
+        }
+
+        found.is_some()
+    }
+
+    fn matches_list_search(&self, item: &str) -> bool {
+        let name = item.rsplit('/').next().unwrap_or(item);
+
+        !self.list_search.is_empty() && name.to_ascii_lowercase().contains(&self.list_search.to_ascii_lowercase())
+    }
+
+    fn selection_changed(&mut self) {
+        self.stop_following();
+
+        let Some(index) = self.file_list.state.selected() else { return };
+        let Some(file_name) = self.file_list.items.get(index).cloned() else { return };
+
+        let file_path = match self.dir.get_file_path(file_name) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        if let Err(e) = self.plugins.on_select(&file_path) {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn directory_changed(&mut self) {
+        self.stop_following();
+
+        if let Err(e) = self.plugins.on_enter_directory(&self.dir.get_current_path()) {
+            self.error_msg = e.to_string();
+        }
+
+        let current_path = self.dir.current_path();
+
+        if current_path != self.entered_dir {
+            self.notice.clear();
+            self.list_search.clear();
+            self.remember_view(&self.entered_dir.clone());
+            self.recall_dir_view(&current_path);
+            self.hooks.run(HookEvent::Leave, &self.entered_dir);
+            self.hooks.run(HookEvent::Enter, &current_path);
+            self.entered_dir = current_path.clone();
+            self.network_fs = mounts::network_fs(&current_path);
+        }
+
+        if self.config.zoxide.enabled && current_path.is_dir() {
+            if let Err(e) = zoxide::add(&current_path) {
+                self.error_msg = e.to_string();
+            }
+        }
+    }
+
+    fn collect_hook_output(&mut self) {
+        for output in self.hooks.try_output() {
+            self.push_notice(&output);
+        }
+    }
+
+    fn push_notice(&mut self, text: &str) {
+        if !self.notice.is_empty() {
+            self.notice.push_str(" | ");
+        }
+
+        self.notice.push_str(text);
+    }
+
+    fn select_previous_action(&mut self) {
+        self.select_list.state.select_previous();
+    }
+
+    fn select_next_action(&mut self) {
+        self.select_list.state.select_next();
+    }
+
+    fn move_into(&mut self) {
+        if let Some(folder) = self.file_list.state.selected().and_then(|i| self.file_list.items.get(i)) {
+            let folder = folder.to_string();
+
+            // is_dir follows symlinks, so linked directories can be entered too
+            if !self.dir.current_path().join(&folder).is_dir() {
+                return;
+            }
+
+            self.dir.next_path(folder);
+            self.directory_changed();
+        }
+    }
+
+    fn toggle_path_mode(&mut self) {
+        let path_mode = match self.dir.path_mode() {
+            PathMode::Logical => PathMode::Physical,
+            PathMode::Physical => PathMode::Logical,
+        };
+
+        self.dir.set_path_mode(path_mode);
+    }
+
+    fn move_out(&mut self) {
+        self.dir.previous_path();
+        self.refresh_files();
+        self.directory_changed();
+    }
+
+    fn expand_selected(&mut self) {
+        if self.dir.is_flat() {
+            return;
+        }
+
+        if self.dir.expand(&self.selected_file_name()) {
+            self.refresh_files();
+        }
+    }
+
+    // Collapses the selected directory, or the one containing the selected entry
+    fn collapse_selected(&mut self) {
+        let file_name = self.selected_file_name();
+
+        let target = match self.dir.is_expanded(&file_name) {
+            true => file_name,
+            false => match file_name.rsplit_once('/') {
+                Some((parent, _)) if !self.dir.is_flat() => parent.to_string(),
+                _ => return,
+            },
+        };
+
+        self.dir.collapse(&target);
+        self.refresh_files();
+        self.select_file(&target);
+    }
+
+    fn toggle_flat_listing(&mut self) {
+        let flat = match self.dir.is_flat() {
+            true => None,
+            false => Some(FlatListing {
+                max_depth: self.config.listing.flat_depth,
+                max_entries: self.config.listing.flat_max_entries,
+            }),
+        };
+
+        self.dir.set_flat(flat);
+        self.refresh_files();
+        self.file_list.state.select(Some(0));
+    }
+
+    /// Writes the first config file, with the answers of the setup when they're saved,
+    /// and applies them to this run too.
+    fn finish_setup(&mut self, save: bool) {
+        self.app_mode = AppMode::Files;
+
+        let contents = match save {
+            true => self.setup_window.config_toml(),
+            false => setup_window::KEPT_DEFAULTS.to_string(),
+        };
+
+        match Config::write_initial(&contents) {
+            Ok(file_path) => self.push_notice(&format!("Settings saved to {}", file_path.display())),
+            Err(e) => self.error_msg = e.to_string(),
+        }
+
+        if !save {
+            return;
+        }
+
+        self.config.listing.layout = self.setup_window.layout;
+        self.config.listing.details = self.setup_window.details;
+        self.config.listing.show_hidden = self.setup_window.show_hidden;
+        self.config.zoxide.enabled = self.setup_window.zoxide;
+        self.details = self.setup_window.details;
+        self.dir.set_show_hidden(self.setup_window.show_hidden);
+
+        // The listing started out with the defaults, which directories fall back to
+        self.default_view = self.current_view();
+        self.relist();
+    }
+
+    fn relist(&mut self) {
+        self.refresh_files();
+        self.file_list.state.select(Some(0));
+    }
+
+    fn set_text_filter(&mut self, text: String) {
+        self.dir.filter_mut().text = text;
+        self.relist();
+    }
+
+    fn set_kind_filter(&mut self, kind: Option<KindFilter>) {
+        self.dir.filter_mut().kind = kind;
+        self.relist();
+    }
+
+    fn apply_view(&mut self, view: View) {
+        self.set_view(view);
+        self.relist();
+    }
+
+    fn set_view(&mut self, view: View) {
+        self.dir.set_sort(view.sort, view.descending);
+        self.dir.set_show_hidden(view.hidden);
+
+        let filter = self.dir.filter_mut();
+        filter.text = view.filter;
+        filter.kind = match view.category {
+            Some(category) => Some(KindFilter::Category(category)),
+            None => KindFilter::parse_extensions(&view.extensions.join(",")),
+        };
+        filter.modified = view.modified;
+    }
+
+    /// The current sort order, filters and hidden toggle as an unnamed view.
+    fn current_view(&self) -> View {
+        let (sort, descending) = self.dir.sort();
+        let filter = self.dir.filter();
+
+        View {
+            name: String::new(),
+            key: None,
+            sort,
+            descending,
+            hidden: self.dir.shows_hidden(),
+            filter: filter.text.clone(),
+            category: filter.kind.as_ref().and_then(KindFilter::category),
+            extensions: match &filter.kind {
+                Some(KindFilter::Extensions(extensions)) => extensions.clone(),
+                _ => Vec::new(),
+            },
+            modified: filter.modified,
+        }
+    }
+
+    fn remember_view(&mut self, dir: &Path) {
+        if self.config.listing.remember_views == ViewMemory::Off {
+            return;
+        }
+
+        if let Err(e) = self.dir_views.remember(dir, self.current_view(), &self.default_view) {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    // Switches to the view the directory was left with, keeping the same entry selected
+    fn recall_dir_view(&mut self, dir: &Path) {
+        if self.config.listing.remember_views == ViewMemory::Off {
+            return;
+        }
+
+        let view = self.dir_views.get(dir).cloned().unwrap_or_else(|| self.default_view.clone());
+
+        if view == self.current_view() {
+            return;
+        }
+
+        let selected = self.selected_file_name();
+        self.set_view(view);
+        self.refresh_files();
+        self.select_file(&selected);
+    }
+
+    fn quit(&mut self) {
+        self.remember_view(&self.dir.current_path());
+        self.app_mode = AppMode::Exit;
+    }
+
+    fn save_view(&mut self, name: &str) {
+        if name.is_empty() {
+            self.error_msg = MyError::CommandError("Usage: view save <name>".to_string()).to_string();
+            return;
+        }
+
+        let view = View {
+            name: name.to_string(),
+            ..self.current_view()
+        };
+
+        if let Err(e) = self.config.save_view(view) {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn recall_view(&mut self, argument: &str) {
+        if let Some(name) = argument.strip_prefix("save") {
+            self.save_view(name.trim());
+            return;
+        }
+
+        match self.config.views.iter().find(|view| view.name == argument).cloned() {
+            Some(view) => self.apply_view(view),
+            None => self.error_msg = MyError::CommandError(format!("No view named {}", argument)).to_string(),
+        }
+    }
+
+    fn cycle_category_filter(&mut self) {
+        let current = self.dir.filter().kind.as_ref().and_then(KindFilter::category);
+        self.set_kind_filter(Category::next(current).map(KindFilter::Category));
+    }
+
+    fn cycle_recency_filter(&mut self) {
+        let filter = self.dir.filter_mut();
+        filter.modified = Recency::next(filter.modified);
+        self.relist();
+    }
+
+    fn move_to_project_root(&mut self) {
+        let Some(root) = self.dir.project_root(&self.config.paths.root_markers) else {
+            self.error_msg = MyError::FileError("No project root above this directory".to_string()).to_string();
+            return;
+        };
+
+        self.dir.change_path(root);
+        self.file_list.state.select(Some(0));
+        self.directory_changed();
+    }
+
+    fn enter_select_menu(&mut self) {
+        let file_name = self.selected_file_name();
+        let metadata = self.dir.get_metadata(file_name.clone());
+        let is_file = metadata.as_ref().is_some_and(|metadata| matches!(metadata.filetype, directory::FileTypeEnum::File));
+        let is_symlink = metadata.as_ref().is_some_and(|metadata| metadata.link_target.is_some());
+        let executable = metadata.as_ref().is_some_and(|metadata| metadata.is_executable());
+        let compressed = Codec::for_path(Path::new(&file_name)).is_some();
+        let archive = ArchiveFormat::for_path(Path::new(&file_name)).is_some();
+        let encrypted = Path::new(&file_name).extension().is_some_and(|extension| extension == ENCRYPTED_EXTENSION);
+        let has_checksum = is_file && checksum::sidecar_for(&self.dir.current_path().join(&file_name)).is_some();
+
+        // Index and worktree columns of `git status --porcelain`
+        let git_status = match self.git.status() {
+            Some(_) => git::file_status(&self.dir.current_path().join(&file_name)).unwrap_or_default(),
+            None => String::default(),
+        };
+        let mut git_columns = git_status.chars();
+        let (staged, unstaged) = (git_columns.next().unwrap_or(' '), git_columns.next().unwrap_or(' '));
+
+        // Only offer actions that make sense for the selected entry
+        self.select_list.items = FileAction::iter()
+            .filter(|action| match action {
+                FileAction::Run => executable,
+                FileAction::Edit => is_file,
+                FileAction::EditLinkTarget => is_symlink,
+                FileAction::Compress => is_file && !compressed,
+                // A compressed tarball is extracted, not just decompressed
+                FileAction::Decompress => is_file && compressed && !archive,
+                FileAction::Extract => is_file && archive,
+                FileAction::Encrypt => is_file && !encrypted,
+                FileAction::Decrypt => is_file && encrypted,
+                FileAction::Verify => has_checksum,
+                FileAction::Stage => unstaged != ' ',
+                FileAction::Unstage => !matches!(staged, ' ' | '?'),
+                FileAction::Discard => !matches!(unstaged, ' ' | '?'),
+                _ => true,
+            })
+            .map(|action| action.to_string())
+            .chain(self.custom_actions_for(&file_name).map(|action| action.name.clone()))
+            .chain(self.plugins.action_names())
+            .collect();
+
+        self.app_mode = AppMode::Select;
+        self.select_list.state.select(Some(0));
+    }
+
+    fn exit_select_menu(&mut self) {
+        self.app_mode = AppMode::Files;
+        self.select_list.state.select(None);
+    }
+
+    fn select_menu(&mut self) {
+        let Some(file_name) = self.file_list.state.selected().and_then(|index| self.file_list.items.get(index)).cloned() else { return };
+        let Some(action_name) = self.select_list.state.selected().and_then(|index| self.select_list.items.get(index)).cloned() else { return };
+        let Ok(action) = action_name.parse::<FileAction>() else {
+            let custom_action = self.custom_actions_for(&file_name).find(|action| action.name == action_name).cloned();
+
+            match custom_action {
+                Some(action) => self.run_custom_action(&action, file_name),
+                None => self.run_plugin_action(&action_name, file_name),
+            }
+            return;
+        };
+
+        match action {
+            FileAction::Open => {
+                self.app_mode = AppMode::Files;
+                self.open_file();
+            },
+            FileAction::Edit => {
+                self.app_mode = AppMode::Files;
+                self.edit_file();
+            },
+            FileAction::Copy | FileAction::Move => {
+                self.batch_kind = if matches!(action, FileAction::Copy) { BatchKind::Copy } else { BatchKind::Move };
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::BatchDestination;
+            },
+            FileAction::SendTo => {
+                self.exit_select_menu();
+                self.open_bookmarks();
+            },
+            FileAction::Delete if !self.marked.is_empty() => {
+                self.batch_kind = BatchKind::Delete;
+                self.plan_batch(None);
+            },
+            FileAction::Delete => {
+                self.delete_confirmation = self.delete_confirmation_for(&file_name);
+                self.pending_target = FileSnapshot::take(file_name.clone(), self.dir.current_path().join(&file_name));
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::Delete;
+            }
+            FileAction::Rename => { 
+                self.pending_target = FileSnapshot::take(file_name.clone(), self.dir.current_path().join(&file_name));
+                self.user_input = UserInput::new(file_name);
+                self.app_mode = AppMode::Rename;
+            },
+            FileAction::EditLinkTarget => {
+                let target = self.dir.get_metadata(file_name.clone()).and_then(|metadata| metadata.link_target).unwrap_or_default();
+
+                self.pending_target = FileSnapshot::take(file_name.clone(), self.dir.current_path().join(&file_name));
+                self.user_input = UserInput::new(target.display().to_string());
+                self.app_mode = AppMode::LinkTarget;
+            },
+            FileAction::Properties if self.marked.is_empty() => {
+                let path = self.dir.current_path().join(&file_name);
+
+                match PropertiesWindow::open(&path) {
+                    Ok(window) => {
+                        self.properties_window = window;
+                        self.app_mode = AppMode::Properties;
+                    },
+                    Err(e) => {
+                        self.error_msg = e.to_string();
+                        self.exit_select_menu();
+                    },
+                }
+            },
+            // Marked entries all get the same mode, optionally recursively
+            FileAction::Properties => {
+                let mode = match self.dir.get_metadata(file_name) {
+                    Some(metadata) => format!("{:o}", metadata.mode),
+                    None => String::default(),
+                };
+
+                self.user_input = UserInput::new(mode);
+                self.chmod_recursive = false;
+                self.app_mode = AppMode::Permissions;
+            },
+            FileAction::Yank | FileAction::Cut => {
+                self.exit_select_menu();
+                self.stage(if matches!(action, FileAction::Yank) { BatchKind::Copy } else { BatchKind::Move });
+            },
+            FileAction::Run => {
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::RunArgs;
+            },
+            FileAction::Compress => self.app_mode = AppMode::Compress,
+            FileAction::Decompress => self.app_mode = AppMode::Decompress,
+            FileAction::Extract => {
+                self.exit_select_menu();
+                self.extract_archive();
+            },
+            FileAction::Encrypt => {
+                self.passphrase_input = true;
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::Encrypt;
+            },
+            FileAction::Verify => {
+                self.exit_select_menu();
+                self.verify_checksums();
+            },
+            FileAction::CalculateSize => {
+                self.exit_select_menu();
+                self.calculate_size();
+            },
+            FileAction::Stage | FileAction::Unstage => {
+                self.exit_select_menu();
+                self.run_git(action);
+            },
+            FileAction::Discard => {
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::Discard;
+            },
+            FileAction::Decrypt => {
+                let Ok(path) = self.dir.get_file_path(file_name) else { return };
+
+                self.passphrase_input = encryption::is_passphrase_encrypted(Path::new(&path));
+                self.user_input = UserInput::default();
+                self.app_mode = AppMode::Decrypt;
+            },
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        let file_name = self.selected_file_name();
+        let file_path = self.dir.current_path().join(file_name);
+
+        if !self.marked.remove(&file_path) {
+            self.marked.insert(file_path);
+        }
+
+        self.select_next_file();
+    }
+
+    // Every listed entry as a full path, skipping placeholders like the one for unlistable directories
+    fn listed_paths(&self) -> Vec<PathBuf> {
+        let current_path = self.dir.current_path();
+
+        self.file_list.items
+            .iter()
+            .map(|item| current_path.join(item))
+            .filter(|path| path.symlink_metadata().is_ok())
+            .collect()
+    }
+
+    fn mark_all(&mut self) {
+        self.marked.extend(self.listed_paths());
+    }
+
+    /// Marks every unmarked entry in the listing and unmarks the rest.
+    fn invert_marks(&mut self) {
+        for path in self.listed_paths() {
+            if !self.marked.remove(&path) {
+                self.marked.insert(path);
+            }
+        }
+    }
+
+    /// Adds entries matching the glob to the marks. Patterns containing `/` are matched
+    /// against the path relative to the current directory, others against the file name.
+    fn mark_matching(&mut self, pattern: &str) {
+        let matcher = match Glob::new(pattern) {
+            Ok(glob) => glob.compile_matcher(),
+            Err(_) => {
+                self.error_msg = MyError::CommandError(format!("Invalid pattern {}", pattern)).to_string();
+                return;
+            }
+        };
+
+        let current_path = self.dir.current_path();
+
+        let matching = self.listed_paths()
+            .into_iter()
+            .filter(|path| match pattern.contains('/') {
+                true => path.strip_prefix(&current_path).is_ok_and(|relative| matcher.is_match(relative)),
+                false => path.file_name().is_some_and(|name| matcher.is_match(name)),
+            })
+            .collect::<Vec<PathBuf>>();
+
+        if matching.is_empty() {
+            self.error_msg = MyError::FileError(format!("Nothing matches {}", pattern)).to_string();
+        }
+
+        self.marked.extend(matching);
+    }
+
+    /// "12 items (340 MiB)" for marked entries, otherwise the selected entry's name.
+    fn targets_description(&self) -> String {
+        match self.marked.len() {
+            0 => self.selected_file_name(),
+            1 => format!("1 item ({})", format_size(self.marked.total_size())),
+            count => format!("{} items ({})", count, format_size(self.marked.total_size())),
+        }
+    }
+
+    fn plan_batch(&mut self, destination: Option<&Path>) {
+        self.plan_paths(self.target_paths(), &self.targets_description(), destination);
+    }
+
+    fn plan_paths(&mut self, paths: Vec<PathBuf>, description: &str, destination: Option<&Path>) {
+        self.pasting_register = None;
+        self.batch_window.title = match destination {
+            Some(destination) => format!("{} {} to {}", self.batch_kind, description, destination.display()),
+            None => format!("{} {}", self.batch_kind, description),
+        };
+
+        match BatchPlan::new(self.batch_kind, paths, destination, self.config.copy) {
+            Ok(plan) => {
+                self.batch_window.plan = Some(plan);
+                self.batch_window.state.select(Some(0));
+                self.app_mode = AppMode::BatchPreview;
+            },
+            Err(e) => {
+                self.error_msg = e.to_string();
+                self.app_mode = AppMode::Files;
+            },
+        }
+    }
+
+    fn toggle_keep_both(&mut self) {
+        let Some(plan) = &self.batch_window.plan else { return };
+
+        if plan.kind == BatchKind::Delete {
+            return;
+        }
+
+        let options = CopyOptions { keep_both: !plan.options.keep_both, ..plan.options };
+
+        match plan.replan(options) {
+            Ok(plan) => self.batch_window.plan = Some(plan),
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
+
+    /// Moves always keep the attributes, so only copies can opt out.
+    fn toggle_preserve(&mut self) {
+        let Some(plan) = &mut self.batch_window.plan else { return };
+
+        if plan.kind == BatchKind::Copy {
+            plan.options.preserve = !plan.options.preserve;
+        }
+    }
+
+    fn cycle_symlink_policy(&mut self) {
+        let Some(plan) = &mut self.batch_window.plan else { return };
+
+        if plan.kind == BatchKind::Copy {
+            plan.options.symlinks = plan.options.symlinks.next();
+        }
+    }
+
+    /// Updating changes which destinations conflict, so the plan is made again.
+    fn cycle_update_policy(&mut self) {
+        let Some(plan) = &self.batch_window.plan else { return };
+
+        if plan.kind != BatchKind::Copy {
+            return;
+        }
+
+        let options = CopyOptions { update: plan.options.update.next(), ..plan.options };
+
+        match plan.replan(options) {
+            Ok(plan) => self.batch_window.plan = Some(plan),
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
+
+    /// Runs every step of the reviewed plan that has no conflict. Copies run in the background.
+    fn run_batch(&mut self) {
+        let Some(plan) = self.batch_window.plan.take() else { return };
+        let mut outcome = BatchOutcome::for_plan(&plan);
+
+        for step in plan.runnable() {
+            let source = step.source.clone();
+            let options = plan.options;
+            let source_name = source.to_string_lossy().to_string();
+            outcome.start(&source);
+
+            match (plan.kind, step.destination.clone()) {
+                (BatchKind::Copy, Some(destination)) => {
+                    self.tasks.push(BackgroundTask::spawn_with_progress(
+                        "Copy",
+                        source.clone(),
+                        move |progress| batch::copy_recursive(&source, &destination, &options, progress).map(|_| destination),
+                    ));
+                },
+                (BatchKind::Move, Some(destination)) => match batch::rename_path(&source, &destination) {
+                    // A copy that can take a while, so it runs like one
+                    Err(MyError::CrossDevice(_)) => {
+                        self.tasks.push(BackgroundTask::spawn_with_progress(
+                            "Move",
+                            source.clone(),
+                            move |progress| batch::move_across(&source, &destination, progress).map(|_| destination),
+                        ));
+                    },
+                    result => {
+                        self.record("Move", &source_name, Some(&destination.to_string_lossy()), &result);
+                        outcome.finish(&source, &result);
+
+                        if result.is_ok() {
+                            self.undo_history.push(UndoOperation::Move { from: source, to: destination });
+                        }
+                    },
+                },
+                (BatchKind::Delete, _) if source.is_dir() && !source.is_symlink() => self.delete_tree(source),
+                (BatchKind::Delete, _) => {
+                    let result = batch::remove_path(&source);
+                    self.record("Delete", &source_name, None, &result);
+                    outcome.finish(&source, &result);
+                },
+                // Planned as a conflict, so it never gets here
+                (BatchKind::Copy | BatchKind::Move, None) => {
+                    let result: Result<(), MyError> = Err(MyError::FileError(format!("{} has no name to keep", source_name)));
+                    outcome.finish(&source, &result);
+                },
+            }
+        }
+
+        if plan.conflicts() > 0 {
+            self.error_msg = MyError::FileError(format!("Skipped {} conflicting items", plan.conflicts())).to_string();
+        }
+
+        self.batch_outcome = Some(outcome);
+        self.report_batch();
+        self.marked.clear();
+    }
+
+    /// Once every step has finished, says how many failed and why the first one did.
+    fn report_batch(&mut self) {
+        let Some(outcome) = &self.batch_outcome else { return };
+
+        if !outcome.is_finished() {
+            return;
+        }
+
+        // Cut entries are gone from where they were once pasted, so only skipped or failed ones stay
+        if let Some(name) = self.pasting_register.take().filter(|_| outcome.kind == BatchKind::Move) {
+            self.registers.retain_existing(name);
+        }
+
+        match outcome.failed.first() {
+            Some((_, e)) => {
+                self.error_msg = MyError::FileError(format!(
+                    "{} of {} items failed, <R> retries them. First: {}",
+                    outcome.failed.len(),
+                    outcome.ran,
+                    e,
+                )).to_string();
+            },
+            None => self.batch_outcome = None,
+        }
+    }
+
+    /// Plans the failed items of the last batch again, for another look before they run.
+    fn retry_failed_batch(&mut self) {
+        let Some(outcome) = self.batch_outcome.as_ref().filter(|outcome| outcome.is_finished()) else {
+            self.error_msg = MyError::FileError("No failed items to retry".to_string()).to_string();
+            return;
+        };
+
+        match outcome.retry() {
+            Ok(plan) => {
+                self.batch_kind = plan.kind;
+                self.batch_window.title = format!("{} {} failed items again", plan.kind, plan.steps.len());
+                self.batch_window.plan = Some(plan);
+                self.batch_window.state.select(Some(0));
+                self.batch_outcome = None;
+                self.app_mode = AppMode::BatchPreview;
+            },
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
+
+    /// The entries an action applies to: every marked entry, or the selected one when nothing is marked.
+    fn target_paths(&self) -> Vec<PathBuf> {
+        if !self.marked.is_empty() {
+            return self.marked.iter().cloned().collect();
+        }
+
+        match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => vec![PathBuf::from(path)],
+            Err(_) => Vec::new(),
+        }
+    }
+
+    fn change_permissions(&mut self) {
+        let input = self.user_input.get_input_value();
+        let spec = match input.parse::<ModeSpec>() {
+            Ok(spec) => spec,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let operation = if self.chmod_recursive { "Chmod -R" } else { "Chmod" };
+
+        for path in self.target_paths() {
+            let source = path.to_string_lossy().to_string();
+            let result = permissions::set_mode(&path, &spec, self.chmod_recursive).map(|_| ());
+            self.record(operation, &source, Some(&input), &result);
+
+            if let Err(e) = result {
+                self.error_msg = e.to_string();
+            }
+        }
+
+        self.marked.clear();
+    }
+
+    /// Applies every changed field of the Properties dialog, going on past failures.
+    /// The dialog stays open when something failed, so the value can be corrected.
+    fn apply_properties(&mut self) {
+        let path = self.properties_window.path.clone();
+        let source = path.to_string_lossy().to_string();
+        let changes = self.properties_window.changes();
+        let mut failed = 0;
+        let mut first_error = None;
+
+        for (property, value) in &changes {
+            let result = properties::apply(&path, property, value);
+            self.record(property.operation(), &source, Some(&format!("{} {}", property.label(), value)), &result);
+
+            match result {
+                Ok(()) => self.properties_window.mark_applied(property),
+                Err(e) => {
+                    failed += 1;
+                    first_error.get_or_insert(e);
+                },
+            }
+        }
+
+        if let Some(e) = first_error {
+            self.error_msg = match failed {
+                1 => e.to_string(),
+                _ => MyError::FileError(format!("Couldn't apply {} of {} changes, first: {}", failed, changes.len(), e.message())).to_string(),
+            };
+            return;
+        }
+
+        if !changes.is_empty() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            self.push_notice(&format!("Changed {} of {}", format_quantity(changes.len() as u64, "property", "properties"), name));
+        }
+
+        self.app_mode = AppMode::Files;
+    }
+
+    fn toggle_executable(&mut self) {
+        let file_path = match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let (result, mode) = match permissions::toggle_executable(Path::new(&file_path)) {
+            Ok(mode) => (Ok(()), format!("{:o}", mode)),
+            Err(e) => (Err(e), String::default()),
+        };
+
+        self.record("Chmod", &file_path, Some(&mode), &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn enter_attributes(&mut self) {
+        match cfg!(windows) {
+            true => self.app_mode = AppMode::Attributes,
+            false => self.error_msg = MyError::FileError("Attributes are only supported on Windows".to_string()).to_string(),
+        }
+    }
+
+    fn toggle_attribute(&mut self, attribute: Attribute) {
+        let file_name = self.selected_file_name();
+        let file_path = match self.dir.get_file_path(file_name.clone()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let (result, attributes) = match permissions::toggle_attribute(Path::new(&file_path), attribute) {
+            Ok(attributes) => (Ok(()), permissions::attribute_string(attributes)),
+            Err(e) => (Err(e), String::default()),
+        };
+
+        self.record("Attributes", &file_path, Some(&attributes), &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+            self.app_mode = AppMode::Files;
+            return;
+        }
+
+        // Hiding the entry can take it out of the listing, leaving another one selected
+        self.refresh_files();
+
+        if self.selected_file_name() != file_name {
+            self.app_mode = AppMode::Files;
+        }
+    }
+
+    fn enter_finder_tags(&mut self) {
+        match cfg!(target_os = "macos") {
+            true => self.app_mode = AppMode::FinderTags,
+            false => self.error_msg = MyError::FileError("Finder tags are only supported on macOS".to_string()).to_string(),
+        }
+    }
+
+    fn toggle_tag_color(&mut self, color: TagColor) {
+        let file_path = match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let (result, tags) = match finder::toggle_color(Path::new(&file_path), color) {
+            Ok(tags) => (Ok(()), tags.join(", ")),
+            Err(e) => (Err(e), String::default()),
+        };
+
+        self.record("Tag", &file_path, Some(&tags), &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+            self.app_mode = AppMode::Files;
+        }
+    }
+
+    fn clear_quarantine(&mut self) {
+        let file_path = match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        if !finder::is_quarantined(Path::new(&file_path)) {
+            return;
+        }
+
+        let result = finder::clear_quarantine(Path::new(&file_path));
+        self.record("Unquarantine", &file_path, None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+            self.app_mode = AppMode::Files;
+        }
+    }
+
+    fn find_in_preview(&mut self, forward: bool) {
+        let found = match forward {
+            true => self.preview_pane.next_match(),
+            false => self.preview_pane.previous_match(),
+        };
+
+        if !found {
+            self.error_msg = MyError::FileError("No matches in preview".to_string()).to_string();
+        }
+    }
+
+    fn compress_file(&mut self) {
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+        let path = PathBuf::from(path);
+        let (codec, keep) = (self.compress_codec, self.keep_original);
+
+        self.tasks.push(BackgroundTask::spawn(
+            &format!("Compress ({})", codec),
+            path.clone(),
+            move || compression::compress(&path, codec, keep),
+        ));
+    }
+
+    fn decompress_file(&mut self) {
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+        let path = PathBuf::from(path);
+        let keep = self.keep_original;
+
+        self.tasks.push(BackgroundTask::spawn(
+            "Decompress",
+            path.clone(),
+            move || compression::decompress(&path, keep),
+        ));
+    }
+
+    fn extract_archive(&mut self) {
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+        let path = PathBuf::from(path);
+
+        self.tasks.push(BackgroundTask::spawn(
+            "Extract",
+            path.clone(),
+            move || archive::extract(&path),
+        ));
+    }
+
+    fn encrypt_file(&mut self) {
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+        let path = PathBuf::from(path);
+        let key = match self.passphrase_input {
+            true => EncryptionKey::Passphrase(self.user_input.get_input_value()),
+            false => EncryptionKey::Recipient(self.user_input.get_input_value()),
+        };
+
+        self.tasks.push(BackgroundTask::spawn(
+            "Encrypt",
+            path.clone(),
+            move || encryption::encrypt(&path, key),
+        ));
+    }
+
+    fn decrypt_file(&mut self) {
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+        let path = PathBuf::from(path);
+        let secret = self.user_input.get_input_value();
+
+        self.tasks.push(BackgroundTask::spawn(
+            "Decrypt",
+            path.clone(),
+            move || encryption::decrypt(&path, secret),
+        ));
+    }
+
+    fn run_git(&mut self, action: FileAction) {
+        let path = self.dir.current_path().join(self.selected_file_name());
+
+        let (operation, result) = match action {
+            FileAction::Stage => ("Git stage", git::stage(&path)),
+            FileAction::Unstage => ("Git unstage", git::unstage(&path)),
+            FileAction::Discard => ("Git discard", git::discard(&path)),
+            _ => return,
+        };
+
+        self.record(operation, &path.to_string_lossy(), None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn verify_checksums(&mut self) {
+        let path = self.dir.current_path().join(self.selected_file_name());
+        let Some(sidecar) = checksum::sidecar_for(&path) else { return };
+
+        self.verification = Some(BackgroundTask::spawn(
+            "Verify",
+            sidecar.clone(),
+            move || checksum::verify(&sidecar),
+        ));
+    }
+
+    fn run_executable(&mut self) {
+        let file_path = match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => PathBuf::from(path),
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let args = self.user_input.get_input_value();
+        self.suspended_command = Some(ExternalCommand::executable(&file_path, &args, &self.dir.current_path()));
+    }
+
+    fn toggle_diff(&mut self) {
+        if self.git.status().is_none() {
+            self.error_msg = MyError::FileError("Not in a git repository".to_string()).to_string();
+            return;
+        }
+
+        let path = self.dir.current_path().join(self.selected_file_name());
+
+        if !self.diff_paths.remove(&path) {
+            self.diff_paths.insert(path);
+        }
+    }
+
+    fn toggle_follow(&mut self) {
+        if self.follower.is_some() {
+            self.stop_following();
+            return;
+        }
+
+        let Ok(path) = self.dir.get_file_path(self.selected_file_name()) else { return };
+
+        match Follower::new(Path::new(&path)) {
+            Ok(follower) => {
+                self.follower = Some(follower);
+                self.preview_pane.pinned = true;
+                self.preview_pane.status = Some("following");
+            },
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
+
+    fn toggle_follow_pause(&mut self) {
+        let Some(follower) = &mut self.follower else { return };
+
+        follower.paused = !follower.paused;
+        self.preview_pane.pinned = !follower.paused;
+        self.preview_pane.status = Some(if follower.paused { "paused" } else { "following" });
+    }
+
+    fn stop_following(&mut self) {
+        self.follower = None;
+        self.preview_pane.pinned = false;
+        self.preview_pane.status = None;
+    }
+
+    fn force_preview(&mut self) {
+        if let Ok(path) = self.dir.get_file_path(self.selected_file_name()) {
+            self.previewer.force(Path::new(&path));
+        }
+    }
+
+    fn open_file(&mut self) {
+        let file_name = self.selected_file_name();
+        let file_path = match self.dir.get_file_path(file_name) {
+            Ok(path) => PathBuf::from(path),
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        if file_path.is_dir() {
+            self.move_into();
+            return;
+        }
+
+        self.open_path(file_path);
+    }
+
+    /// Opens a file with its configured opener, from the listing or from search results.
+    fn open_path(&mut self, file_path: PathBuf) {
+        let command = self.opener.resolve(&file_path);
+
+        if command.terminal {
+            self.suspended_command = Some(command);
+        } else if let Err(e) = command.run() {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn edit_file(&mut self) {
+        match self.dir.get_file_path(self.selected_file_name()) {
+            Ok(path) => self.edit_path(PathBuf::from(path)),
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
+
+    fn edit_path(&mut self, file_path: PathBuf) {
+        if file_path.is_dir() {
+            self.error_msg = MyError::IsADirectory(file_path).to_string();
+            return;
+        }
+
+        let command = self.opener.editor(&file_path);
+
+        if command.terminal {
+            self.suspended_command = Some(command);
+        } else if let Err(e) = command.run() {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    /// Configured actions that apply to the entry named `file_name`.
+    fn custom_actions_for(&self, file_name: &str) -> impl Iterator<Item = &CustomAction> {
+        let path = self.dir.current_path().join(file_name);
+
+        self.config.actions.iter().filter(move |action| action.matcher.matches(&path))
+    }
+
+    fn run_custom_action(&mut self, action: &CustomAction, file_name: String) {
+        self.app_mode = AppMode::Files;
+
+        let file_path = match self.dir.get_file_path(file_name) {
+            Ok(path) => PathBuf::from(path),
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let command = ExternalCommand::for_file(&action.command, &file_path, action.terminal, action.detached && !action.terminal);
+
+        if command.terminal {
+            self.suspended_command = Some(command);
+            return;
+        }
+
+        let result = command.run();
+        self.record(&action.name, &file_path.to_string_lossy(), None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn run_plugin_action(&mut self, action: &str, file_name: String) {
+        self.app_mode = AppMode::Files;
+
+        let file_path = match self.dir.get_file_path(file_name) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let result = self.plugins.run_action(action, &file_path);
+        self.record(&format!("Plugin {}", action), &file_path, None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn delete_confirmation_for(&self, file_name: &str) -> DeleteConfirmation {
+        let Ok(file_path) = self.dir.get_file_path(file_name.to_string()) else {
+            return DeleteConfirmation::Simple;
+        };
+
+        let file_path = PathBuf::from(file_path);
+
+        if !file_path.is_dir() || file_path.is_symlink() {
+            return DeleteConfirmation::Simple;
+        }
+
+        let limits = &self.config.delete;
+        let stats = directory::dir_stats(&file_path, limits.confirm_entries, limits.confirm_size);
+
+        if stats.entries > limits.confirm_entries || stats.size > limits.confirm_size {
+            DeleteConfirmation::TypedName(stats)
+        } else {
+            DeleteConfirmation::Simple
+        }
+    }
+
+    fn delete_confirmed(&self) -> bool {
+        let input = self.user_input.get_input_value();
+
+        match self.delete_confirmation {
+            DeleteConfirmation::Simple => input == "y",
+            DeleteConfirmation::TypedName(_) => input == self.pending_target_name(),
+        }
+    }
+
+    /// The name of the entry a pending rename or delete acts on, which stays the same
+    /// even if the listing changes underneath the prompt.
+    fn pending_target_name(&self) -> String {
+        match &self.pending_target {
+            Some(target) => target.file_name.clone(),
+            None => self.selected_file_name(),
+        }
+    }
+
+    /// Works out what the prompt shows about the name typed so far.
+    fn check_typed_name(&mut self) {
+        let input = self.user_input.get_input_value();
+        let (name, mode) = permissions::split_mode(&input);
+
+        // Spell out the mode the entry will get, so a name ending in digits can't surprise
+        let mode = match (mode, permissions::umask()) {
+            (Some(mode), _) => format!("mode {:03o}", mode),
+            (None, Some(mask)) => {
+                let default = permissions::default_mode(name.ends_with('/')).unwrap_or_default();
+                format!("mode {:03o} by umask {:03o}, a trailing 755 sets one", default, mask)
+            },
+            (None, None) => "name".to_string(),
+        };
+
+        let error = match input.is_empty() {
+            true => None,
+            false => self.validate_typed_name().err().map(|e| e.to_string()),
+        };
+
+        self.typed_name = TypedName { mode, error };
+    }
+
+    /// Checks the name typed into the Create or Rename prompt. A trailing `/` creates a
+    /// directory, possibly followed by a mode, and entries of the flat listing are renamed
+    /// within the directory they're in.
+    fn validate_typed_name(&self) -> Result<(), MyError> {
+        let input = self.user_input.get_input_value();
+
+        let name = match self.app_mode {
+            AppMode::Create => {
+                let (typed, _) = permissions::split_mode(&input);
+                let (expanded, _) = self.typed_create_name()?;
+                let expanded = expanded.strip_suffix('/').unwrap_or(&expanded);
+
+                // What `~` or a variable expanded to may be anywhere, as long as its directory exists
+                if expanded == typed.strip_suffix('/').unwrap_or(typed) {
+                    return directory::validate_name(expanded);
+                }
+
+                let path = self.dir.current_path().join(expanded);
+                let parent = path.parent().unwrap_or(&path);
+
+                if !parent.is_dir() {
+                    return Err(MyError::FileError(format!("No such directory {}", parent.display())));
+                }
+
+                return directory::validate_name(&path.file_name().unwrap_or_default().to_string_lossy());
+            },
+            AppMode::Rename => match self.pending_target_name().rsplit_once('/') {
+                Some((parent, _)) => input
+                    .strip_prefix(parent)
+                    .and_then(|name| name.strip_prefix('/'))
+                    .ok_or_else(|| MyError::FileError(format!("The entry has to stay in {}/", parent)))?,
+                None => &input,
+            },
+            _ => return Ok(()),
+        };
+
+        directory::validate_name(name)
+    }
+
+    /// The name typed into the Create prompt with `~` and variables expanded, and the mode
+    /// typed after it.
+    fn typed_create_name(&self) -> Result<(String, Option<u32>), MyError> {
+        let input = self.user_input.get_input_value();
+        let (name, mode) = permissions::split_mode(&input);
+
+        expansion::expand(name).map(|name| (name, mode))
+    }
+
+    /// Makes sure the entry of a pending rename or delete is still what it was when the
+    /// operation was started. A changed entry is only acted on once the change has been
+    /// pointed out and confirmed again.
+    fn check_pending_target(&mut self) -> Staleness {
+        let Some(target) = &self.pending_target else { return Staleness::Vanished };
+        let staleness = target.staleness();
+
+        match staleness {
+            Staleness::Unchanged => {},
+            Staleness::Changed => {
+                self.error_msg = MyError::FileError(format!(
+                    "{} changed since it was selected, press Enter again to go ahead",
+                    target.file_name,
+                )).to_string();
+                self.pending_target = FileSnapshot::take(target.file_name.clone(), target.path.clone());
+            },
+            Staleness::Vanished => {
+                self.error_msg = MyError::FileError(format!("{} no longer exists", target.file_name)).to_string();
+                self.pending_target = None;
+            },
+        }
+
+        staleness
+    }
+
+    fn delete_file(&mut self) {
+        let file_name = self.pending_target_name();
+        let file_path = match self.dir.get_file_path(file_name.clone()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let metadata = match self.dir.get_metadata(file_name) {
+            Some(metadata) => metadata,
+            None => return
+        };
+
+        // Trees can take a while, so they're removed in the background and counted as they go
+        if metadata.filetype == directory::FileTypeEnum::Directory && metadata.link_target.is_none() {
+            self.delete_tree(PathBuf::from(file_path));
+            return;
+        }
+
+        let result = self.dir.delete(file_path.clone(), metadata.filetype);
+        self.record("Delete", &file_path, None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn delete_tree(&mut self, path: PathBuf) {
+        let source = path.clone();
+
+        self.tasks.push(BackgroundTask::spawn_with_progress(
+            "Delete",
+            source,
+            move |progress| batch::remove_tree(&path, progress).map(|_| PathBuf::new()),
+        ));
+    }
+
+    fn rename_file(&mut self) {
+        let file_name = self.pending_target_name();
+        let file_path = match self.dir.get_file_path(file_name) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        let new_file_path = match self.dir.get_file_path(self.user_input.get_input_value()) {
+            Ok(path) => path,
+            Err(e) => {
+                self.error_msg = e.to_string();
+                return;
+            }
+        };
+
+        self.move_entry(true, file_path, new_file_path);
+    }
+
+    fn retarget_link(&mut self) {
+        let input = self.user_input.get_input_value();
+
+        if input.is_empty() {
+            self.error_msg = MyError::FileError("The link target can't be empty".to_string()).to_string();
+            return;
+        }
+
+        let link = self.dir.current_path().join(self.pending_target_name());
+        let previous = fs::read_link(&link).unwrap_or_default();
+        let target = PathBuf::from(&input);
+
+        let result = directory::retarget_symlink(&link, &target);
+        self.record("Retarget", &link.to_string_lossy(), Some(&input), &result);
+
+        match result {
+            Ok(_) => self.undo_history.push(UndoOperation::Retarget { link, from: previous, to: target }),
+            Err(e) => self.error_msg = e.to_string(),
         }
     }
 
-    fn select_previous_file(&mut self) {
-        self.file_list.state.select_previous();
+    fn pick_register(&mut self, name: char) {
+        self.picking_register = false;
+
+        match Registers::is_name(name) {
+            true => self.register = Some(name),
+            false => self.error_msg = MyError::CommandError(format!("No register {}, only a–z and \"", name)).to_string(),
+        }
     }
 
-    fn select_next_file(&mut self) {
-        self.file_list.state.select_next();
+    /// Yanks (copy) or cuts (move) the marked entries, or the selected one, into the picked
+    /// register, replacing what it held.
+    fn stage(&mut self, kind: BatchKind) {
+        let name = self.register.take().unwrap_or(registers::UNNAMED);
+        let paths = self.target_paths();
+
+        if paths.is_empty() {
+            return;
+        }
+
+        self.registers.store(name, kind, paths);
+        self.marked.clear();
     }
 
-    fn select_previous_action(&mut self) {
-        self.select_list.state.select_previous();
+    /// Plans copying or moving the picked register's entries here, through the usual dry run.
+    fn paste_register(&mut self) {
+        let name = self.register.take().unwrap_or(registers::UNNAMED);
+
+        let Some(register) = self.registers.get(name) else {
+            self.error_msg = MyError::CommandError(format!("Register {} is empty, fill it with <y> or <X>", name)).to_string();
+            return;
+        };
+
+        let (kind, paths) = (register.kind, register.paths.clone());
+        let description = match paths.as_slice() {
+            [path] => path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+            _ => format_quantity(paths.len() as u64, "item", "items"),
+        };
+
+        self.batch_kind = kind;
+        self.plan_paths(paths, &description, Some(&self.dir.current_path()));
+        self.pasting_register = Some(name);
     }
 
-    fn select_next_action(&mut self) {
-        self.select_list.state.select_next();
+    /// Renames or moves unless the destination is taken, which is never overwritten
+    /// but asked about instead.
+    fn move_entry(&mut self, rename: bool, source: String, destination: String) {
+        if directory::is_taken(Path::new(&source), Path::new(&destination)) {
+            self.pending_move = Some(PendingMove { rename, source, destination });
+            self.app_mode = AppMode::MoveCollision;
+            return;
+        }
+
+        let result = self.dir.rename(source.clone(), destination.clone());
+        self.record(if rename { "Rename" } else { "Move" }, &source, Some(&destination), &result);
+
+        let (from, to) = (PathBuf::from(source), PathBuf::from(destination));
+
+        if result.is_ok() && rename {
+            self.select_after_refresh(&to);
+        }
+
+        match result {
+            Ok(_) if rename => self.undo_history.push(UndoOperation::Rename { from, to }),
+            Ok(_) => self.undo_history.push(UndoOperation::Move { from, to }),
+            Err(e) => self.error_msg = e.to_string(),
+        }
     }
 
-    fn move_into(&mut self) {
-        match self.file_list.state.selected() {
-            Some(i) => {
-                let folder = self.file_list.items[i].to_string();
-                self.dir.next_path(folder);
+    fn move_keeping_both(&mut self) {
+        let Some(pending) = self.pending_move.take() else { return };
+        let destination = directory::unique_path(Path::new(&pending.destination), &HashSet::new());
 
-            }
-            None => {}
-        };
+        self.app_mode = AppMode::Files;
+        self.move_entry(pending.rename, pending.source, destination.to_string_lossy().to_string());
     }
 
-    fn move_out(&mut self) {
-        self.dir.previous_path();
-        self.file_list.items = match self.dir.dir_contents() {
-            Ok(contents) => contents,
-            Err(_) => vec!["No such directory".to_string()],
-        };
+    /// Moves or copies every marked entry (or the selected one) into the selected bookmark,
+    /// through the same dry run as other bulk operations.
+    fn send_to_bookmark(&mut self, kind: BatchKind) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+        let Some(bookmark) = self.bookmarks.get(index) else { return };
+        let destination = PathBuf::from(&bookmark.path);
+
+        self.batch_kind = kind;
+        self.plan_batch(Some(&destination));
     }
 
-    fn enter_select_menu(&mut self) {
-        self.app_mode = AppMode::Select;
-        self.select_list.state.select(Some(0));
+    fn open_bookmarks(&mut self) {
+        self.bookmarks.validate();
+        self.app_mode = AppMode::Bookmarks;
+
+        if self.bookmark_window.state.selected().is_none() {
+            self.bookmark_window.state.select(Some(0));
+        }
     }
 
-    fn exit_select_menu(&mut self) {
+    fn jump_to_bookmark(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+        let Some(bookmark) = self.bookmarks.get(index) else { return };
+
+        if bookmark.missing || !Path::new(&bookmark.path).is_dir() {
+            self.error_msg = MyError::FileError(format!("No such directory {}, relocate the bookmark with <R> or remove it with <d>", bookmark.path)).to_string();
+            self.bookmarks.validate();
+            return;
+        }
+
+        self.dir.change_path(PathBuf::from(&bookmark.path));
+        self.file_list.state.select(Some(0));
         self.app_mode = AppMode::Files;
-        self.select_list.state.select(None);
+        self.directory_changed();
     }
 
-    fn select_menu(&mut self) {
-        let Some(index) = self.file_list.state.selected() else { return };
-        let file_name = self.file_list.items[index].clone();
+    fn jump_to_slot(&mut self, slot: u8) {
+        let Some(bookmark) = self.bookmarks.by_slot(slot) else {
+            self.error_msg = MyError::ConfigError(format!("No bookmark on {}, assign one with <B> then <{}>", slot, slot)).to_string();
+            return;
+        };
 
-        let Some(index) = self.select_list.state.selected() else { return };
-        let Ok(action) = self.select_list.items[index].parse::<FileAction>() else { return };
+        let path = PathBuf::from(&bookmark.path);
 
-        match action {
-            FileAction::Delete => {
-                self.user_input = UserInput::default();
-                self.app_mode = AppMode::Delete;
-            }
-            FileAction::Rename => { 
-                self.user_input = UserInput::new(file_name);
-                self.app_mode = AppMode::Rename;
+        if !path.is_dir() {
+            self.error_msg = MyError::FileError(format!("No such directory {}, <B> lists the bookmark to relocate <R> or remove <d>", bookmark.path)).to_string();
+            self.bookmarks.validate();
+            return;
+        }
+
+        self.dir.change_path(path);
+        self.file_list.state.select(Some(0));
+        self.directory_changed();
+    }
+
+    fn add_bookmark(&mut self) {
+        self.bookmarks.add(self.dir.current_path());
+        self.bookmark_window.state.select(Some(self.bookmarks.entries().len() - 1));
+        self.save_bookmarks();
+    }
+
+    fn remove_bookmark(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+
+        self.bookmarks.remove(index);
+        self.save_bookmarks();
+    }
+
+    fn enter_bookmark_rename(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+        let Some(bookmark) = self.bookmarks.get(index) else { return };
+
+        self.user_input = UserInput::new(bookmark.label.clone());
+        self.app_mode = AppMode::RenameBookmark;
+    }
+
+    fn enter_bookmark_relocate(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+        let Some(bookmark) = self.bookmarks.get(index) else { return };
+
+        self.user_input = UserInput::new(bookmark.path.clone());
+        self.app_mode = AppMode::RelocateBookmark;
+    }
+
+    fn relocate_bookmark(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+
+        match self.dir.resolve_dir(&self.user_input.get_input_value()) {
+            Ok(path) => {
+                self.bookmarks.relocate(index, path);
+                self.save_bookmarks();
+                self.app_mode = AppMode::Bookmarks;
             },
-            FileAction::Bookmark => {
-                let file_path = match self.dir.get_file_path(file_name.clone()) {
-                    Ok(path) => path,
-                    Err(e) => {
-                        self.error_msg = e.to_string();
-                        return;
-                    }
-                };
+            Err(e) => self.error_msg = e.to_string(),
+        }
+    }
 
-                self.bookmarked.full_path = file_path;
-                self.bookmarked.file_name = file_name;
+    /// Flags bookmarks that point nowhere anymore, so they can be fixed before jumping to them.
+    fn check_bookmarks(&mut self) {
+        match self.bookmarks.validate() {
+            0 => {},
+            1 => self.push_notice("A bookmark's directory is missing; <B> lists it to relocate <R> or remove <d>"),
+            missing => self.push_notice(&format!("{} bookmarks' directories are missing; <B> lists them to relocate <R> or remove <d>", missing)),
+        }
+    }
 
-                self.app_mode = AppMode::Files;
-            }
+    fn rename_bookmark(&mut self) {
+        let Some(index) = self.bookmark_window.state.selected() else { return };
+
+        self.bookmarks.rename(index, self.user_input.get_input_value());
+        self.save_bookmarks();
+    }
+
+    fn save_bookmarks(&mut self) {
+        if let Err(e) = self.bookmarks.save() {
+            self.error_msg = e.to_string();
         }
     }
 
-    fn delete_file(&mut self) {
-        let Some(index) = self.file_list.state.selected() else { return };
-        let file_name = self.file_list.items[index].clone();
-        let file_path = match self.dir.get_file_path(file_name.clone()) {
-            Ok(path) => path,
-            Err(e) => {
-                self.error_msg = e.to_string();
+    fn run_command(&mut self) {
+        let input = self.user_input.get_input_value();
+        let (command, argument) = match input.trim().split_once(char::is_whitespace) {
+            Some((command, argument)) => (command.to_string(), argument.trim().to_string()),
+            None => (input.trim().to_string(), String::default()),
+        };
+
+        match command.as_str() {
+            "cd" => self.change_directory(&argument),
+            "view" => self.recall_view(&argument),
+            "prune" => self.plan_prune(&argument),
+            "version" | "about" => self.app_mode = AppMode::About,
+            "" => {},
+            _ => self.error_msg = MyError::CommandError(format!("Unknown command {}", command)).to_string(),
+        }
+    }
+
+    /// Plans deleting the empty directories below the current one, or with `links` the broken
+    /// symlinks, through the usual dry run.
+    fn plan_prune(&mut self, argument: &str) {
+        let (paths, singular, plural) = match argument {
+            "" => (self.dir.empty_directories(), "empty directory", "empty directories"),
+            "links" => (self.dir.broken_links(), "broken link", "broken links"),
+            _ => {
+                self.error_msg = MyError::CommandError("Usage: prune [links]".to_string()).to_string();
                 return;
             }
         };
 
-        let metadata = match self.dir.get_metadata(file_name) {
-            Some(metadata) => metadata,
-            None => return
-        };
+        if paths.is_empty() {
+            self.push_notice(&format!("No {} below here", plural));
+            return;
+        }
 
-        match self.dir.delete(file_path, metadata.filetype) {
-            Ok(_) => {},
-            Err(e) => self.error_msg = e.to_string(),
-        };
+        let description = format_quantity(paths.len() as u64, singular, plural);
+
+        self.batch_kind = BatchKind::Delete;
+        self.plan_paths(paths, &description, None);
     }
 
-    fn rename_file(&mut self) {
-        let Some(index) = self.file_list.state.selected() else { return };
-        let file_name = self.file_list.items[index].clone();
-        let file_path = match self.dir.get_file_path(file_name) {
+    fn change_directory(&mut self, target: &str) {
+        let new_path = if target.is_empty() {
+            dirs::home_dir().ok_or_else(|| MyError::FileError("No home directory".to_string()))
+        } else {
+            self.dir.resolve_dir(target)
+        };
+
+        let new_path = match new_path {
             Ok(path) => path,
+            Err(_) if self.config.zoxide.enabled => {
+                match zoxide::query(target, &self.dir.current_path()) {
+                    Ok(path) => path,
+                    Err(e) => {
+                        self.error_msg = e.to_string();
+                        return;
+                    }
+                }
+            },
             Err(e) => {
                 self.error_msg = e.to_string();
                 return;
             }
         };
 
-        let new_file_path = match self.dir.get_file_path(self.user_input.get_input_value()) {
-            Ok(path) => path,
+        self.dir.change_path(new_path);
+        self.file_list.state.select(Some(0));
+        self.directory_changed();
+    }
+
+    fn enter_search(&mut self, kind: SearchKind) {
+        self.search_window.kind = kind;
+        self.user_input = UserInput::default();
+        self.app_mode = AppMode::Search;
+    }
+
+    fn run_search(&mut self) {
+        let pattern = self.user_input.get_input_value();
+        self.app_mode = AppMode::Files;
+
+        if pattern.is_empty() {
+            return;
+        }
+
+        let root = self.dir.current_path();
+        let results = match search::search(&root, &pattern, self.search_window.kind, &self.config.search) {
+            Ok(results) => results,
             Err(e) => {
                 self.error_msg = e.to_string();
                 return;
             }
         };
 
-        match self.dir.rename(file_path, new_file_path) {
-            Ok(_) => {},
+        if results.is_empty() {
+            self.error_msg = MyError::FileError(format!("No matches for {}", pattern)).to_string();
+            return;
+        }
+
+        self.search_window.root = root;
+        self.search_window.pattern = pattern;
+        self.search_window.results = results;
+        self.search_window.state.select(Some(0));
+        self.app_mode = AppMode::SearchResults;
+    }
+
+    fn jump_to_search_result(&mut self) {
+        let Some(path) = self.search_window.selected().cloned() else { return };
+        let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else { return };
+
+        self.dir.change_path(parent.to_path_buf());
+        self.refresh_files();
+        self.select_file(&file_name.to_string_lossy());
+        self.app_mode = AppMode::Files;
+        self.directory_changed();
+    }
+
+    /// Shows the results of the last search again, as they were left.
+    fn return_to_search_results(&mut self) {
+        if self.search_window.results.is_empty() {
+            self.error_msg = MyError::FileError("No search results to return to".to_string()).to_string();
+            return;
+        }
+
+        self.app_mode = AppMode::SearchResults;
+    }
+
+    fn select_file(&mut self, file_name: &str) {
+        let index = self.file_list.items.iter().position(|item| item == file_name);
+        self.file_list.state.select(Some(index.unwrap_or(0)));
+    }
+
+    /// Creates the typed file, or directory if it ends with `/`. With `keep_both`, a taken
+    /// name gets a `name (1).ext` style suffix instead of failing.
+    fn create_file(&mut self, keep_both: bool) {
+        let (name, mode) = match self.typed_create_name() {
+            Ok(typed) => typed,
             Err(e) => {
                 self.error_msg = e.to_string();
+                return;
             }
         };
-    }
 
-    fn move_bookmarked(&mut self) {
-        let new_path = match self.dir.get_file_path(self.bookmarked.file_name.clone()) {
+        let mut file_path = match self.dir.get_file_path(name) {
             Ok(path) => path,
             Err(e) => {
                 self.error_msg = e.to_string();
@@ -362,31 +3452,98 @@ impl App {
             }
         };
 
-        match self.dir.rename(self.bookmarked.full_path.clone(), new_path) {
-            Ok(_) => {},
-            Err(e) => {
-                self.error_msg = e.to_string();
-            }
+        if keep_both {
+            let is_dir = file_path.ends_with('/');
+            let unique = directory::unique_path(Path::new(file_path.trim_end_matches('/')), &HashSet::new());
+            file_path = format!("{}{}", unique.to_string_lossy(), if is_dir { "/" } else { "" });
+        }
+
+        let contents = match self.create_seed {
+            _ if file_path.ends_with('/') => None,
+            CreateSeed::Empty => None,
+            CreateSeed::Clipboard => match clipboard::paste() {
+                Ok(contents) => Some(contents),
+                Err(e) => {
+                    self.error_msg = e.to_string();
+                    return;
+                }
+            },
+            CreateSeed::Stdin => self.stdin_content.clone(),
         };
-        
-        self.bookmarked = Bookmarked::default();
+
+        let result = match &contents {
+            Some(contents) => self.dir.create_with_contents(file_path.clone(), contents, mode),
+            None => self.dir.create(file_path.clone(), mode),
+        };
+        self.record("Create", &file_path, None, &result);
+
+        match result.is_ok() && file_path.ends_with('/') && self.enter_created {
+            true => {
+                self.dir.change_path(PathBuf::from(file_path.trim_end_matches('/')));
+                self.directory_changed();
+            },
+            false if result.is_ok() => self.select_after_refresh(Path::new(file_path.trim_end_matches('/'))),
+            false => {},
+        }
+
+        match result {
+            // Seeded files can't pass the untouched check, so there's nothing to undo
+            Ok(_) if contents.is_some() => {},
+            Ok(_) => self.undo_history.push(UndoOperation::Create { path: PathBuf::from(file_path) }),
+            Err(e) => self.error_msg = e.to_string(),
+        }
     }
 
-    fn create_file(&mut self) {
-        let file_path = match self.dir.get_file_path(self.user_input.get_input_value()) {
-            Ok(path) => path,
+    fn record(&mut self, operation: &str, source: &str, destination: Option<&str>, result: &Result<(), MyError>) {
+        let entry = JournalEntry::new(operation, source, destination, result);
+
+        if let Err(e) = self.journal.record(&entry) {
+            self.error_msg = e.to_string();
+        }
+    }
+
+    fn open_undo_history(&mut self) {
+        if self.undo_history.is_empty() {
+            self.error_msg = MyError::FileError("Nothing to undo".to_string()).to_string();
+            return;
+        }
+
+        self.undo_window.state.select(Some(0));
+        self.app_mode = AppMode::Undo;
+    }
+
+    fn undo_selected(&mut self) {
+        let Some(index) = self.undo_window.state.selected() else { return };
+        let Some(description) = self.undo_history.get(index).map(|op| op.description()) else { return };
+
+        let result = self.undo_history.undo(index);
+        self.record("Undo", &description, None, &result);
+
+        if let Err(e) = result {
+            self.error_msg = e.to_string();
+        }
+
+        if self.undo_history.is_empty() {
+            self.app_mode = AppMode::Files;
+        }
+    }
+
+    fn open_journal(&mut self) {
+        self.journal_window.entries = match self.journal.entries() {
+            Ok(entries) => entries,
             Err(e) => {
                 self.error_msg = e.to_string();
                 return;
             }
         };
 
-        match self.dir.create(file_path) {
-            Ok(_) => {},
-            Err(e) => {
-                self.error_msg = e.to_string();
-            }
-        };
+        self.journal_window.state.select(Some(0));
+        self.app_mode = AppMode::Journal;
+    }
+
+    fn open_tasks(&mut self) {
+        self.task_window.state.select(Some(0));
+        self.app_mode = AppMode::Tasks;
     }
 }
 
@@ -394,12 +3551,13 @@ impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let main_height = area.height.saturating_sub(4);
 
-        let [header_area, mut main_area] = Layout::vertical([
+        let [header_area, mut main_area, hint_area] = Layout::vertical([
             Constraint::Length(2),
             Constraint::Length(main_height),
+            Constraint::Length(1),
         ]).areas(area);
 
-        if !self.error_msg.is_empty() {
+        if !self.error_msg.is_empty() || !self.notice.is_empty() {
             let error_area: Rect;
 
             [error_area, main_area] = Layout::vertical([
@@ -410,59 +3568,199 @@ impl Widget for &mut App {
             self.render_error(error_area, buf);
         }
 
-        match self.app_mode {
-            AppMode::Rename | AppMode::Delete | AppMode::Create => {
-                let input_area: Rect;
+        if let Some(kind) = PromptKind::of(self.app_mode) {
+            let input_area: Rect;
+
+            [main_area, input_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(3),
+            ]).areas(main_area);
+
+            self.render_input(kind, input_area, buf);
+        }
 
-                [main_area, input_area] = Layout::vertical([
-                    Constraint::Fill(1),
-                    Constraint::Length(3),
-                ]).areas(main_area);
+        let (files_area, mut metadata_area) = match self.config.listing.layout {
+            MainLayout::Standard => {
+                let [files_area, metadata_area] = Layout::horizontal(
+                    [Constraint::Fill(2), Constraint::Fill(1)]
+                ).areas(main_area);
 
-                self.render_input(input_area, buf);
+                (files_area, metadata_area)
             },
-            _ => {},
-        };
+            MainLayout::Miller => {
+                let [parent_area, files_area, metadata_area] = Layout::horizontal(
+                    [Constraint::Fill(1), Constraint::Fill(2), Constraint::Fill(2)]
+                ).areas(main_area);
 
-        let [files_area, mut metadata_area] = Layout::horizontal(
-            [Constraint::Fill(2), Constraint::Fill(1)]
-        ).areas(main_area);
+                self.render_parent(parent_area, buf);
+                (files_area, metadata_area)
+            },
+        };
 
-        App::render_header(header_area, buf);
+        self.render_header(header_area, buf);
+        self.render_hints(hint_area, buf);
         self.render_files(files_area, buf);
 
-        if !self.bookmarked.file_name.is_empty() {
-            let bookmark_area: Rect;
+        if !self.registers.is_empty() || self.register.is_some() || self.picking_register {
+            let registers_area: Rect;
+            let lines = self.registers.len() as u16 + u16::from(self.register.is_some() || self.picking_register);
 
-            [metadata_area, bookmark_area] = Layout::vertical([
+            [metadata_area, registers_area] = Layout::vertical([
                 Constraint::Fill(1),
-                Constraint::Length(2),
+                Constraint::Length(lines + 1),
             ]).areas(metadata_area);
 
-            self.render_bookmark(bookmark_area, buf);
+            self.render_registers(registers_area, buf);
         }
 
         match self.app_mode {
-            AppMode::Select | AppMode::Rename | AppMode::Delete => {
+            AppMode::Select | AppMode::Rename | AppMode::Delete | AppMode::Permissions | AppMode::RunArgs | AppMode::Compress | AppMode::Decompress | AppMode::Encrypt | AppMode::Decrypt | AppMode::Discard => {
                 let [metadata_area, select_area] = Layout::vertical(
                     [Constraint::Fill(1); 2]
                 ).areas(metadata_area);
 
-                self.render_metadata(metadata_area, buf);
-                self.render_select_menu(select_area, buf);
-            },
-            _ => self.render_metadata(metadata_area, buf),
-        };
+                self.render_details(metadata_area, buf);
+                self.render_select_menu(select_area, buf);
+            },
+            _ => self.render_details(metadata_area, buf),
+        };
+
+        if self.app_mode == AppMode::Help {
+            let help_area = Rect {
+                x: area.width / 6,
+                y: area.height / 8,
+                width: area.width * 2 / 3,
+                height: area.height * 3 / 4,
+            };
+
+            HelpWindow { bindings: help::bindings(&self.help_mode) }.render_help(help_area, buf);
+        }
+
+        if self.app_mode == AppMode::Setup {
+            let setup_area = Rect {
+                x: area.width / 8,
+                y: area.height / 3,
+                width: area.width * 3 / 4,
+                height: 6.min(area.height),
+            };
+
+            self.setup_window.render_setup(setup_area, buf);
+        }
+
+        if self.app_mode == AppMode::About {
+            let about_area = Rect {
+                x: area.width / 6,
+                y: area.height / 3,
+                width: area.width * 2 / 3,
+                height: 8.min(area.height),
+            };
+
+            AboutWindow::render_about(about_area, buf);
+        }
+
+        if self.app_mode == AppMode::ErrorDetails {
+            let error_area = Rect {
+                x: area.width / 6,
+                y: area.height / 4,
+                width: area.width * 2 / 3,
+                height: area.height / 2,
+            };
+
+            ErrorWindow::render_error(&self.error_msg, &self.notice, error_area, buf);
+        }
+
+        if self.app_mode == AppMode::Properties {
+            let properties_area = Rect {
+                x: area.width / 6,
+                y: area.height / 4,
+                width: area.width * 2 / 3,
+                height: area.height / 2,
+            };
+
+            self.properties_window.render_properties(properties_area, buf);
+        }
+
+        if matches!(self.app_mode, AppMode::Bookmarks | AppMode::RenameBookmark | AppMode::RelocateBookmark) {
+            let bookmarks_area = Rect {
+                x: area.width / 4,
+                y: area.height / 4,
+                width: area.width / 2,
+                height: area.height / 2,
+            };
+
+            self.bookmark_window.render_bookmarks(&self.bookmarks, bookmarks_area, buf);
+        }
+
+        if self.app_mode == AppMode::Journal {
+            let journal_area = Rect {
+                x: area.width / 8,
+                y: area.height / 6,
+                width: area.width * 3 / 4,
+                height: area.height * 2 / 3,
+            };
+
+            self.journal_window.render_journal(journal_area, buf);
+        }
+
+        if self.app_mode == AppMode::Tasks {
+            let tasks_area = Rect {
+                x: area.width / 8,
+                y: area.height / 6,
+                width: area.width * 3 / 4,
+                height: area.height * 2 / 3,
+            };
+
+            let running = self.tasks
+                .iter()
+                .map(|task| (task.operation.as_str(), task.source.as_path(), task.progress.as_ref()))
+                .chain(self.verification.iter().map(|task| (task.operation.as_str(), task.source.as_path(), task.progress.as_ref())))
+                .collect::<Vec<RunningTask>>();
+
+            self.task_window.render_tasks(&running, &self.finished_tasks, tasks_area, buf);
+        }
+
+        if self.app_mode == AppMode::BatchPreview {
+            let batch_area = Rect {
+                x: area.width / 6,
+                y: area.height / 4,
+                width: area.width * 2 / 3,
+                height: area.height / 2,
+            };
+
+            self.batch_window.render_plan(batch_area, buf);
+        }
+
+        if self.app_mode == AppMode::Checksums {
+            let checksums_area = Rect {
+                x: area.width / 6,
+                y: area.height / 4,
+                width: area.width * 2 / 3,
+                height: area.height / 2,
+            };
+
+            self.checksum_window.render_results(checksums_area, buf);
+        }
 
-        if self.app_mode == AppMode::Help {
-            let help_area = Rect {
-                x: area.width / 3,
+        if self.app_mode == AppMode::Undo {
+            let undo_area = Rect {
+                x: area.width / 6,
                 y: area.height / 4,
-                width: area.width / 3,
+                width: area.width * 2 / 3,
                 height: area.height / 2,
             };
 
-            HelpWindow::default().render_help(help_area, buf);
+            self.undo_window.render_history(&self.undo_history, undo_area, buf);
+        }
+
+        if self.app_mode == AppMode::SearchResults {
+            let results_area = Rect {
+                x: area.width / 6,
+                y: area.height / 6,
+                width: area.width * 2 / 3,
+                height: area.height * 2 / 3,
+            };
+
+            self.search_window.render_results(results_area, buf);
         }
     }
 }
@@ -471,42 +3769,323 @@ impl Widget for &mut App {
 // Rendering logic
 impl App {
     fn render_error(&self, area: Rect, buf: &mut Buffer) {
-        let error = Line::from(
-            self.error_msg.clone().red()
-        );
+        let mut lines = Vec::new();
+
+        if !self.error_msg.is_empty() {
+            let mut line = Line::from(self.error_msg.clone().red());
+
+            if self.app_mode == AppMode::Files {
+                line.spans.push("  Details ".into());
+                line.spans.push("<Enter>".blue());
+            }
 
-        Paragraph::new(error)
+            lines.push(line);
+        }
+
+        if !self.notice.is_empty() {
+            lines.push(Line::from(self.notice.clone().yellow()));
+        }
+
+        Paragraph::new(lines)
             .bold()
             .centered()
             .render(area, buf);
     }
 
-    fn render_header(area: Rect, buf: &mut Buffer) {
-        Paragraph::new("Directory Manager")
+    /// The bindings of the current mode that fit on one line, with help for the rest.
+    fn render_hints(&self, area: Rect, buf: &mut Buffer) {
+        let more = match self.is_text_input() || self.app_mode == AppMode::Help {
+            true => Vec::new(),
+            false => vec![" All keys ".into(), "<?> ".blue()],
+        };
+        let width = (area.width as usize).saturating_sub(more.iter().map(|span| span.width()).sum());
+
+        // The keys that can finish a started sequence, so `g` shows where it can go
+        let bindings = match (self.sequence.is_pending(), self.app_mode) {
+            (true, AppMode::Select) => help::MENU_SEQUENCES,
+            (true, _) => help::SEQUENCES,
+            (false, mode) => help::bindings(&mode),
+        };
+
+        let mut line = Line::from(help::hint_spans(bindings, width));
+        line.spans.extend(more);
+
+        Paragraph::new(line)
+            .dark_gray()
+            .render(area, buf);
+    }
+
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let title = match config::profile() {
+            Some(profile) => format!("Directory Manager [{}]", profile),
+            None => "Directory Manager".to_string(),
+        };
+
+        Paragraph::new(title)
             .bold()
             .centered()
             .render(area, buf);
+
+        if let Some(repo) = self.git.status() {
+            let mut line = Line::from(vec![
+                format!(" {} ", repo.name).bold(),
+                "on ".dark_gray(),
+                repo.branch.clone().magenta(),
+            ]);
+
+            if repo.dirty {
+                line.spans.push(" *".yellow());
+            }
+
+            Paragraph::new(line)
+                .left_aligned()
+                .render(area, buf);
+        }
+
+        let running = self.tasks
+            .iter()
+            .map(|task| (&task.operation, &task.source, task.progress.as_ref()))
+            .chain(self.verification.iter().map(|task| (&task.operation, &task.source, task.progress.as_ref())))
+            .chain(self.size_calculation.iter().map(|task| (&task.operation, &task.source, task.progress.as_ref())))
+            .map(|(operation, source, progress)| {
+                let file_name = source.file_name().unwrap_or_default().to_string_lossy();
+                let mut status = match progress.percent() {
+                    Some(_) if progress.counts_items() => {
+                        format!("{}: {} {}/{}", operation, file_name, format_count(progress.done()), format_count(progress.total()))
+                    },
+                    Some(percent) => match progress.speed() {
+                        0 => format!("{}: {} {}%", operation, file_name, percent),
+                        speed => format!("{}: {} {}% at {}/s", operation, file_name, percent, format_size(speed)),
+                    },
+                    None => format!("{}: {}…", operation, file_name),
+                };
+
+                if let Some(remaining) = progress.remaining() {
+                    status.push_str(&format!(", {} left", format_duration(remaining)));
+                }
+
+                status
+            })
+            .collect::<Vec<String>>();
+
+        let mut status = Line::default();
+
+        if !running.is_empty() {
+            status.spans.push(format!("{} ", running.join(", ")).yellow());
+        }
+
+        if let Some(size) = self.trash_size.as_ref().filter(|size| size.items > 0) {
+            status.spans.push(format!("Trash: {}, empty ", format_size(size.bytes)).dark_gray());
+            status.spans.push("<Z> ".blue());
+        }
+
+        if !status.spans.is_empty() {
+            Paragraph::new(status)
+                .right_aligned()
+                .render(area, buf);
+        }
     }
 
     fn render_files(&mut self, area: Rect, buf: &mut Buffer) {
-        let current_path = Line::from(format!(" {} ", self.dir.get_current_path())).left_aligned();
+        let mut current_path = Line::from(format!(" {} ", self.dir.get_current_path())).left_aligned();
+
+        if self.dir.is_flat() {
+            current_path.spans.push("[flat] ".into());
+        }
+
+        let (sort, descending) = self.dir.sort();
+
+        if sort != SortMode::Name || descending {
+            let direction = if descending { " ↓" } else { "" };
+            current_path.spans.push(format!("[by {}{}] ", sort, direction).into());
+        }
+
+        if !self.dir.shows_hidden() {
+            current_path.spans.push("[no hidden] ".into());
+        }
+
+        if self.scan.is_some() {
+            current_path.spans.push("[scanning, <Esc> stops] ".into());
+        }
+
+        match (&self.network_fs, self.is_cautious()) {
+            (Some(fs_type), true) => current_path.spans.push(format!("[{}, <Ctrl-r> refreshes] ", fs_type).into()),
+            (Some(fs_type), false) => current_path.spans.push(format!("[{}] ", fs_type).into()),
+            (None, _) => {},
+        }
+
+        let filter = self.dir.filter();
+
+        if !filter.text.is_empty() {
+            current_path.spans.push(format!("[filter: {}] ", filter.text).into());
+        }
+
+        if let Some(kind) = &filter.kind {
+            current_path.spans.push(format!("[{}] ", kind).into());
+        }
+
+        if let Some(recency) = filter.modified {
+            current_path.spans.push(format!("[{}] ", recency).into());
+        }
+
+        if self.dir.has_ignore() && self.dir.shows_ignored() {
+            current_path.spans.push("[ignored shown] ".into());
+        }
+
+        if self.dir.path_mode() == PathMode::Physical {
+            current_path.spans.push("[physical] ".into());
+        } else if self.config.paths.show_resolved {
+            if let Some(resolved) = self.dir.resolved_path() {
+                current_path.spans.push(format!("→ {} ", resolved.display()).dark_gray());
+            }
+        }
 
         let instruction = Line::from(vec![
             " Help ".into(),
-            "<?> ".blue().into(),
+            "<?> ".blue(),
         ]);
 
-        let block = Block::bordered()
-            .title(Line::from(" Files "))
+        // The path gives way to the tags and the help hint, keeping its last components whole
+        let reserved = current_path.spans[1..].iter().map(Span::width).sum::<usize>() + instruction.width() + 4;
+        let path_width = (area.width as usize).saturating_sub(reserved);
+        current_path.spans[0] = format!(" {} ", shorten_path(&self.dir.get_current_path(), path_width)).into();
+
+        let title = match &self.scan {
+            Some(scan) => {
+                let frame = SPINNER[(scan.elapsed().as_millis() / TICK_INTERVAL.as_millis()) as usize % SPINNER.len()];
+                format!(" Files {} {:.1}s ", frame, scan.elapsed().as_secs_f64())
+            },
+            None if self.file_list.items == [UNLISTABLE] => " Files ".to_string(),
+            None => match (self.file_list.items.len(), self.file_list.state.selected()) {
+                (0, _) => " Files: 0 items ".to_string(),
+                // Where the cursor is among what's listed, filters applied; it can be past the end until `visible_rows` pulls it back
+                (count, Some(selected)) => format!(" Files: {}/{} ", format_count(selected.min(count - 1) as u64 + 1), format_count(count as u64)),
+                (1, None) => " Files: 1 item ".to_string(),
+                (count, None) => format!(" Files: {} items ", format_count(count as u64)),
+            },
+        };
+
+        let mut block = Block::bordered()
+            .title(Line::from(title))
             .title_bottom(current_path.yellow())
             .title_bottom(instruction.right_aligned())
             .border_set(border::THICK);
 
+        if self.focused_panel() == Some(Panel::Files) {
+            block = block.border_style(Style::new().yellow());
+        }
+
+        if !self.marked.is_empty() {
+            let summary = format!(" {} marked, {} total ", self.marked.len(), format_size(self.marked.total_size()));
+            block = block.title(Line::from(summary.yellow()).right_aligned());
+        }
+
+        let current_path = self.dir.current_path();
+        let mut list_area = block.inner(area);
+        block.render(area, buf);
+
+        let columns = match self.details {
+            true => self.config.listing.columns.clone(),
+            false => vec![ColumnConfig { name: Column::Name, width: None }],
+        };
+
+        // The highlight symbol and mark take two cells, every column after the first a separator
+        let fixed_width = columns.iter().filter_map(ColumnConfig::width).sum::<usize>() + columns.len().saturating_sub(1) + 2;
+        let name_width = (list_area.width as usize).saturating_sub(fixed_width);
+
+        if self.details {
+            let header_area: Rect;
+            [header_area, list_area] = Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(list_area);
+
+            self.render_column_header(&columns, name_width, header_area, buf);
+        }
+
+        // Show why entries are listed: the search while there is one, otherwise the name filter
+        let query = match self.list_search.is_empty() {
+            true => self.dir.filter().text.clone(),
+            false => self.list_search.clone(),
+        };
+        let query = query.as_str();
+
+        // Only the entries on screen are looked up, as every one is a stat and maybe a git lookup
+        let rows = self.file_list.visible_rows(list_area.height as usize);
+        let mut state = ListState::default().with_selected(self.file_list.state.selected().and_then(|selected| selected.checked_sub(rows.start)));
+
         let items: Vec<ListItem> = self
             .file_list
-            .items
+            .items[rows]
             .iter()
-            .map(|item| ListItem::from(format!(" {}", item)))
+            .map(|item| {
+                // Every entry would be a round trip to the server
+                let metadata = match self.is_cautious() {
+                    true => None,
+                    false => self.dir.get_metadata(item.clone()),
+                };
+                let (mark, style) = if self.marked.contains(&current_path.join(item)) {
+                    ("*", Style::new().yellow())
+                } else if metadata.as_ref().is_some_and(|metadata| metadata.is_executable()) {
+                    (" ", Style::new().green())
+                } else if metadata.as_ref().is_some_and(|metadata| metadata.filetype.is_special()) {
+                    (" ", Style::new().magenta())
+                } else if metadata.as_ref().is_some_and(|metadata| metadata.empty) {
+                    (" ", Style::new().dark_gray())
+                } else {
+                    (" ", Style::new())
+                };
+
+                let indicator = metadata.as_ref().map(|metadata| metadata.filetype.indicator()).unwrap_or_default();
+                let mut row = vec![Span::raw(mark)];
+
+                for (index, column) in columns.iter().enumerate() {
+                    if index > 0 {
+                        row.push(Span::raw(" "));
+                    }
+
+                    let value = match column.name {
+                        Column::Name if !self.details => self.display_name(item) + indicator,
+                        Column::Name => fit(&(self.display_name(item) + indicator), name_width, false),
+                        Column::Git => self.git.file_status(&current_path.join(item)).unwrap_or_default().to_string(),
+                        name => name.value(metadata.as_ref(), &self.config.listing.time_format),
+                    };
+
+                    match column.name {
+                        Column::Name => row.extend(highlight_matches(&value, query, MATCH_STYLE)),
+                        name => row.push(Span::raw(fit(&value, column.width().unwrap_or(name_width), name.right_aligned()))),
+                    }
+                }
+
+                ListItem::from(Line::from(row)).style(style)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, list_area, buf, &mut state);
+    }
+
+    // Left column of the Miller layout, with the current directory highlighted
+    fn render_parent(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(Line::from(" .. "))
+            .border_set(border::THICK);
+
+        let parent = match self.is_cautious() {
+            true => None,
+            false => self.dir.parent_contents(),
+        };
+
+        let Some((names, current)) = parent else {
+            block.render(area, buf);
+            return;
+        };
+
+        let mut state = ListState::default().with_selected(names.iter().position(|name| *name == current));
+        let items: Vec<ListItem> = names
+            .into_iter()
+            .map(|name| ListItem::from(format!(" {}", name)))
             .collect();
 
         let list = List::new(items)
@@ -515,40 +4094,218 @@ impl App {
             .highlight_symbol(">")
             .highlight_spacing(HighlightSpacing::Always);
 
-        StatefulWidget::render(list, area, buf, &mut self.file_list.state);
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+
+    // Column titles, with the one the listing is sorted by highlighted
+    fn render_column_header(&self, columns: &[ColumnConfig], name_width: usize, area: Rect, buf: &mut Buffer) {
+        let (sort, descending) = self.dir.sort();
+        let mut header = Line::from("  ");
+
+        for (index, column) in columns.iter().enumerate() {
+            if index > 0 {
+                header.spans.push(" ".into());
+            }
+
+            let width = column.width().unwrap_or(name_width);
+            let (column, right_aligned) = (column.name, column.name.right_aligned());
+
+            if column.is_sorted_by(sort) {
+                let arrow = if descending { "↓" } else { "↑" };
+                let title = match sort {
+                    SortMode::Extension => format!("{} (ext) {}", column.title(), arrow),
+                    _ => format!("{} {}", column.title(), arrow),
+                };
+
+                header.spans.push(fit(&title, width, right_aligned).yellow().bold());
+            } else {
+                header.spans.push(fit(column.title(), width, right_aligned).dark_gray().bold());
+            }
+        }
+
+        Paragraph::new(header).render(area, buf);
+    }
+
+    // Entries of expanded directories are indented under their parent instead of showing the whole relative path
+    fn display_name(&self, item: &str) -> String {
+        if self.dir.is_flat() {
+            return item.to_string();
+        }
+
+        let depth = item.matches('/').count();
+        let name = item.rsplit('/').next().unwrap_or(item);
+        let marker = if self.dir.is_expanded(item) { "▾ " } else { "" };
+
+        format!("{}{}{}", "  ".repeat(depth), marker, name)
+    }
+
+    fn render_details(&mut self, area: Rect, buf: &mut Buffer) {
+        let info = self.metadata_lines();
+
+        if info.is_empty() {
+            self.render_preview(area, buf);
+            return;
+        }
+
+        let [metadata_area, preview_area] = Layout::vertical([
+            Constraint::Length(info.len() as u16 + 2),
+            Constraint::Fill(1),
+        ]).areas(area);
+
+        App::render_metadata(info, metadata_area, buf);
+        self.render_preview(preview_area, buf);
     }
 
-    fn render_metadata(&mut self, area: Rect, buf: &mut Buffer) {
-        let file_name = match self.file_list.state.selected() {
-            Some(i) => self.file_list.items[i].to_string(),
+    fn selected_file_name(&self) -> String {
+        match self.file_list.state.selected() {
+            Some(i) => self.file_list.items.get(i).cloned().unwrap_or_default(),
             None => "".to_string()
+        }
+    }
+
+    fn metadata_lines(&mut self) -> Vec<Line<'static>> {
+        let file_name = self.selected_file_name();
+
+        let plugin_columns = match self.dir.get_file_path(file_name.clone()) {
+            Ok(path) => self.plugins.columns(&path),
+            Err(_) => Vec::new(),
         };
 
+        let path = self.dir.current_path().join(&file_name);
         let metadata = match self.dir.get_metadata(file_name) {
             Some(metadata) => metadata,
-            None => return
+            None => return Vec::new()
         };
 
-        let block = Block::bordered()
-            .title(Line::from(" Properties "))
-            .border_set(border::THICK);
+        let executable = if metadata.is_executable() { " (executable)".green() } else { "".into() };
+        let sparse = if metadata.is_sparse() { " (sparse)".yellow() } else { "".into() };
+        let allocated = metadata.allocated().filter(|_| metadata.filetype == directory::FileTypeEnum::File);
 
-        let info = vec![
+        let mut info = vec![
             Line::from(vec![
-                " Filename: ".blue().into(),
+                " Filename: ".blue(),
                 metadata.file_name.into(),
             ]),
             Line::from(vec![
-                " Type: ".blue().into(),
+                " Type: ".blue(),
                 metadata.filetype.to_string().into(),
+                executable,
             ]),
             Line::from(vec![
-                " Size: ".blue().into(),
+                " Size: ".blue(),
                 metadata.size.to_string().into(),
                 " B".into(),
             ]),
+            Line::from(vec![
+                " Permissions: ".blue(),
+                permissions::mode_string(metadata.mode).into(),
+                format!(" ({:o})", metadata.mode).into(),
+            ]),
         ];
 
+        if let Some(allocated) = allocated {
+            info.insert(3, Line::from(vec![
+                " On Disk: ".blue(),
+                allocated.to_string().into(),
+                " B".into(),
+                sparse,
+            ]));
+        }
+
+        if let Some(modified) = metadata.modified {
+            info.push(Line::from(vec![
+                " Modified: ".blue(),
+                self.config.listing.time_format.format(modified).into(),
+            ]));
+        }
+
+        let tags = finder::tags(&path);
+
+        if !tags.is_empty() {
+            info.push(Line::from(vec![
+                " Tags: ".blue(),
+                tags.join(", ").into(),
+            ]));
+        }
+
+        if finder::is_quarantined(&path) {
+            info.push(Line::from(vec![
+                " Quarantined: ".blue(),
+                "yes".yellow(),
+                " (clear <t><x>)".into(),
+            ]));
+        }
+
+        if cfg!(windows) {
+            info.push(Line::from(vec![
+                " Attributes: ".blue(),
+                permissions::attribute_string(metadata.attributes).into(),
+            ]));
+        }
+
+        if self.git.status().is_some() {
+            match self.git.last_commit(&path) {
+                Some(Some(commit)) => {
+                    info.push(Line::from(vec![
+                        " Last Commit: ".blue(),
+                        format!("{} ", commit.hash).yellow(),
+                        commit.summary.clone().into(),
+                    ]));
+                    info.push(Line::from(vec![
+                        " Committed: ".blue(),
+                        format!("{} by {}", self.config.listing.time_format.format(commit.time), commit.author).into(),
+                    ]));
+                },
+                Some(None) => info.push(Line::from(vec![" Last Commit: ".blue(), "none".dark_gray()])),
+                None => info.push(Line::from(vec![" Last Commit: ".blue(), "loading…".dark_gray()])),
+            }
+        }
+
+        if let Some(target) = metadata.link_target {
+            info.push(Line::from(vec![
+                " Link Target: ".blue(),
+                target.display().to_string().into(),
+            ]));
+        }
+
+        if let Some(inode) = metadata.inode.filter(|_| self.extended_properties) {
+            info.push(Line::from(vec![
+                " Inode: ".blue(),
+                inode.inode.to_string().into(),
+            ]));
+            info.push(Line::from(vec![
+                " Hard Links: ".blue(),
+                inode.links.to_string().into(),
+            ]));
+            info.push(Line::from(vec![
+                " Blocks: ".blue(),
+                format!("{} × 512 B allocated", inode.blocks).into(),
+            ]));
+            info.push(Line::from(vec![
+                " I/O Block: ".blue(),
+                format!("{} B", inode.block_size).into(),
+            ]));
+            info.push(Line::from(vec![
+                " Device: ".blue(),
+                format!("{} ({:x}h)", inode.device, inode.device).into(),
+            ]));
+        }
+
+        info.extend(plugin_columns.into_iter().map(|(name, value)| {
+            Line::from(vec![
+                format!(" {}: ", name).blue(),
+                value.into(),
+            ])
+        }));
+
+        info
+    }
+
+    fn render_metadata(info: Vec<Line>, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title(Line::from(" Properties "))
+            .border_set(border::THICK);
+
         Paragraph::new(info)
             .block(block)
             .bold()
@@ -556,13 +4313,67 @@ impl App {
             .render(area, buf);
     }
 
-    fn render_bookmark(&self, area: Rect, buf: &mut Buffer) {
-        let bookmark = Line::from(vec![
-            " Bookmark: ".blue().into(),
-            self.bookmarked.file_name.clone().into(),
-        ]);
+    fn render_preview(&mut self, area: Rect, buf: &mut Buffer) {
+        let path = self.dir.get_file_path(self.selected_file_name()).ok().map(PathBuf::from);
+
+        let show_diff = path.as_ref().is_some_and(|path| self.diff_paths.contains(path));
+
+        let preview = match (&path, &self.follower) {
+            (Some(path), Some(follower)) if follower.path() == path => Preview::Lines(follower.lines()),
+            (Some(path), _) if show_diff => self.previewer.diff(path),
+            (Some(path), _) if self.is_cautious() && !self.previewer.is_forced(path) => Preview::Lines(vec![
+                "on a network file system —".to_string(),
+                "press v to preview".to_string(),
+            ]),
+            (Some(path), _) => self.previewer.preview(path),
+            (None, _) => Preview::Lines(Vec::new()),
+        };
+
+        let lines = match preview {
+            Preview::Lines(lines) => lines,
+            Preview::Skipped { binary, size } => {
+                let kind = if binary { "binary, " } else { "" };
+                vec![
+                    format!("{}{} —", kind, format_size(size)),
+                    "press v to view anyway".to_string(),
+                ]
+            },
+            Preview::Special(file_type) => vec![format!("{} — no preview", file_type)],
+        };
+
+        let focused = self.focused_panel() == Some(Panel::Preview);
+
+        self.preview_pane.diff = show_diff && self.follower.is_none();
+        self.preview_pane.set_content(path.as_deref(), lines);
+        self.preview_pane.render(focused, area, buf);
+    }
+
+    fn render_registers(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines = self.registers
+            .iter()
+            .map(|(name, register)| {
+                let verb = if register.kind == BatchKind::Move { "Cut" } else { "Yanked" };
+                let names = register.paths
+                    .iter()
+                    .map(|path| path.file_name().unwrap_or_default().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                Line::from(vec![
+                    format!(" \"{} ", name).blue(),
+                    format!("{}: ", verb).blue(),
+                    names.into(),
+                ])
+            })
+            .collect::<Vec<Line>>();
+
+        if self.picking_register {
+            lines.push(Line::from(" \"… register a–z".yellow()));
+        } else if let Some(name) = self.register {
+            lines.push(Line::from(format!(" \"{} for the next <y>, <X> or <b>", name).yellow()));
+        }
 
-        Paragraph::new(bookmark)
+        Paragraph::new(lines)
             .bold()
             .left_aligned()
             .render(area, buf);
@@ -571,7 +4382,7 @@ impl App {
     fn render_select_menu(&mut self, area: Rect, buf: &mut Buffer) {
         let title_bottom = Line::from(vec![
             " Exit ".into(),
-            "<Esc> ".blue().into(),
+            "<Esc> ".blue(),
         ]);
 
         let block = Block::bordered()
@@ -595,32 +4406,220 @@ impl App {
         StatefulWidget::render(list, area, buf, &mut self.select_list.state);
     }
 
-    fn render_input(&mut self, area: Rect, buf: &mut Buffer) {
+    fn render_input(&mut self, kind: PromptKind, area: Rect, buf: &mut Buffer) {
+        let accent = kind.accent();
         let block = Block::bordered()
-            .title(Line::from(" Input "))
+            .title(Line::from(format!(" {} {} ", kind.icon(), kind.title())))
+            .border_style(Style::new().fg(accent))
             .border_set(border::THICK);
 
         let input_text = match self.app_mode {
-            AppMode::Rename => Line::from(vec![" Renaming a file: ".blue().into()]),
+            AppMode::LinkTarget => Line::from(vec![" New link target: ".fg(accent)]),
+            AppMode::Rename => Line::from(vec![" Renaming a file: ".fg(accent)]),
             AppMode::Delete => {
-                let Some(index) = self.file_list.state.selected() else { return };
-                let file_name = self.file_list.items[index].clone();
+                let file_name = self.pending_target_name();
+
+                match &self.delete_confirmation {
+                    DeleteConfirmation::Simple => Line::from(vec![
+                        " Delete a file: ".fg(accent),
+                        file_name.into(),
+                        " (y/n) ".fg(accent),
+                    ]),
+                    DeleteConfirmation::TypedName(stats) => {
+                        // The scan stops at the first exceeded limit, so report the limit rather than a partial count
+                        let limits = &self.config.delete;
+                        let reason = if stats.entries > limits.confirm_entries {
+                            format!("more than {} entries", limits.confirm_entries)
+                        } else {
+                            format!("more than {}", format_size(limits.confirm_size))
+                        };
+
+                        Line::from(vec![
+                            " Delete a directory: ".fg(accent),
+                            file_name.into(),
+                            format!(" ({}). Type its name to confirm: ", reason).fg(accent),
+                        ])
+                    },
+                }
+            },
+            AppMode::Create => {
+                let seed = match self.create_seed {
+                    CreateSeed::Empty => "empty",
+                    CreateSeed::Clipboard => "from clipboard",
+                    CreateSeed::Stdin => "from stdin",
+                };
+
+                let input = self.user_input.get_input_value();
+                let (name, _) = permissions::split_mode(&input);
+                let mode = &self.typed_name.mode;
+
+                match name.ends_with('/') {
+                    true => {
+                        let then = if self.enter_created { "then enter it" } else { "stay here" };
+                        Line::from(vec![format!(" Creating a directory ({} <Tab>, {}): ", then, mode).fg(accent)])
+                    },
+                    false => Line::from(vec![format!(" Creating a file ({} <Tab>, {}): ", seed, mode).fg(accent)]),
+                }
+            },
+            AppMode::RenameBookmark => Line::from(vec![" Renaming a bookmark: ".fg(accent)]),
+            AppMode::RelocateBookmark => Line::from(vec![" Relocating a bookmark to: ".fg(accent)]),
+            AppMode::Command => Line::from(vec![" :".fg(accent)]),
+            AppMode::PreviewSearch => Line::from(vec![" Search preview: ".fg(accent)]),
+            AppMode::ListSearch => {
+                let matches = self.file_list.items
+                    .iter()
+                    .filter(|item| self.matches_list_search(item))
+                    .count();
+
+                Line::from(vec![format!(" Search listing ({} matches, <n><N> to cycle): ", matches).fg(accent)])
+            },
+            AppMode::Views => {
+                let views = self.config.views
+                    .iter()
+                    .filter_map(|view| view.key.map(|key| format!("<{}> {}", key, view.name)))
+                    .collect::<Vec<String>>();
+
+                match views.is_empty() {
+                    true => Line::from(vec![" No views with a key in config, use :view <name> ".fg(accent)]),
+                    false => Line::from(vec![format!(" View: {} ", views.join(", ")).fg(accent)]),
+                }
+            },
+            AppMode::CreateCollision => Line::from(vec![" Already exists, keep both <b> or change the name <Esc>: ".fg(accent)]),
+            AppMode::MoveCollision => {
+                let Some(pending) = &self.pending_move else { return };
+                let back = if pending.rename { "change the name" } else { "cancel" };
+
+                Line::from(vec![
+                    format!(" {} already exists, keep both <b> or {} <Esc> ", pending.destination, back).fg(accent),
+                ])
+            },
+            AppMode::Attributes => {
+                let attributes = self.dir.get_metadata(self.selected_file_name()).map_or(0, |metadata| metadata.attributes);
+                let toggles = Attribute::ALL
+                    .iter()
+                    .map(|attribute| {
+                        let state = if attribute.is_set(attributes) { "on" } else { "off" };
+                        format!("{} ({}) <{}>", attribute.name(), state, attribute.key())
+                    })
+                    .collect::<Vec<String>>();
+
+                Line::from(vec![
+                    " Attributes of ".fg(accent),
+                    self.selected_file_name().into(),
+                    format!(": {}, done <Esc> ", toggles.join(", ")).fg(accent),
+                ])
+            },
+            AppMode::FinderTags => {
+                let file_name = self.selected_file_name();
+                let path = self.dir.current_path().join(&file_name);
+                let tags = finder::tags(&path);
+                let colors = TagColor::ALL.map(|color| color.name());
+                let quarantine = if finder::is_quarantined(&path) { ", clear quarantine <x>" } else { "" };
 
                 Line::from(vec![
-                    " Delete a file: ".blue().into(),
+                    " Tags of ".fg(accent),
                     file_name.into(),
-                    " (y/n) ".blue().into(),
+                    ": ".fg(accent),
+                    if tags.is_empty() { "none".into() } else { tags.join(", ").into() },
+                    format!(". Toggle {} <1-7>{}, done <Esc> ", colors.join(", "), quarantine).fg(accent),
                 ])
             },
-            AppMode::Create => Line::from(vec![" Creating a file: ".blue().into()]),
+            AppMode::BatchDestination => Line::from(vec![
+                format!(" {} ", self.batch_kind).fg(accent),
+                self.targets_description().into(),
+                " to directory: ".fg(accent),
+            ]),
+            AppMode::MarkGlob => Line::from(vec![" Mark matching (e.g. *.log): ".fg(accent)]),
+            AppMode::Filter => Line::from(vec![" Filter (keep <Enter>, clear <Esc>): ".fg(accent)]),
+            AppMode::ExtensionFilter => Line::from(vec![" Show only extensions (e.g. rs,toml; empty for all): ".fg(accent)]),
+            AppMode::Compress => {
+                let keep = if self.keep_original { "keep original" } else { "replace original" };
+
+                Line::from(vec![
+                    " Compress ".fg(accent),
+                    self.selected_file_name().into(),
+                    " with ".fg(accent),
+                    self.compress_codec.to_string().into(),
+                    format!(" (<g>zip, <z>std), {} <Tab>, start <Enter> ", keep).fg(accent),
+                ])
+            },
+            AppMode::Decompress => {
+                let keep = if self.keep_original { "keep original" } else { "replace original" };
+
+                Line::from(vec![
+                    " Decompress ".fg(accent),
+                    self.selected_file_name().into(),
+                    format!(" ({} <Tab>, start <Enter>) ", keep).fg(accent),
+                ])
+            },
+            AppMode::EmptyTrash => {
+                let size = self.trash_size.as_ref().map(|size| {
+                    format!("{}, {}", format_quantity(size.items, "item", "items"), format_size(size.bytes))
+                }).unwrap_or_default();
+
+                Line::from(vec![format!(" Permanently delete everything in the trash ({}) (y/n) ", size).fg(accent)])
+            },
+            AppMode::Discard => Line::from(vec![
+                " Discard unstaged changes to ".fg(accent),
+                self.selected_file_name().into(),
+                " (y/n) ".fg(accent),
+            ]),
+            AppMode::Encrypt => {
+                let key = if self.passphrase_input { "passphrase" } else { "recipient (age1...)" };
+
+                Line::from(vec![
+                    " Encrypt ".fg(accent),
+                    self.selected_file_name().into(),
+                    format!(" with {} <Tab>: ", key).fg(accent),
+                ])
+            },
+            AppMode::Decrypt => {
+                let secret = if self.passphrase_input { "passphrase" } else { "identity file" };
+
+                Line::from(vec![
+                    " Decrypt ".fg(accent),
+                    self.selected_file_name().into(),
+                    format!(" with {}: ", secret).fg(accent),
+                ])
+            },
+            AppMode::RunArgs => Line::from(vec![
+                " Run ".fg(accent),
+                self.selected_file_name().into(),
+                " with arguments: ".fg(accent),
+            ]),
+            AppMode::Permissions => {
+                let target = if self.marked.is_empty() {
+                    "".to_string()
+                } else {
+                    format!("{} marked ({}), ", self.marked.len(), format_size(self.marked.total_size()))
+                };
+
+                let recursive = if self.chmod_recursive { "recursive" } else { "not recursive" };
+
+                Line::from(vec![
+                    format!(" Permissions ({}{} <Tab>, e.g. 644, 644/755, 755X, u+x,go-w): ", target, recursive).fg(accent),
+                ])
+            },
+            AppMode::Search => match self.search_window.kind {
+                SearchKind::Name => Line::from(vec![" Find by name: ".fg(accent)]),
+                SearchKind::Content => Line::from(vec![" Find in contents: ".fg(accent)]),
+            },
             _ => Line::from(vec!["".into()]),
         };
 
-        let input_value = self.user_input.get_input_value();
+        let mut input_value = self.user_input.get_input_value();
+
+        if self.passphrase_input && matches!(self.app_mode, AppMode::Encrypt | AppMode::Decrypt) {
+            input_value = "*".repeat(input_value.chars().count());
+        }
 
         let mut input_block = input_text;
         input_block.spans.push(input_value.into());
-        input_block.spans.push("_".yellow().into());
+        input_block.spans.push("_".yellow());
+
+        if let Some(e) = self.typed_name.error.as_ref().filter(|_| matches!(self.app_mode, AppMode::Create | AppMode::Rename)) {
+            input_block.spans.push(format!(" {}", e).red());
+        }
 
         Paragraph::new(input_block)
             .block(block)
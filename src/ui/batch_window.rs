@@ -0,0 +1,96 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::batch::{BatchKind, BatchPlan};
+
+use super::app::SELECTED_STYLE;
+
+/// Dry run of a bulk copy, move or delete, shown for confirmation before it runs.
+#[derive(Default)]
+pub struct BatchWindow {
+    pub plan: Option<BatchPlan>,
+    /// e.g. "Move 12 items (340 MiB) to ~/backup"
+    pub title: String,
+    pub state: ListState,
+}
+
+impl BatchWindow {
+    pub fn render_plan(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(plan) = &self.plan else { return };
+
+        Clear.render(area, buf);
+
+        let conflicts = plan.conflicts();
+        let summary = match conflicts {
+            0 => " no conflicts ".green(),
+            _ => format!(" {} conflicting, will be skipped ", conflicts).red(),
+        };
+
+        let mut title = Line::from(vec![format!(" {} ", self.title).into(), summary]);
+
+        if plan.needs_symlink_choice() {
+            title.spans.push(format!("{} symlinks, copy or follow them? ", plan.symlinks).yellow());
+        }
+
+        let mut bottom_title = Line::from(vec![
+            " Run ".into(),
+            "<Enter>".blue(),
+            " Cancel ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        if plan.kind == BatchKind::Copy {
+            bottom_title.spans.splice(0..0, [
+                format!(" Update ({}) ", plan.options.update).into(),
+                "<u>".blue(),
+                format!(" Symlinks ({}) ", plan.options.symlinks).into(),
+                "<l>".blue(),
+                format!(" Preserve ({}) ", on_off(plan.options.preserve)).into(),
+                "<p>".blue(),
+            ]);
+        }
+
+        if plan.kind != BatchKind::Delete {
+            bottom_title.spans.splice(0..0, [format!(" Keep both ({}) ", on_off(plan.options.keep_both)).into(), "<b>".blue()]);
+        }
+
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = plan.steps
+            .iter()
+            .map(|step| {
+                let action = match &step.destination {
+                    Some(destination) => format!(" {} → {}", step.source.display(), destination.display()),
+                    None => format!(" {}", step.source.display()),
+                };
+
+                match step.conflict {
+                    Some(conflict) => ListItem::from(Line::from(vec![action.red(), format!(" ({})", conflict).red()])),
+                    None if step.updates => ListItem::from(Line::from(vec![action.into(), " (update)".yellow()])),
+                    None => ListItem::from(action),
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
+
+fn on_off(enabled: bool) -> &'static str {
+    if enabled { "on" } else { "off" }
+}
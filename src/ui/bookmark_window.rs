@@ -0,0 +1,79 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::bookmarks::Bookmarks;
+
+use super::app::SELECTED_STYLE;
+
+#[derive(Default)]
+pub struct BookmarkWindow {
+    pub state: ListState,
+}
+
+impl BookmarkWindow {
+    pub fn render_bookmarks(&mut self, bookmarks: &Bookmarks, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let bottom_title = Line::from(vec![
+            " Jump ".into(),
+            "<Enter>".blue(),
+            " Add ".into(),
+            "<a>".blue(),
+            " Rename ".into(),
+            "<r>".blue(),
+            " Relocate ".into(),
+            "<R>".blue(),
+            " Slot ".into(),
+            "<1-9>".blue(),
+            " Remove ".into(),
+            "<d>".blue(),
+            " Move to ".into(),
+            "<m>".blue(),
+            " Copy to ".into(),
+            "<c>".blue(),
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" Bookmarks "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = bookmarks
+            .entries()
+            .iter()
+            .map(|bookmark| {
+                let slot = match bookmark.slot {
+                    Some(slot) => format!(" {}", slot).yellow(),
+                    None => "  ".into(),
+                };
+
+                let path = match bookmark.missing {
+                    true => format!("{} (missing)", bookmark.path).red(),
+                    false => bookmark.path.clone().dark_gray(),
+                };
+
+                ListItem::from(Line::from(vec![
+                    slot,
+                    format!(" {} ", bookmark.label).bold(),
+                    path,
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
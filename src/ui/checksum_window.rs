@@ -0,0 +1,68 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::checksum::{Verification, VerificationStatus};
+
+use super::app::SELECTED_STYLE;
+
+#[derive(Default)]
+pub struct ChecksumWindow {
+    pub sidecar: String,
+    pub results: Vec<Verification>,
+    pub state: ListState,
+}
+
+impl ChecksumWindow {
+    pub fn render_results(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let failed = self.results
+            .iter()
+            .filter(|result| result.status != VerificationStatus::Passed)
+            .count();
+
+        let summary = if failed == 0 {
+            format!(" all {} passed ", self.results.len()).green()
+        } else {
+            format!(" {} of {} failed ", failed, self.results.len()).red()
+        };
+
+        let bottom_title = Line::from(vec![
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(vec![format!(" Verify {} ", self.sidecar).into(), summary]))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .map(|result| {
+                let status = match result.status {
+                    VerificationStatus::Passed => " PASS    ".green(),
+                    VerificationStatus::Failed => " FAIL    ".red(),
+                    VerificationStatus::Missing => " MISSING ".yellow(),
+                };
+
+                ListItem::from(Line::from(vec![status, result.file_name.clone().into()]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,105 @@
+use serde::Deserialize;
+
+use crate::{directory::{FileMetadata, FileTypeEnum, SortMode}, permissions};
+
+use super::format::{format_size, TimeFormat};
+
+/// A column of the detailed file list. The name column takes whatever width is left
+/// unless it's given one.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Name,
+    Size,
+    /// Allocated size, which `~` marks as less than the apparent one for sparse files
+    Disk,
+    Modified,
+    Permissions,
+    Owner,
+    Git,
+}
+
+impl Column {
+    pub const DEFAULT: [Column; 4] = [Column::Name, Column::Size, Column::Modified, Column::Permissions];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Column::Name => "Name",
+            Column::Size => "Size",
+            Column::Disk => "Disk",
+            Column::Modified => "Modified",
+            Column::Permissions => "Permissions",
+            Column::Owner => "Owner",
+            Column::Git => "Git",
+        }
+    }
+
+    /// Default width of the column, or None for the name column.
+    pub fn width(&self) -> Option<usize> {
+        match self {
+            Column::Name => None,
+            Column::Size => Some(10),
+            Column::Disk => Some(10),
+            Column::Modified => Some(16),
+            Column::Permissions => Some(11),
+            Column::Owner => Some(10),
+            Column::Git => Some(3),
+        }
+    }
+
+    pub fn right_aligned(&self) -> bool {
+        matches!(self, Column::Size | Column::Disk)
+    }
+
+    pub fn is_sorted_by(&self, sort: SortMode) -> bool {
+        matches!(
+            (self, sort),
+            (Column::Name, SortMode::Name | SortMode::Extension) | (Column::Size, SortMode::Size) | (Column::Modified, SortMode::Modified)
+        )
+    }
+
+    /// The cell for every column but the name and git status, which the caller formats.
+    pub fn value(&self, metadata: Option<&FileMetadata>, time_format: &TimeFormat) -> String {
+        let Some(metadata) = metadata else { return String::new() };
+
+        match self {
+            Column::Name => metadata.file_name.clone(),
+            Column::Size => match metadata.filetype {
+                FileTypeEnum::Directory => "-".to_string(),
+                file_type if file_type.is_special() => "-".to_string(),
+                _ => format_size(metadata.size),
+            },
+            Column::Disk => match (metadata.filetype, metadata.allocated()) {
+                (FileTypeEnum::Directory, _) => "-".to_string(),
+                (file_type, _) if file_type.is_special() => "-".to_string(),
+                (_, Some(allocated)) if metadata.is_sparse() => format!("~{}", format_size(allocated)),
+                (_, Some(allocated)) => format_size(allocated),
+                (_, None) => String::new(),
+            },
+            Column::Modified => metadata.modified
+                .map(|modified| time_format.format(modified))
+                .unwrap_or_default(),
+            Column::Permissions => permissions::mode_string(metadata.mode),
+            Column::Owner => metadata.owner.map(permissions::user_name).unwrap_or_default(),
+            Column::Git => String::new(),
+        }
+    }
+}
+
+/// Pads or truncates `text` to exactly `width` characters.
+pub fn fit(text: &str, width: usize, right_aligned: bool) -> String {
+    let length = text.chars().count();
+
+    if length > width {
+        let mut truncated = text.chars().take(width.saturating_sub(1)).collect::<String>();
+        if width > 0 {
+            truncated.push('…');
+        }
+        return truncated;
+    }
+
+    match right_aligned {
+        true => format!("{:>width$}", text, width = width),
+        false => format!("{:<width$}", text, width = width),
+    }
+}
@@ -0,0 +1,142 @@
+use std::io;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use crate::external::ExternalCommand;
+
+use super::app::App;
+
+/// Runs an `App` without a real terminal: feeds it synthetic key events
+/// and renders each frame into an in-memory `TestBackend`.
+/// Commands that would suspend the terminal are collected instead of run.
+pub struct Driver {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    suspended_commands: Vec<ExternalCommand>,
+}
+
+impl Driver {
+    pub fn new(app: App, width: u16, height: u16) -> io::Result<Self> {
+        let mut driver = Self {
+            app,
+            terminal: Terminal::new(TestBackend::new(width, height))?,
+            suspended_commands: Vec::new(),
+        };
+        driver.render()?;
+
+        Ok(driver)
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.app.is_running()
+    }
+
+    pub fn send(&mut self, event: Event) -> io::Result<()> {
+        self.app.handle_event(event);
+
+        if let Some(command) = self.app.take_suspended_command() {
+            self.suspended_commands.push(command);
+        }
+
+        self.render()
+    }
+
+    /// Runs the work the event loop does while waiting for input.
+    pub fn tick(&mut self) -> io::Result<()> {
+        self.app.tick();
+        self.render()
+    }
+
+    pub fn take_suspended_commands(&mut self) -> Vec<ExternalCommand> {
+        std::mem::take(&mut self.suspended_commands)
+    }
+
+    pub fn press(&mut self, code: KeyCode) -> io::Result<()> {
+        self.press_with(code, KeyModifiers::NONE)
+    }
+
+    pub fn press_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> io::Result<()> {
+        self.send(Event::Key(KeyEvent::new(code, modifiers)))
+    }
+
+    /// Plays a key sequence such as `jj<Enter>y<Enter>`. Plain characters are sent as-is,
+    /// named keys go between angle brackets and `<lt>` sends a literal `<`.
+    pub fn play(&mut self, keys: &str) -> io::Result<()> {
+        for code in parse_keys(keys)? {
+            if !self.is_running() {
+                break;
+            }
+
+            self.press(code)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn resize(&mut self, width: u16, height: u16) -> io::Result<()> {
+        self.terminal.backend_mut().resize(width, height);
+        self.send(Event::Resize(width, height))
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    pub fn screen(&self) -> String {
+        let buffer = self.buffer();
+        let width = buffer.area.width.max(1) as usize;
+
+        buffer.content
+            .chunks(width)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>().trim_end().to_string())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn render(&mut self) -> io::Result<()> {
+        let app = &mut self.app;
+        self.terminal.draw(|frame| frame.render_widget(app, frame.area()))?;
+
+        Ok(())
+    }
+}
+
+pub fn parse_keys(keys: &str) -> io::Result<Vec<KeyCode>> {
+    let mut codes = Vec::new();
+    let mut chars = keys.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '<' {
+            codes.push(KeyCode::Char(c));
+            continue;
+        }
+
+        let name = chars.by_ref().take_while(|c| *c != '>').collect::<String>();
+        let code = match name.to_lowercase().as_str() {
+            "enter" | "cr" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "bs" | "backspace" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "space" => KeyCode::Char(' '),
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "lt" => KeyCode::Char('<'),
+            _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown key <{}>", name))),
+        };
+
+        codes.push(code);
+    }
+
+    Ok(codes)
+}
@@ -0,0 +1,48 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+
+/// The whole error and every notice, which the one line above the listing cuts short.
+pub struct ErrorWindow;
+
+impl ErrorWindow {
+    pub fn render_error(error: &str, notice: &str, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let mut lines = Vec::new();
+
+        if let Some((category, message)) = error.split_once(": ") {
+            lines.push(Line::from(format!(" {}", category).red().bold()));
+            lines.push(Line::from(format!(" {}", message)));
+        } else if !error.is_empty() {
+            lines.push(Line::from(format!(" {}", error)));
+        }
+
+        if !notice.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(" Notices".yellow().bold()));
+            lines.extend(notice.split(" | ").map(|notice| Line::from(format!(" {}", notice))));
+        }
+
+        let bottom_title = Line::from(vec![
+            " Dismiss ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" Error Details "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false })
+            .left_aligned()
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,206 @@
+use std::{env, time::{Duration, SystemTime}};
+
+use chrono::{format::{Item, StrftimeItems}, DateTime, Local};
+use ratatui::{style::{Color, Style}, text::Span};
+use serde::Deserialize;
+
+/// How search and filter matches stand out wherever they're highlighted.
+pub const MATCH_STYLE: Style = Style::new().bg(Color::Yellow).fg(Color::Black);
+
+const SIZE_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+/// Formats a byte count with binary units, e.g. `1.2 GiB`.
+pub fn format_size(bytes: u64) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+
+    while size >= 1024.0 && unit < SIZE_UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, SIZE_UNITS[unit])
+    } else {
+        format!("{:.1} {}", size, SIZE_UNITS[unit])
+    }
+}
+
+/// Groups the digits of a count by thousands, e.g. `18,992`.
+pub fn format_count(count: u64) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() * 4 / 3);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// A duration to the second, or to the minute past an hour, e.g. `2m 10s`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+
+    match seconds {
+        0..60 => format!("{}s", seconds),
+        60..3600 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+/// A grouped count with its noun, e.g. `1 file` or `1,204 files`.
+pub fn format_quantity(count: u64, singular: &str, plural: &str) -> String {
+    format!("{} {}", format_count(count), if count == 1 { singular } else { plural })
+}
+
+/// Shortens a path to fit `width` characters. Leading directories are abbreviated fish-style
+/// one at a time, outermost first (`/u/s/l/project`), and if that's still too long the start
+/// is cut off with `…`, so the last components always stay visible.
+pub fn shorten_path(path: &str, width: usize) -> String {
+    if path.chars().count() <= width {
+        return path.to_string();
+    }
+
+    let separator = std::path::MAIN_SEPARATOR_STR;
+    let mut components = path.split(separator).map(str::to_string).collect::<Vec<String>>();
+
+    for index in 0..components.len().saturating_sub(1) {
+        // Hidden directories keep their dot, so `.config` becomes `.c`
+        let keep = if components[index].starts_with('.') { 2 } else { 1 };
+        components[index] = components[index].chars().take(keep).collect();
+
+        let shortened = components.join(separator);
+
+        if shortened.chars().count() <= width {
+            return shortened;
+        }
+    }
+
+    let shortened = components.join(separator);
+    let skip = shortened.chars().count() + 1 - width.max(1);
+
+    format!("…{}", shortened.chars().skip(skip).collect::<String>())
+}
+
+/// How timestamps are shown: `"relative"` ("2 hours ago"), a strftime pattern like
+/// `"%d %b %Y"`, or `"locale"` for the date order of the locale in `LC_TIME` or `LANG`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum TimeFormat {
+    Relative,
+    Pattern(String),
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        TimeFormat::Pattern(locale_pattern().to_string())
+    }
+}
+
+impl TryFrom<String> for TimeFormat {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "relative" => Ok(TimeFormat::Relative),
+            "locale" => Ok(TimeFormat::default()),
+            // chrono only reports a bad pattern by failing to display it, so check up front
+            pattern if StrftimeItems::new(pattern).any(|item| item == Item::Error) => {
+                Err(format!("invalid time format \"{}\"", pattern))
+            },
+            pattern => Ok(TimeFormat::Pattern(pattern.to_string())),
+        }
+    }
+}
+
+impl TimeFormat {
+    pub fn format(&self, time: SystemTime) -> String {
+        let time = DateTime::<Local>::from(time);
+
+        match self {
+            TimeFormat::Relative => relative_time(time),
+            TimeFormat::Pattern(pattern) => time.format(pattern).to_string(),
+        }
+    }
+}
+
+// Times in the future, e.g. from a skewed clock, get the locale's absolute format
+fn relative_time(time: DateTime<Local>) -> String {
+    let seconds = Local::now().signed_duration_since(time).num_seconds();
+
+    let (count, unit) = match seconds {
+        ..0 => return time.format(locale_pattern()).to_string(),
+        0..60 => return "just now".to_string(),
+        60..3600 => (seconds / 60, "minute"),
+        3600..86_400 => (seconds / 3600, "hour"),
+        86_400..2_592_000 => (seconds / 86_400, "day"),
+        2_592_000..31_536_000 => (seconds / 2_592_000, "month"),
+        _ => (seconds / 31_536_000, "year"),
+    };
+
+    match count {
+        1 => format!("1 {} ago", unit),
+        count => format!("{} {}s ago", count, unit),
+    }
+}
+
+/// A date and time pattern in the order the locale writes dates, always 16 characters
+/// wide to fit the modified column. Unknown locales get ISO 8601.
+fn locale_pattern() -> &'static str {
+    let locale = ["LC_ALL", "LC_TIME", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+
+    // e.g. `de_AT.UTF-8@euro`
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    let (language, region) = name.split_once('_').unwrap_or((name, ""));
+
+    match (language, region) {
+        (_, "US") => "%m/%d/%Y %H:%M",
+        (_, "CA") => "%Y-%m-%d %H:%M",
+        ("en" | "fr" | "es" | "it" | "pt" | "el", _) => "%d/%m/%Y %H:%M",
+        ("de" | "da" | "fi" | "nb" | "nn" | "no" | "pl" | "cs" | "sk" | "ru" | "uk" | "tr" | "ro" | "hr" | "sl" | "sr" | "bg" | "et" | "lv", _) => "%d.%m.%Y %H:%M",
+        ("nl", _) => "%d-%m-%Y %H:%M",
+        _ => "%Y-%m-%d %H:%M",
+    }
+}
+
+/// Splits `text` into spans, styling every case-insensitive occurrence of `query`.
+pub fn highlight_matches(text: &str, query: &str, style: Style) -> Vec<Span<'static>> {
+    if query.is_empty() {
+        return vec![Span::raw(text.to_string())];
+    }
+
+    // ASCII lowercasing keeps byte offsets identical between `text` and `haystack`
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+
+    let mut spans = Vec::new();
+    let mut last = 0;
+
+    for (start, _) in haystack.match_indices(&needle) {
+        if start < last {
+            continue;
+        }
+
+        if start > last {
+            spans.push(Span::raw(text[last..start].to_string()));
+        }
+
+        let end = start + needle.len();
+        spans.push(Span::styled(text[start..end].to_string(), style));
+        last = end;
+    }
+
+    if last < text.len() {
+        spans.push(Span::raw(text[last..].to_string()));
+    }
+
+    spans
+}
@@ -1,51 +1,323 @@
-use ratatui::{buffer::Buffer, layout::Rect, style::{Style, Stylize}, symbols::border, text::Line, widgets::{Block, Paragraph, Widget}};
+use ratatui::{buffer::Buffer, layout::{Constraint, Layout, Rect}, style::{Style, Stylize}, symbols::border, text::{Line, Span}, widgets::{Block, Clear, Paragraph, Widget}};
 
-pub struct HelpWindow {
-    commands: Vec<Command>,
+use super::app::AppMode;
+
+/// A key binding as shown in the help window and the hint bar. The keys themselves are
+/// handled in `App::handle_key_event`, so a binding changed there has to change here too.
+pub struct Binding {
+    pub action: &'static str,
+    pub keys: &'static str,
 }
 
-struct Command {
-    name: String,
-    keybind: String,
+const fn bind(action: &'static str, keys: &'static str) -> Binding {
+    Binding { action, keys }
 }
 
-impl Command {
-    pub fn get_line(&self) -> Line {
-        Line::from(vec![
-            self.name.clone().into(),
-            self.keybind.clone().blue().into(),
-        ])
+// Every list starts with the bindings worth showing when only a few fit in the hint bar
+
+const FILES: &[Binding] = &[
+    bind("Select", "<Enter>"),
+    bind("Move Into", "<m>"),
+    bind("Move Out", "<->"),
+    bind("Create", "<a>"),
+    bind("Mark", "<Space>"),
+    bind("Search Listing", "</>"),
+    bind("Next, Previous Match", "<n><N>"),
+    bind("Find by Name", "<f>"),
+    bind("Filter Names", "<|>"),
+    bind("Command", "<:>"),
+    bind("Move", "<↓↑>"),
+    bind("Top, Bottom", "<gg><G> <Home><End>"),
+    bind("Home, Root Directory", "<gh><gr>"),
+    bind("Expand, Collapse", "<→←>"),
+    bind("Project Root", "<r>"),
+    bind("Refresh", "<Ctrl-r>"),
+    bind("Flat Listing", "<L>"),
+    bind("Filter Category", "<c>"),
+    bind("Filter Modified Today, Week, Month", "<w>"),
+    bind("Filter Extensions", "<e>"),
+    bind("Sort By, Reverse", "<s><S>"),
+    bind("Toggle Hidden", "<.>"),
+    bind("Toggle Ignored", "<,>"),
+    bind("Empty Trash", "<Z>"),
+    bind("Details Layout", "<D>"),
+    bind("Extended Properties", "<i>"),
+    bind("Recall View", "<V>"),
+    bind("Logical/Physical Path", "<P>"),
+    bind("Open", "<o>"),
+    bind("Edit", "<E>"),
+    bind("View Anyway", "<v>"),
+    bind("Focus Preview", "<p>"),
+    bind("Next, Previous Panel", "<Tab><Shift-Tab>"),
+    bind("Toggle Executable", "<x>"),
+    bind("Windows Attributes", "<W>"),
+    bind("Finder Tags, Quarantine", "<t>"),
+    bind("Toggle Git Diff", "<d>"),
+    bind("Clear Marks", "<Esc>"),
+    bind("Mark All, Invert", "<A><I>"),
+    bind("Mark Matching", "<+>"),
+    bind("Yank, Cut to Register", "<y><X>"),
+    bind("Paste Register", "<b>"),
+    bind("Pick Register", "<\"a-z>"),
+    bind("Jump to Bookmark Slot", "<1-9>"),
+    bind("Bookmarks", "<B>"),
+    bind("Journal", "<J>"),
+    bind("Background Tasks", "<T>"),
+    bind("Retry Failed Batch", "<R>"),
+    bind("Undo History", "<u>"),
+    bind("Find in Contents", "<F>"),
+    bind("Back to Results", "<gs>"),
+    bind("Error Details", "<Enter>"),
+    bind("Quit", "<q>"),
+];
+
+const SELECT: &[Binding] = &[
+    bind("Run", "<Enter>"),
+    bind("Move", "<↓↑>"),
+    bind("Top, Bottom", "<gg><G>"),
+    bind("Back", "<Esc>"),
+];
+
+const PREVIEW: &[Binding] = &[
+    bind("Scroll", "<↓↑>"),
+    bind("Page Down, Up", "<Space><PgUp>"),
+    bind("Search", "</>"),
+    bind("Next, Previous Match", "<n><N>"),
+    bind("Follow File", "<f>"),
+    bind("Pause Following", "<s>"),
+    bind("Top, Bottom", "<g><G>"),
+    bind("Wrap Lines", "<w>"),
+    bind("Line Numbers", "<l>"),
+    bind("Toggle Git Diff", "<d>"),
+    bind("Next, Previous Panel", "<Tab><Shift-Tab>"),
+    bind("Back", "<Esc>"),
+];
+
+const BOOKMARKS: &[Binding] = &[
+    bind("Jump", "<Enter>"),
+    bind("Add Current", "<a>"),
+    bind("Remove", "<d>"),
+    bind("Rename", "<r>"),
+    bind("Relocate", "<R>"),
+    bind("Assign Slot", "<0-9>"),
+    bind("Send Marked: Move, Copy", "<m><c>"),
+    bind("Move", "<↓↑>"),
+    bind("Close", "<Esc>"),
+];
+
+const LIST: &[Binding] = &[
+    bind("Move", "<↓↑>"),
+    bind("Close", "<Esc>"),
+];
+
+const SEARCH_RESULTS: &[Binding] = &[
+    bind("Go to", "<Enter>"),
+    bind("Open", "<o>"),
+    bind("Edit", "<E>"),
+    bind("Move", "<↓↑>"),
+    bind("Close", "<Esc>"),
+];
+
+const UNDO: &[Binding] = &[
+    bind("Undo", "<Enter>"),
+    bind("Move", "<↓↑>"),
+    bind("Close", "<Esc>"),
+];
+
+const TASKS: &[Binding] = &[
+    bind("Stop", "<x>"),
+    bind("Clear Finished", "<c>"),
+    bind("Move", "<↓↑>"),
+    bind("Close", "<Esc>"),
+];
+
+const BATCH_PREVIEW: &[Binding] = &[
+    bind("Run", "<Enter>"),
+    bind("Keep Both", "<b>"),
+    bind("Update", "<u>"),
+    bind("Symlinks", "<l>"),
+    bind("Preserve", "<p>"),
+    bind("Move", "<↓↑>"),
+    bind("Cancel", "<Esc>"),
+];
+
+/// What can follow a key that starts a sequence, shown while it waits for the rest.
+pub const SEQUENCES: &[Binding] = &[
+    bind("Top", "<gg>"),
+    bind("Home", "<gh>"),
+    bind("Root Directory", "<gr>"),
+    bind("Back to Results", "<gs>"),
+];
+
+/// What can follow `g` in the action menu, which only has a top to go to.
+pub const MENU_SEQUENCES: &[Binding] = &[
+    bind("Top", "<gg>"),
+];
+
+const TEXT_INPUT: &[Binding] = &[
+    bind("Confirm", "<Enter>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const CREATE: &[Binding] = &[
+    bind("Create", "<Enter>"),
+    bind("Seed, Enter New Directory", "<Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const PERMISSIONS: &[Binding] = &[
+    bind("Apply", "<Enter>"),
+    bind("Recursive", "<Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const PROPERTIES: &[Binding] = &[
+    bind("Apply", "<Enter>"),
+    bind("Next, Previous Field", "<Tab><Shift-Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const COMPRESS: &[Binding] = &[
+    bind("Start", "<Enter>"),
+    bind("Gzip, Zstd", "<g><z>"),
+    bind("Keep Original", "<Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const DECOMPRESS: &[Binding] = &[
+    bind("Start", "<Enter>"),
+    bind("Keep Original", "<Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const ENCRYPT: &[Binding] = &[
+    bind("Encrypt", "<Enter>"),
+    bind("Passphrase, Recipient", "<Tab>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const FILTER: &[Binding] = &[
+    bind("Keep", "<Enter>"),
+    bind("Clear", "<Esc>"),
+];
+
+const LIST_SEARCH: &[Binding] = &[
+    bind("Keep", "<Enter>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const COLLISION: &[Binding] = &[
+    bind("Keep Both", "<b>"),
+    bind("Back", "<Esc>"),
+];
+
+const ATTRIBUTES: &[Binding] = &[
+    bind("Read-only, Hidden, Archive", "<r><h><a>"),
+    bind("Done", "<Esc>"),
+];
+
+const FINDER_TAGS: &[Binding] = &[
+    bind("Toggle Color", "<1-7>"),
+    bind("Clear Quarantine", "<x>"),
+    bind("Done", "<Esc>"),
+];
+
+const VIEWS: &[Binding] = &[
+    bind("Recall", "<key>"),
+    bind("Cancel", "<Esc>"),
+];
+
+const HELP: &[Binding] = &[
+    bind("About", "<i>"),
+    bind("Close", "<Esc>"),
+];
+
+const SETUP: &[Binding] = &[
+    bind("Change", "<Space>"),
+    bind("Save", "<Enter>"),
+    bind("Move", "<↓↑>"),
+    bind("Keep Defaults", "<Esc>"),
+];
+
+const ERROR_DETAILS: &[Binding] = &[
+    bind("Dismiss", "<Esc>"),
+];
+
+const CLOSE: &[Binding] = &[
+    bind("Close", "<Esc>"),
+];
+
+/// The bindings valid in `mode`.
+pub fn bindings(mode: &AppMode) -> &'static [Binding] {
+    match mode {
+        AppMode::Files => FILES,
+        AppMode::Select => SELECT,
+        AppMode::Preview => PREVIEW,
+        AppMode::Bookmarks => BOOKMARKS,
+        AppMode::Journal | AppMode::Checksums => LIST,
+        AppMode::SearchResults => SEARCH_RESULTS,
+        AppMode::Undo => UNDO,
+        AppMode::Tasks => TASKS,
+        AppMode::BatchPreview => BATCH_PREVIEW,
+        AppMode::Create => CREATE,
+        AppMode::Permissions => PERMISSIONS,
+        AppMode::Properties => PROPERTIES,
+        AppMode::Compress => COMPRESS,
+        AppMode::Decompress => DECOMPRESS,
+        AppMode::Encrypt => ENCRYPT,
+        AppMode::Filter => FILTER,
+        AppMode::ListSearch => LIST_SEARCH,
+        AppMode::CreateCollision | AppMode::MoveCollision => COLLISION,
+        AppMode::Attributes => ATTRIBUTES,
+        AppMode::FinderTags => FINDER_TAGS,
+        AppMode::Views => VIEWS,
+        AppMode::Help => HELP,
+        AppMode::About => CLOSE,
+        AppMode::ErrorDetails => ERROR_DETAILS,
+        AppMode::Setup => SETUP,
+        AppMode::Rename | AppMode::Delete | AppMode::Decrypt | AppMode::Discard | AppMode::EmptyTrash | AppMode::RenameBookmark | AppMode::RelocateBookmark | AppMode::Command
+            | AppMode::Search | AppMode::PreviewSearch | AppMode::RunArgs | AppMode::ExtensionFilter
+            | AppMode::MarkGlob | AppMode::BatchDestination | AppMode::LinkTarget => TEXT_INPUT,
+        AppMode::Exit => &[],
     }
 }
 
-impl Default for HelpWindow {
-    fn default() -> Self {
-        Self {
-            commands: vec![
-                Command { name: "Move".to_string(), keybind: "<↓↑>".to_string() },
-                Command { name: "Move Into".to_string(), keybind: "<m>".to_string() },
-                Command { name: "Move Out".to_string(), keybind: "<->".to_string() },
-                Command { name: "Select".to_string(), keybind: "<Enter>".to_string() },
-                Command { name: "Move Bookmarked".to_string(), keybind: "<b>".to_string() },
-                Command { name: "Quit".to_string(), keybind: "<q>".to_string() },
-                Command { name: "Create".to_string(), keybind: "<a>".to_string() },
-            ],
+/// The bindings as `Action <key>` spans, for as many as fit in `width` columns.
+pub fn hint_spans(bindings: &[Binding], width: usize) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut used = 0;
+
+    for binding in bindings {
+        let length = binding.action.chars().count() + binding.keys.chars().count() + 3;
+
+        if used + length > width {
+            break;
         }
+
+        spans.push(format!(" {} ", binding.action).into());
+        spans.push(binding.keys.blue());
+        spans.push(" ".into());
+        used += length;
     }
+
+    spans
 }
 
-impl HelpWindow {
+/// Lists every binding of the mode help was opened from.
+pub struct HelpWindow<'a> {
+    pub bindings: &'a [Binding],
+}
+
+impl HelpWindow<'_> {
     pub fn render_help(&self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
         buf.set_style(area, Style::default());
 
-        let commands = self.commands
-            .iter()
-            .map(|c| c.get_line())
-            .collect::<Vec<Line>>();
-
         let bottom_title = Line::from(vec![
+            " About ".into(),
+            "<i>".blue(),
             " Close ".into(),
-            "<Esc>".blue().into(),
+            "<Esc>".blue(),
         ]);
 
         let block = Block::bordered()
@@ -53,11 +325,27 @@ impl HelpWindow {
             .title_bottom(bottom_title.right_aligned())
             .border_set(border::THICK);
 
-        Paragraph::new(commands)
-            .block(block)
-            .bold()
-            .centered()
-            .render(area, buf);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        // Spread over as many columns as it takes to show every binding
+        let rows = (inner.height as usize).max(1);
+        let columns = self.bindings.chunks(rows).collect::<Vec<&[Binding]>>();
+        let areas = Layout::horizontal(vec![Constraint::Fill(1); columns.len()]).split(inner);
+
+        for (bindings, area) in columns.into_iter().zip(areas.iter()) {
+            let commands = bindings
+                .iter()
+                .map(|binding| Line::from(vec![
+                    format!("{} ", binding.action).into(),
+                    binding.keys.blue(),
+                ]))
+                .collect::<Vec<Line>>();
+
+            Paragraph::new(commands)
+                .bold()
+                .centered()
+                .render(*area, buf);
+        }
     }
 }
-
@@ -0,0 +1,68 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::journal::JournalEntry;
+
+use super::app::SELECTED_STYLE;
+
+#[derive(Default)]
+pub struct JournalWindow {
+    pub entries: Vec<JournalEntry>,
+    pub state: ListState,
+}
+
+impl JournalWindow {
+    pub fn render_journal(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let bottom_title = Line::from(vec![
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" Journal "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let mut line = Line::from(vec![
+                    format!(" {} ", entry.time).dark_gray(),
+                    format!("{} ", entry.user).dark_gray(),
+                    format!("{} ", entry.operation).bold(),
+                    entry.source.clone().into(),
+                ]);
+
+                if !entry.destination.is_empty() {
+                    line.spans.push(" → ".blue());
+                    line.spans.push(entry.destination.clone().into());
+                }
+
+                if entry.succeeded() {
+                    line.spans.push(" ok".green());
+                } else {
+                    line.spans.push(format!(" {}", entry.result).red());
+                }
+
+                ListItem::from(line)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
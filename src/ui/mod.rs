@@ -1,3 +1,20 @@
 pub mod app;
 pub mod user_input;
 pub mod help;
+pub mod driver;
+pub mod bookmark_window;
+pub mod search_window;
+pub mod journal_window;
+pub mod undo_window;
+pub mod format;
+pub mod preview_pane;
+pub mod checksum_window;
+pub mod batch_window;
+pub mod columns;
+pub mod task_window;
+pub mod about_window;
+pub mod error_window;
+pub mod setup_window;
+pub mod properties_window;
+pub mod prompt;
+pub mod sequence;
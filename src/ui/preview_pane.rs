@@ -0,0 +1,191 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+
+use super::format::{highlight_matches, MATCH_STYLE};
+
+/// Scroll position, display toggles and search state of the preview pane.
+/// Scrolling is by source line, so search matches land exactly at the top.
+#[derive(Default)]
+pub struct PreviewPane {
+    pub wrap: bool,
+    pub line_numbers: bool,
+    /// Shown next to the title, e.g. while following a file
+    pub status: Option<&'static str>,
+    /// Keeps the last line in view as content grows
+    pub pinned: bool,
+    /// Colors added and removed lines of a unified diff
+    pub diff: bool,
+    scroll: usize,
+    query: String,
+    path: Option<PathBuf>,
+    lines: Vec<String>,
+    page_height: usize,
+}
+
+impl PreviewPane {
+    /// Replaces the content, resetting the scroll position when a different file is shown.
+    pub fn set_content(&mut self, path: Option<&Path>, lines: Vec<String>) {
+        if self.path.as_deref() != path {
+            self.path = path.map(Path::to_path_buf);
+            self.scroll = 0;
+        }
+
+        self.lines = lines;
+        self.scroll = self.scroll.min(self.lines.len().saturating_sub(1));
+
+        if self.pinned {
+            self.scroll_to_bottom();
+        }
+    }
+
+    pub fn scroll_down(&mut self, amount: usize) {
+        self.scroll = (self.scroll + amount).min(self.lines.len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self, amount: usize) {
+        self.scroll = self.scroll.saturating_sub(amount);
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_down(self.page_height.max(1));
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_up(self.page_height.max(1));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.lines.len().saturating_sub(self.page_height.max(1));
+    }
+
+    /// Sets the search query and jumps to its first match from the current position.
+    pub fn search(&mut self, query: String) -> bool {
+        self.query = query;
+        self.find_from(self.scroll, true)
+    }
+
+    pub fn next_match(&mut self) -> bool {
+        self.find_from(self.scroll + 1, true)
+    }
+
+    pub fn previous_match(&mut self) -> bool {
+        match self.scroll.checked_sub(1) {
+            Some(start) => self.find_from(start, false),
+            None => self.find_from(self.lines.len().saturating_sub(1), false),
+        }
+    }
+
+    // Searches forwards or backwards from `start`, wrapping around the ends
+    fn find_from(&mut self, start: usize, forward: bool) -> bool {
+        if self.query.is_empty() || self.lines.is_empty() {
+            return false;
+        }
+
+        let query = self.query.to_ascii_lowercase();
+        let count = self.lines.len();
+
+        let found = (0..count)
+            .map(|offset| match forward {
+                true => (start + offset) % count,
+                false => (start + count - offset % count) % count,
+            })
+            .find(|index| self.lines[*index].to_ascii_lowercase().contains(&query));
+
+        if let Some(index) = found {
+            self.scroll = index;
+        }
+
+        found.is_some()
+    }
+
+    pub fn render(&mut self, focused: bool, area: Rect, buf: &mut Buffer) {
+        self.page_height = area.height.saturating_sub(2) as usize;
+
+        let status = self.status.or(if self.diff { Some("diff") } else { None });
+
+        let title = match status {
+            Some(status) => Line::from(vec![" Preview ".into(), format!("[{}] ", status).yellow()]),
+            None => Line::from(" Preview "),
+        };
+
+        let mut block = Block::bordered()
+            .title(title)
+            .border_set(border::THICK);
+
+        if focused {
+            let bottom_title = Line::from(vec![
+                " Wrap ".into(),
+                "<w>".blue(),
+                " Numbers ".into(),
+                "<l>".blue(),
+                " Search ".into(),
+                "</>".blue(),
+                " Follow ".into(),
+                "<f> ".blue(),
+            ]);
+
+            block = block
+                .title_bottom(bottom_title.right_aligned())
+                .border_style(Style::new().yellow());
+        }
+
+        let number_width = self.lines.len().to_string().len();
+
+        let text = self.lines
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(self.page_height)
+            .map(|(index, line)| {
+                let mut spans = vec![Span::raw(" ")];
+
+                if self.line_numbers {
+                    spans.push(format!("{:>width$} ", index + 1, width = number_width).dark_gray());
+                }
+
+                spans.extend(highlight_matches(line, &self.query, MATCH_STYLE));
+
+                match self.diff {
+                    true => Line::from(spans).style(diff_style(line)),
+                    false => Line::from(spans),
+                }
+            })
+            .collect::<Vec<Line>>();
+
+        let mut paragraph = Paragraph::new(text)
+            .block(block)
+            .left_aligned();
+
+        if self.wrap {
+            paragraph = paragraph.wrap(Wrap { trim: false });
+        }
+
+        paragraph.render(area, buf);
+    }
+}
+
+fn diff_style(line: &str) -> Style {
+    if line.starts_with("+++") || line.starts_with("---") {
+        Style::new().bold()
+    } else if line.starts_with('+') {
+        Style::new().green()
+    } else if line.starts_with('-') {
+        Style::new().red()
+    } else if line.starts_with("@@") {
+        Style::new().cyan()
+    } else {
+        Style::new()
+    }
+}
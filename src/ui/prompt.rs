@@ -0,0 +1,80 @@
+use ratatui::style::Color;
+
+use super::app::AppMode;
+
+/// What an input prompt is for. Each kind has its own title, accent color and icon, so a
+/// delete confirmation can't be mistaken for a rename at a glance.
+#[derive(Clone, Copy, PartialEq)]
+pub enum PromptKind {
+    Create,
+    Rename,
+    Delete,
+    Conflict,
+    Destination,
+    Search,
+    Filter,
+    Command,
+    Input,
+}
+
+impl PromptKind {
+    /// The prompt `mode` shows in the input line, or None when it has none.
+    pub fn of(mode: AppMode) -> Option<PromptKind> {
+        let kind = match mode {
+            AppMode::Create => PromptKind::Create,
+            AppMode::Rename | AppMode::RenameBookmark | AppMode::RelocateBookmark | AppMode::LinkTarget => PromptKind::Rename,
+            AppMode::Delete | AppMode::Discard | AppMode::EmptyTrash => PromptKind::Delete,
+            AppMode::CreateCollision | AppMode::MoveCollision => PromptKind::Conflict,
+            AppMode::BatchDestination => PromptKind::Destination,
+            AppMode::Search | AppMode::PreviewSearch | AppMode::ListSearch => PromptKind::Search,
+            AppMode::Filter | AppMode::ExtensionFilter | AppMode::MarkGlob => PromptKind::Filter,
+            AppMode::Command | AppMode::Views => PromptKind::Command,
+            AppMode::Permissions | AppMode::RunArgs | AppMode::Compress | AppMode::Decompress | AppMode::Encrypt
+                | AppMode::Decrypt | AppMode::Attributes | AppMode::FinderTags => PromptKind::Input,
+            _ => return None,
+        };
+
+        Some(kind)
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            PromptKind::Create => "Create",
+            PromptKind::Rename => "Rename",
+            PromptKind::Delete => "Delete",
+            PromptKind::Conflict => "Conflict",
+            PromptKind::Destination => "Destination",
+            PromptKind::Search => "Search",
+            PromptKind::Filter => "Filter",
+            PromptKind::Command => "Command",
+            PromptKind::Input => "Input",
+        }
+    }
+
+    pub fn icon(&self) -> &'static str {
+        match self {
+            PromptKind::Create => "+",
+            PromptKind::Rename => "✎",
+            PromptKind::Delete => "✗",
+            PromptKind::Conflict => "!",
+            PromptKind::Destination => "→",
+            PromptKind::Search => "/",
+            PromptKind::Filter => "|",
+            PromptKind::Command => ":",
+            PromptKind::Input => "›",
+        }
+    }
+
+    /// The color of the border, title and prompt text.
+    pub fn accent(&self) -> Color {
+        match self {
+            PromptKind::Create => Color::Green,
+            PromptKind::Rename => Color::Yellow,
+            PromptKind::Delete => Color::Red,
+            PromptKind::Conflict => Color::LightRed,
+            PromptKind::Destination | PromptKind::Search => Color::Cyan,
+            PromptKind::Filter => Color::Magenta,
+            PromptKind::Command | PromptKind::Input => Color::Blue,
+        }
+    }
+}
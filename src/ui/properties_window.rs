@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::{my_errors::MyError, properties::{self, Property}};
+
+use super::{app::SELECTED_STYLE, user_input::UserInput};
+
+/// A property being edited: the value it had when the dialog opened and what's typed now.
+pub struct PropertyField {
+    pub property: Property,
+    pub original: String,
+    pub input: UserInput,
+    pub editable: bool,
+}
+
+/// Mode, ownership, times and extended attributes of one entry, edited together and
+/// applied at once.
+#[derive(Default)]
+pub struct PropertiesWindow {
+    pub path: PathBuf,
+    pub fields: Vec<PropertyField>,
+    pub state: ListState,
+}
+
+impl PropertiesWindow {
+    pub fn open(path: &Path) -> Result<Self, MyError> {
+        let fields = properties::current(path)?
+            .into_iter()
+            .map(|current| PropertyField {
+                property: current.property,
+                input: UserInput::new(current.value.clone()),
+                original: current.value,
+                editable: current.editable,
+            })
+            .collect::<Vec<PropertyField>>();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            fields,
+            state: ListState::default().with_selected(Some(0)),
+        })
+    }
+
+    /// Moves to the next editable field, or the previous one, wrapping around.
+    pub fn focus_next(&mut self, forward: bool) {
+        let count = self.fields.len();
+        let Some(mut index) = self.state.selected() else { return };
+
+        for _ in 0..count {
+            index = if forward { (index + 1) % count } else { (index + count - 1) % count };
+
+            if self.fields[index].editable {
+                self.state.select(Some(index));
+                return;
+            }
+        }
+    }
+
+    pub fn focused_input(&mut self) -> Option<&mut UserInput> {
+        let index = self.state.selected()?;
+
+        self.fields.get_mut(index).filter(|field| field.editable).map(|field| &mut field.input)
+    }
+
+    /// Takes the typed value of `property` as its value now, once it has been set.
+    pub fn mark_applied(&mut self, property: &Property) {
+        for field in self.fields.iter_mut().filter(|field| field.property == *property) {
+            field.original = field.input.get_input_value();
+        }
+    }
+
+    /// The fields whose value was changed, with the value to apply.
+    pub fn changes(&self) -> Vec<(Property, String)> {
+        self.fields
+            .iter()
+            .filter(|field| field.editable && field.input.get_input_value() != field.original)
+            .map(|field| (field.property.clone(), field.input.get_input_value()))
+            .collect()
+    }
+
+    pub fn render_properties(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+
+        let bottom_title = Line::from(vec![
+            " Next ".into(),
+            "<Tab>".blue(),
+            " Apply ".into(),
+            "<Enter>".blue(),
+            " Cancel ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(format!(" Properties of {} ", name)))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let width = self.fields.iter().map(|field| field.property.label().chars().count()).max().unwrap_or_default();
+        let selected = self.state.selected();
+
+        let items = self.fields
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                let mut value = field.input.get_input_value();
+
+                if selected == Some(index) {
+                    value.push('_');
+                }
+
+                let value = match (field.editable, field.input.get_input_value() != field.original) {
+                    (false, _) => value.dark_gray(),
+                    (true, true) => value.yellow(),
+                    (true, false) => value.into(),
+                };
+
+                ListItem::from(Line::from(vec![
+                    format!(" {:<width$}  ", field.property.label(), width = width).bold(),
+                    value,
+                ]))
+            })
+            .collect::<Vec<ListItem>>();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::search::SearchKind;
+
+use super::{app::SELECTED_STYLE, format::{highlight_matches, MATCH_STYLE}};
+
+pub struct SearchWindow {
+    pub kind: SearchKind,
+    pub root: PathBuf,
+    pub pattern: String,
+    pub results: Vec<PathBuf>,
+    pub state: ListState,
+}
+
+impl Default for SearchWindow {
+    fn default() -> Self {
+        Self {
+            kind: SearchKind::Name,
+            root: PathBuf::default(),
+            pattern: String::new(),
+            results: Vec::new(),
+            state: ListState::default(),
+        }
+    }
+}
+
+impl SearchWindow {
+    pub fn selected(&self) -> Option<&PathBuf> {
+        self.state.selected().and_then(|index| self.results.get(index))
+    }
+
+    pub fn render_results(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let title = Line::from(format!(" Results ({}) ", self.results.len()));
+
+        let bottom_title = Line::from(vec![
+            " Go to ".into(),
+            "<Enter>".blue(),
+            " Open, Edit ".into(),
+            "<o><E>".blue(),
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let items: Vec<ListItem> = self
+            .results
+            .iter()
+            .map(|path| {
+                let relative = path.strip_prefix(&self.root).unwrap_or(path).display().to_string();
+
+                // Name matches are highlighted; content matches aren't visible in the path
+                let mut line = Line::from(" ");
+                match self.kind {
+                    SearchKind::Name => line.spans.extend(highlight_matches(&relative, &self.pattern, MATCH_STYLE)),
+                    SearchKind::Content => line.spans.push(relative.into()),
+                }
+
+                ListItem::from(line)
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
@@ -0,0 +1,85 @@
+use std::time::{Duration, Instant};
+
+/// How long the first keys of a sequence wait for the rest before they're dropped.
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// What a multi-key binding does.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Chord {
+    Top,
+    Home,
+    Root,
+    SearchResults,
+}
+
+// A key that starts one of these can't be bound on its own, except as what runs once the
+// sequence times out
+const SEQUENCES: &[(&str, Chord)] = &[
+    ("gg", Chord::Top),
+    ("gh", Chord::Home),
+    ("gr", Chord::Root),
+    ("gs", Chord::SearchResults),
+];
+
+pub enum SequenceStep {
+    /// The keys so far start a sequence, so the key is held back
+    Pending,
+    Complete(Chord),
+    /// No sequence starts with this key, which is handled as usual
+    Unmatched,
+}
+
+/// Matches multi-key bindings like `gg` as the keys arrive. Keys that start a sequence are
+/// held until it's complete, another key breaks it off or `SEQUENCE_TIMEOUT` passes.
+#[derive(Default)]
+pub struct SequenceMatcher {
+    pending: String,
+    since: Option<Instant>,
+}
+
+impl SequenceMatcher {
+    pub fn feed(&mut self, key: char) -> SequenceStep {
+        let mut typed = std::mem::take(&mut self.pending);
+        typed.push(key);
+
+        if let Some((_, chord)) = SEQUENCES.iter().find(|(keys, _)| *keys == typed) {
+            self.since = None;
+            return SequenceStep::Complete(*chord);
+        }
+
+        if SEQUENCES.iter().any(|(keys, _)| keys.starts_with(&typed)) {
+            self.pending = typed;
+            self.since = Some(Instant::now());
+            return SequenceStep::Pending;
+        }
+
+        // A broken-off sequence is dropped, but its last key may start a new one
+        self.since = None;
+
+        match typed.chars().count() > 1 {
+            true => self.feed(key),
+            false => SequenceStep::Unmatched,
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// The held keys once they've waited too long, to be handled as typed on their own.
+    pub fn expire(&mut self) -> Option<String> {
+        let since = self.since?;
+
+        if since.elapsed() < SEQUENCE_TIMEOUT {
+            return None;
+        }
+
+        self.since = None;
+        Some(std::mem::take(&mut self.pending))
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+        self.since = None;
+    }
+}
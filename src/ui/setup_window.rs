@@ -0,0 +1,120 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::config::MainLayout;
+
+use super::app::SELECTED_STYLE;
+
+/// Written instead of the answers when the setup is left with `<Esc>`, so it isn't asked again
+pub const KEPT_DEFAULTS: &str = "# Written by the first-run setup, which kept every default; the README describes the settings\n";
+
+/// The questions asked on the first run, before there's a config file, and the answers
+/// picked so far. Saving writes them as the initial `config.toml`.
+pub struct SetupWindow {
+    pub layout: MainLayout,
+    pub details: bool,
+    pub show_hidden: bool,
+    pub zoxide: bool,
+    pub state: ListState,
+}
+
+impl Default for SetupWindow {
+    fn default() -> Self {
+        Self {
+            layout: MainLayout::Standard,
+            details: false,
+            show_hidden: true,
+            zoxide: false,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+}
+
+impl SetupWindow {
+    /// Switches the selected question to its other answer.
+    pub fn toggle_selected(&mut self) {
+        match self.state.selected() {
+            Some(0) => {
+                self.layout = match self.layout {
+                    MainLayout::Standard => MainLayout::Miller,
+                    MainLayout::Miller => MainLayout::Standard,
+                };
+            },
+            Some(1) => self.details = !self.details,
+            Some(2) => self.show_hidden = !self.show_hidden,
+            Some(3) => self.zoxide = !self.zoxide,
+            _ => {},
+        }
+    }
+
+    /// The answers as the contents of a new config file.
+    pub fn config_toml(&self) -> String {
+        let layout = match self.layout {
+            MainLayout::Standard => "standard",
+            MainLayout::Miller => "miller",
+        };
+
+        format!(
+            "# Written by the first-run setup; the README describes every other setting\n\n\
+             [listing]\nlayout = \"{}\"\ndetails = {}\nshow_hidden = {}\n\n\
+             [zoxide]\nenabled = {}\n",
+            layout, self.details, self.show_hidden, self.zoxide,
+        )
+    }
+
+    pub fn render_setup(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let bottom_title = Line::from(vec![
+            " Change ".into(),
+            "<Space>".blue(),
+            " Save ".into(),
+            "<Enter>".blue(),
+            " Keep Defaults ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" Welcome to dirman: a few settings to start with "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let layout = match self.layout {
+            MainLayout::Standard => "file list, properties and preview",
+            MainLayout::Miller => "Miller columns with the parent directory",
+        };
+
+        let answers = [
+            ("Layout", layout),
+            ("Columns for size, date and permissions", yes_no(self.details)),
+            ("Show hidden files", yes_no(self.show_hidden)),
+            ("Resolve :cd through zoxide", yes_no(self.zoxide)),
+        ];
+
+        let items = answers
+            .into_iter()
+            .map(|(question, answer)| ListItem::from(Line::from(vec![
+                format!(" {}: ", question).into(),
+                answer.yellow(),
+            ])))
+            .collect::<Vec<ListItem>>();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
+
+fn yes_no(answer: bool) -> &'static str {
+    if answer { "yes" } else { "no" }
+}
@@ -0,0 +1,127 @@
+use std::{collections::VecDeque, path::Path};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::background::{FinishedTask, Progress};
+
+use super::{app::SELECTED_STYLE, format::{format_count, format_duration, format_size}};
+
+/// A running task as the panel sees it: operation, source and progress.
+pub type RunningTask<'a> = (&'a str, &'a Path, &'a Progress);
+
+#[derive(Default)]
+pub struct TaskWindow {
+    pub state: ListState,
+}
+
+impl TaskWindow {
+    pub fn render_tasks(&mut self, running: &[RunningTask], finished: &VecDeque<FinishedTask>, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let failed = finished.iter().filter(|task| task.error.is_some()).count();
+
+        let mut title = Line::from(format!(" Tasks: {} running ", running.len()));
+        if failed > 0 {
+            title.spans.push(format!("{} failed ", failed).red());
+        }
+
+        let bottom_title = Line::from(vec![
+            " Stop ".into(),
+            "<x> ".blue(),
+            " Clear finished ".into(),
+            "<c> ".blue(),
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(title)
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        let running_items = running.iter().map(|(operation, source, progress)| {
+            let status = match progress.percent() {
+                Some(percent) => format!(" {:>3}% ", percent),
+                None => " ...  ".to_string(),
+            };
+
+            let mut line = Line::from(vec![
+                status.yellow(),
+                format!("{} ", operation).bold(),
+                file_name(source).into(),
+            ]);
+
+            if progress.is_cancelled() {
+                line.spans.push("  stopping…".yellow());
+            }
+
+            if progress.counts_items() {
+                line.spans.push(format!(
+                    "  {}/{} entries at {}/s",
+                    format_count(progress.done()),
+                    format_count(progress.total()),
+                    format_count(progress.speed()),
+                ).dark_gray());
+            } else if progress.done() > 0 {
+                line.spans.push(format!(
+                    "  {} at {}/s",
+                    format_size(progress.done()),
+                    format_size(progress.speed()),
+                ).dark_gray());
+            }
+
+            line.spans.push(format!("  {}", format_duration(progress.elapsed())).dark_gray());
+
+            if let Some(remaining) = progress.remaining() {
+                line.spans.push(format!(", {} left", format_duration(remaining)).dark_gray());
+            }
+
+            ListItem::from(line)
+        });
+
+        // Most recent first
+        let finished_items = finished.iter().rev().map(|task| {
+            let status = match &task.error {
+                None => " done ".green(),
+                Some(_) => " fail ".red(),
+            };
+
+            let mut line = Line::from(vec![
+                status,
+                format!("{} ", task.operation).bold(),
+                file_name(&task.source).into(),
+            ]);
+
+            if task.bytes > 0 {
+                line.spans.push(format!("  {}", format_size(task.bytes)).dark_gray());
+            }
+
+            line.spans.push(format!("  {}", format_duration(task.elapsed)).dark_gray());
+
+            if let Some(error) = &task.error {
+                line.spans.push(format!("  {}", error).red());
+            }
+
+            ListItem::from(line)
+        });
+
+        let list = List::new(running_items.chain(finished_items).collect::<Vec<ListItem>>())
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().unwrap_or_default().to_string_lossy().to_string()
+}
@@ -0,0 +1,55 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, HighlightSpacing, List, ListItem, ListState, StatefulWidget, Widget},
+};
+
+use crate::undo::UndoHistory;
+
+use super::app::SELECTED_STYLE;
+
+#[derive(Default)]
+pub struct UndoWindow {
+    pub state: ListState,
+}
+
+impl UndoWindow {
+    pub fn render_history(&mut self, history: &UndoHistory, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+
+        let bottom_title = Line::from(vec![
+            " Undo ".into(),
+            "<Enter>".blue(),
+            " Close ".into(),
+            "<Esc> ".blue(),
+        ]);
+
+        let block = Block::bordered()
+            .title(Line::from(" Undo History "))
+            .title_bottom(bottom_title.right_aligned())
+            .border_set(border::THICK);
+
+        // Operations whose inverse is no longer valid are dimmed with the reason
+        let items: Vec<ListItem> = history
+            .operations()
+            .map(|operation| match operation.check() {
+                Ok(_) => ListItem::from(format!(" {}", operation.description())),
+                Err(e) => ListItem::from(Line::from(vec![
+                    format!(" {} ", operation.description()).dark_gray(),
+                    format!("({})", e).red(),
+                ])),
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(block)
+            .highlight_style(SELECTED_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(list, area, buf, &mut self.state);
+    }
+}
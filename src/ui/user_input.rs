@@ -1,17 +1,9 @@
+#[derive(Default)]
 pub struct UserInput {
     input_value: String,
     input_index: usize,
 }
 
-impl Default for UserInput {
-    fn default() -> Self {
-        Self {
-            input_value: String::default(),
-            input_index: 0,
-        }
-    }
-}
-
 impl UserInput {
     pub fn get_input_value(&self) -> String {
         self.input_value.clone()
@@ -0,0 +1,106 @@
+use std::{fs, path::PathBuf};
+
+use crate::{directory, my_errors::MyError};
+
+/// A completed operation that can be reverted as long as the file system
+/// still looks the way the operation left it.
+pub enum UndoOperation {
+    Rename { from: PathBuf, to: PathBuf },
+    Move { from: PathBuf, to: PathBuf },
+    Create { path: PathBuf },
+    Retarget { link: PathBuf, from: PathBuf, to: PathBuf },
+}
+
+#[derive(Default)]
+pub struct UndoHistory {
+    operations: Vec<UndoOperation>,
+}
+
+impl UndoOperation {
+    pub fn description(&self) -> String {
+        match self {
+            UndoOperation::Rename { from, to } => format!("Rename {} → {}", from.display(), to.display()),
+            UndoOperation::Move { from, to } => format!("Move {} → {}", from.display(), to.display()),
+            UndoOperation::Create { path } => format!("Create {}", path.display()),
+            UndoOperation::Retarget { link, from, to } => format!("Retarget {} from {} → {}", link.display(), from.display(), to.display()),
+        }
+    }
+
+    pub fn check(&self) -> Result<(), MyError> {
+        match self {
+            UndoOperation::Rename { from, to } | UndoOperation::Move { from, to } => {
+                if !to.exists() {
+                    return Err(MyError::NotFound(to.clone()));
+                }
+
+                if from.exists() {
+                    return Err(MyError::AlreadyExists(from.clone()));
+                }
+            },
+            UndoOperation::Create { path } => {
+                let metadata = fs::metadata(path).map_err(|e| MyError::io(e, path))?;
+
+                let untouched = if metadata.is_dir() {
+                    fs::read_dir(path).map(|mut entries| entries.next().is_none()).unwrap_or(false)
+                } else {
+                    metadata.len() == 0
+                };
+
+                if !untouched {
+                    return Err(MyError::FileError(format!("{} was modified since it was created", path.display())));
+                }
+            },
+            UndoOperation::Retarget { link, to, .. } => {
+                let target = fs::read_link(link).map_err(|e| MyError::io(e, link))?;
+
+                if target != *to {
+                    return Err(MyError::FileError(format!("{} was retargeted again since", link.display())));
+                }
+            },
+        }
+
+        Ok(())
+    }
+
+    pub fn undo(&self) -> Result<(), MyError> {
+        self.check()?;
+
+        match self {
+            UndoOperation::Rename { from, to } | UndoOperation::Move { from, to } => fs::rename(to, from).map_err(|e| MyError::io(e, to)),
+            UndoOperation::Create { path } if path.is_dir() => fs::remove_dir(path).map_err(|e| MyError::io(e, path)),
+            UndoOperation::Create { path } => fs::remove_file(path).map_err(|e| MyError::io(e, path)),
+            UndoOperation::Retarget { link, from, .. } => directory::retarget_symlink(link, from),
+        }
+    }
+}
+
+impl UndoHistory {
+    pub fn push(&mut self, operation: UndoOperation) {
+        self.operations.push(operation);
+    }
+
+    /// Returns the recorded operations, newest first.
+    pub fn operations(&self) -> impl Iterator<Item = &UndoOperation> {
+        self.operations.iter().rev()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&UndoOperation> {
+        self.operations.iter().rev().nth(index)
+    }
+
+    /// Undoes the operation at `index` (newest first) and drops it from the history.
+    pub fn undo(&mut self, index: usize) -> Result<(), MyError> {
+        let Some(position) = self.operations.len().checked_sub(index + 1) else {
+            return Err(MyError::FileError("Nothing to undo".to_string()));
+        };
+
+        self.operations[position].undo()?;
+        self.operations.remove(position);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::my_errors::MyError;
+
+/// The extended attributes of `path` by name, with their value when it's text. Binary
+/// values, like ACLs or SELinux labels, are `None` since they can't be edited as text.
+#[cfg(unix)]
+pub fn list(path: &Path) -> Vec<(String, Option<String>)> {
+    let Ok(names) = xattr::list_deref(path) else { return Vec::new() };
+
+    let mut attributes = names
+        .map(|name| {
+            let value = xattr::get_deref(path, &name)
+                .ok()
+                .flatten()
+                .and_then(|value| String::from_utf8(value).ok())
+                .filter(|value| !value.contains(char::is_control));
+
+            (name.to_string_lossy().to_string(), value)
+        })
+        .collect::<Vec<(String, Option<String>)>>();
+
+    attributes.sort();
+    attributes
+}
+
+#[cfg(not(unix))]
+pub fn list(_path: &Path) -> Vec<(String, Option<String>)> {
+    Vec::new()
+}
+
+/// Sets an attribute, creating it if needed. On Linux, names need a namespace like `user.`.
+#[cfg(unix)]
+pub fn set(path: &Path, name: &str, value: &str) -> Result<(), MyError> {
+    xattr::set_deref(path, name, value.as_bytes()).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(unix))]
+pub fn set(_path: &Path, _name: &str, _value: &str) -> Result<(), MyError> {
+    Err(MyError::FileError("Extended attributes are only supported on Unix".to_string()))
+}
+
+#[cfg(unix)]
+pub fn remove(path: &Path, name: &str) -> Result<(), MyError> {
+    xattr::remove_deref(path, name).map_err(|e| MyError::io(e, path))
+}
+
+#[cfg(not(unix))]
+pub fn remove(_path: &Path, _name: &str) -> Result<(), MyError> {
+    Err(MyError::FileError("Extended attributes are only supported on Unix".to_string()))
+}
@@ -0,0 +1,37 @@
+use std::{path::{Path, PathBuf}, process::Command};
+
+use crate::my_errors::MyError;
+
+pub fn query(terms: &str, exclude: &Path) -> Result<PathBuf, MyError> {
+    let output = Command::new("zoxide")
+        .arg("query")
+        .arg("--exclude")
+        .arg(exclude)
+        .arg("--")
+        .args(terms.split_whitespace())
+        .output()
+        .map_err(|e| MyError::command("zoxide", e))?;
+
+    if !output.status.success() {
+        return Err(MyError::CommandError(format!("No zoxide match for {}", terms)));
+    }
+
+    let path = String::from_utf8_lossy(&output.stdout).trim_end().to_string();
+
+    Ok(PathBuf::from(path))
+}
+
+pub fn add(path: &Path) -> Result<(), MyError> {
+    let status = Command::new("zoxide")
+        .arg("add")
+        .arg("--")
+        .arg(path)
+        .status()
+        .map_err(|e| MyError::command("zoxide", e))?;
+
+    if !status.success() {
+        return Err(MyError::CommandError(format!("Couldn't add {} to zoxide", path.display())));
+    }
+
+    Ok(())
+}